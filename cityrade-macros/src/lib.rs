@@ -1,9 +1,11 @@
 use cityrade_types::resources::ResourceType;
 use proc_macro::TokenStream;
 use quote::{quote, format_ident};
-use syn::{parse_macro_input, DeriveInput, Ident, Data, Fields, parse_quote, Path};
+use syn::{parse_macro_input, DeriveInput};
 use std::collections::HashMap;
 
+// Конфигурации заполняются при разборе атрибутов генератора мира
+#[allow(dead_code)]
 #[derive(Debug, Clone)]
 struct ResourceConfig {
     density: f32,
@@ -12,6 +14,7 @@ struct ResourceConfig {
     biomes: Option<Vec<String>>,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Clone)]
 struct StructureConfig {
     count: String,
@@ -68,18 +71,18 @@ pub fn derive_plugin(input: TokenStream) -> TokenStream {
     
     // Получаем атрибуты плагина
     let mut plugin_name = String::new();
-    let mut plugin_version = String::new();
-    let mut plugin_description = String::new();
-    let mut plugin_author = String::new();
-    let mut plugin_license = String::new();
+    let plugin_version = String::new();
+    let plugin_description = String::new();
+    let plugin_author = String::new();
+    let plugin_license = String::new();
     
     for attr in &input.attrs {
-        if attr.meta.path().is_ident("plugin") {
-            if let syn::Meta::List(meta_list) = &attr.meta {
-                for nested_meta in meta_list.tokens.clone().into_iter() {
-                    // Упрощенная версия для демонстрации
-                    // Здесь нужно использовать syn::parse2() для парсинга токенов
-                }
+        if attr.meta.path().is_ident("plugin")
+            && let syn::Meta::List(meta_list) = &attr.meta
+        {
+            for _nested_meta in meta_list.tokens.clone().into_iter() {
+                // Упрощенная версия для демонстрации
+                // Здесь нужно использовать syn::parse2() для парсинга токенов
             }
         }
     }
@@ -140,12 +143,22 @@ pub fn derive_plugin(input: TokenStream) -> TokenStream {
 
 /// Макрос для регистрации обработчиков событий в плагине
 /// 
-/// Этот макрос позволяет легко добавлять обработчики различных событий игрового сервера
+/// Каждый асинхронный метод `on_*` с аргументом `Arc<Событие>` регистрируется
+/// в асинхронной шине событий (`AsyncEventBus`) через сгенерированный метод
+/// `register_handlers`. Плагин должен храниться в `Arc`, чтобы обработчики
+/// могли выполняться после возврата из `register_handlers`.
+/// 
+/// Атрибут `#[backpressure(...)]` на методе задает размер очереди обработчика
+/// (`capacity`), число событий за одну доставку (`max_per_flush`) и поведение
+/// при переполнении (`overflow = "drop_oldest"` или `"drop_newest"`).
 /// 
 /// # Пример использования
 /// 
-/// ```
+/// ```ignore
+/// use std::sync::Arc;
 /// use cityrade_macros::{Plugin, handler};
+/// use cityrade_types::event_bus::AsyncEventBus;
+/// use cityrade_types::events::{PlayerJoinEvent, PlayerLeaveEvent};
 /// 
 /// #[derive(Plugin)]
 /// #[plugin(name = "EventPlugin")]
@@ -153,62 +166,167 @@ pub fn derive_plugin(input: TokenStream) -> TokenStream {
 /// 
 /// #[handler]
 /// impl EventPlugin {
-///     // Обработчик события тика
-///     async fn on_tick(&self, tick_count: u64) {
-///         println!("Тик: {}", tick_count);
+///     // Обработчик события подключения игрока
+///     async fn on_player_join(&self, event: Arc<PlayerJoinEvent>) {
+///         println!("Игрок {} подключился к миру {}", event.player_id, event.world_id);
 ///     }
 ///     
-///     // Обработчик события подключения игрока
-///     async fn on_player_join(&self, player_id: &str, world_id: &str) {
-///         println!("Игрок {} подключился к миру {}", player_id, world_id);
+///     // Медленный обработчик: не больше 64 событий в очереди, 8 за тик
+///     #[backpressure(capacity = 64, max_per_flush = 8, overflow = "drop_oldest")]
+///     async fn on_player_leave(&self, event: Arc<PlayerLeaveEvent>) {
+///         println!("Игрок {} покинул мир {}", event.player_id, event.world_id);
 ///     }
 /// }
+/// 
+/// let mut bus = AsyncEventBus::new();
+/// Arc::new(EventPlugin).register_handlers(&mut bus);
 /// ```
 #[proc_macro_attribute]
 pub fn handler(_attr: TokenStream, item: TokenStream) -> TokenStream {
-    let input = parse_macro_input!(item as syn::ItemImpl);
-    let self_ty = &input.self_ty;
+    let mut input = parse_macro_input!(item as syn::ItemImpl);
+    let self_ty = input.self_ty.clone();
+    let (impl_generics, _, where_clause) = input.generics.split_for_impl();
+    let type_name = quote!(#self_ty).to_string().replace(' ', "");
     
-    let mut event_handlers = Vec::new();
+    let mut registrations = Vec::new();
+    let mut errors = Vec::new();
     
-    for item in &input.items {
+    for item in &mut input.items {
         if let syn::ImplItem::Fn(method) = item {
-            let method_name = &method.sig.ident;
+            let method_name = method.sig.ident.clone();
             let method_name_str = method_name.to_string();
             
-            if method_name_str.starts_with("on_") {
-                let event_name = method_name_str.strip_prefix("on_").unwrap();
-                let handler = quote! {
-                    fn #method_name(&self) {
-                        println!("Handling event: {}", #event_name);
-                    }
-                };
-                event_handlers.push(handler);
+            if !method_name_str.starts_with("on_") {
+                continue;
             }
+            
+            let options = match take_backpressure_options(&mut method.attrs) {
+                Ok(options) => options,
+                Err(error) => {
+                    errors.push(error.to_compile_error());
+                    continue;
+                }
+            };
+            
+            let event_ty = match handler_event_type(&method.sig) {
+                Ok(event_ty) => event_ty,
+                Err(error) => {
+                    errors.push(error.to_compile_error());
+                    continue;
+                }
+            };
+            
+            let handler_id = format!("{}::{}", type_name, method_name_str);
+            registrations.push(quote! {
+                {
+                    let plugin = ::std::sync::Arc::clone(self);
+                    event_bus.register_handler::<#event_ty, _, _>(
+                        #handler_id.to_string(),
+                        #options,
+                        move |event| {
+                            let plugin = ::std::sync::Arc::clone(&plugin);
+                            async move { plugin.#method_name(event).await }
+                        },
+                    );
+                }
+            });
         }
     }
     
     let expanded = quote! {
         #input
         
-        impl #self_ty {
-            pub fn register_handlers(&self, event_system: &mut cityrade_types::events::EventSystem) {
-                // Регистрация обработчиков
-                #(#event_handlers)*
+        impl #impl_generics #self_ty #where_clause {
+            /// Регистрирует обработчики `on_*` в асинхронной шине событий
+            pub fn register_handlers(
+                self: &::std::sync::Arc<Self>,
+                event_bus: &mut cityrade_types::event_bus::AsyncEventBus,
+            ) {
+                #(#registrations)*
             }
         }
+        
+        #(#errors)*
     };
     
     TokenStream::from(expanded)
 }
 
+/// Извлекает тип события из сигнатуры `async fn on_*(&self, event: Arc<E>)`
+fn handler_event_type(sig: &syn::Signature) -> syn::Result<syn::Type> {
+    if sig.asyncness.is_none() {
+        return Err(syn::Error::new_spanned(sig, "Обработчик события должен быть async"));
+    }
+    
+    let mut inputs = sig.inputs.iter();
+    let has_receiver = matches!(inputs.next(), Some(syn::FnArg::Receiver(receiver)) if receiver.reference.is_some() && receiver.mutability.is_none());
+    let event_arg = inputs.next();
+    
+    if !has_receiver || inputs.next().is_some() {
+        return Err(syn::Error::new_spanned(
+            &sig.inputs,
+            "Обработчик события должен принимать &self и один аргумент Arc<Событие>",
+        ));
+    }
+    
+    if let Some(syn::FnArg::Typed(arg)) = event_arg
+        && let syn::Type::Path(type_path) = arg.ty.as_ref()
+        && let Some(segment) = type_path.path.segments.last()
+        && segment.ident == "Arc"
+        && let syn::PathArguments::AngleBracketed(arguments) = &segment.arguments
+        && let Some(syn::GenericArgument::Type(event_ty)) = arguments.args.first()
+    {
+        return Ok(event_ty.clone());
+    }
+    
+    Err(syn::Error::new_spanned(
+        &sig.inputs,
+        "Аргумент обработчика события должен иметь тип Arc<Событие>",
+    ))
+}
+
+/// Забирает атрибут `#[backpressure(...)]` с метода и строит `HandlerOptions`
+fn take_backpressure_options(attrs: &mut Vec<syn::Attribute>) -> syn::Result<proc_macro2::TokenStream> {
+    let mut options = quote! { cityrade_types::event_bus::HandlerOptions::default() };
+    
+    let Some(index) = attrs.iter().position(|attr| attr.path().is_ident("backpressure")) else {
+        return Ok(options);
+    };
+    let attr = attrs.remove(index);
+    
+    attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("capacity") {
+            let value: syn::LitInt = meta.value()?.parse()?;
+            options = quote! { #options.with_capacity(#value) };
+        } else if meta.path.is_ident("max_per_flush") {
+            let value: syn::LitInt = meta.value()?.parse()?;
+            options = quote! { #options.with_max_per_flush(#value) };
+        } else if meta.path.is_ident("overflow") {
+            let value: syn::LitStr = meta.value()?.parse()?;
+            let policy = match value.value().as_str() {
+                "drop_oldest" => format_ident!("DropOldest"),
+                "drop_newest" => format_ident!("DropNewest"),
+                _ => return Err(meta.error("Ожидается overflow = \"drop_oldest\" или \"drop_newest\"")),
+            };
+            options = quote! {
+                #options.with_overflow(cityrade_types::event_bus::OverflowPolicy::#policy)
+            };
+        } else {
+            return Err(meta.error("Неизвестный параметр backpressure"));
+        }
+        Ok(())
+    })?;
+    
+    Ok(options)
+}
+
 /// Макрос для создания нового плагина
 /// 
 /// Этот макрос создает новый плагин с базовой структурой и необходимыми реализациями.
 /// 
 /// # Пример использования
 /// 
-/// ```
+/// ```ignore
 /// use cityrade_macros::create_plugin;
 /// 
 /// create_plugin! {
@@ -248,7 +366,7 @@ pub fn handler(_attr: TokenStream, item: TokenStream) -> TokenStream {
 /// ```
 #[proc_macro]
 pub fn create_plugin(input: TokenStream) -> TokenStream {
-    let input_str = input.to_string();
+    let _input_str = input.to_string();
     
     // Очень простая реализация для примера
     let expanded = quote! {
@@ -322,7 +440,7 @@ pub fn create_plugin(input: TokenStream) -> TokenStream {
 /// 
 /// # Пример использования
 /// 
-/// ```
+/// ```ignore
 /// use cityrade_macros::building_type;
 /// 
 /// building_type! {
@@ -356,24 +474,24 @@ pub fn building_type(input: TokenStream) -> TokenStream {
             
             match field_name.as_str() {
                 "name" => {
-                    if let syn::Expr::Lit(expr_lit) = &field.expr {
-                        if let syn::Lit::Str(lit_str) = &expr_lit.lit {
-                            name = lit_str.value();
-                        }
+                    if let syn::Expr::Lit(expr_lit) = &field.expr
+                        && let syn::Lit::Str(lit_str) = &expr_lit.lit
+                    {
+                        name = lit_str.value();
                     }
                 },
                 "id" => {
-                    if let syn::Expr::Lit(expr_lit) = &field.expr {
-                        if let syn::Lit::Str(lit_str) = &expr_lit.lit {
-                            id = lit_str.value();
-                        }
+                    if let syn::Expr::Lit(expr_lit) = &field.expr
+                        && let syn::Lit::Str(lit_str) = &expr_lit.lit
+                    {
+                        id = lit_str.value();
                     }
                 },
                 "description" => {
-                    if let syn::Expr::Lit(expr_lit) = &field.expr {
-                        if let syn::Lit::Str(lit_str) = &expr_lit.lit {
-                            description = lit_str.value();
-                        }
+                    if let syn::Expr::Lit(expr_lit) = &field.expr
+                        && let syn::Lit::Str(lit_str) = &expr_lit.lit
+                    {
+                        description = lit_str.value();
                     }
                 },
                 _ => {}
@@ -430,7 +548,7 @@ pub fn building_type(input: TokenStream) -> TokenStream {
 /// 
 /// # Пример использования
 /// 
-/// ```
+/// ```ignore
 /// use cityrade_macros::building;
 /// 
 /// let market = building! {
@@ -446,10 +564,10 @@ pub fn building(input: TokenStream) -> TokenStream {
     
     // Извлекаем поля из макроса
     let mut name = String::new();
-    let mut building_type = String::new();
+    let building_type = String::new();
     let mut level = 1u32;
-    let mut position_x = 0i32;
-    let mut position_y = 0i32;
+    let position_x = 0i32;
+    let position_y = 0i32;
     
     for field in &input.fields {
         if let syn::Member::Named(ident) = &field.member {
@@ -466,10 +584,10 @@ pub fn building(input: TokenStream) -> TokenStream {
                     // ...
                 },
                 "level" => {
-                    if let syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Int(i), .. }) = &field.expr {
-                        if let Ok(l) = i.base10_parse::<u32>() {
-                            level = l;
-                        }
+                    if let syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Int(i), .. }) = &field.expr
+                        && let Ok(l) = i.base10_parse::<u32>()
+                    {
+                        level = l;
                     }
                 },
                 "position" => {
@@ -511,7 +629,7 @@ pub fn building(input: TokenStream) -> TokenStream {
 /// 
 /// # Пример использования
 /// 
-/// ```
+/// ```ignore
 /// use cityrade_macros::world_generator;
 /// 
 /// let generator = world_generator! {
@@ -547,11 +665,11 @@ pub fn world_generator(input: TokenStream) -> TokenStream {
             
             match field_name.as_str() {
                 "seed" => {
-                    if let syn::Expr::Lit(expr_lit) = &field.expr {
-                        if let syn::Lit::Int(lit_int) = &expr_lit.lit {
-                            let seed_value = lit_int.base10_parse::<u64>().unwrap_or(0);
-                            seed = quote!(Some(#seed_value));
-                        }
+                    if let syn::Expr::Lit(expr_lit) = &field.expr
+                        && let syn::Lit::Int(lit_int) = &expr_lit.lit
+                    {
+                        let seed_value = lit_int.base10_parse::<u64>().unwrap_or(0);
+                        seed = quote!(Some(#seed_value));
                     }
                 },
                 "size" => {
@@ -580,7 +698,7 @@ pub fn world_generator(input: TokenStream) -> TokenStream {
 /// 
 /// # Пример использования
 /// 
-/// ```
+/// ```ignore
 /// use cityrade_macros::BuildingType;
 /// 
 /// #[derive(BuildingType)]
@@ -609,10 +727,10 @@ pub fn derive_building_type(input: TokenStream) -> TokenStream {
     
     // Извлекаем атрибуты здания
     let mut building_name = String::new();
-    let mut building_id = String::new();
+    let mut _building_id = String::new();
     let mut building_description = String::new();
-    let mut base_costs: Vec<(ResourceType, u32)> = Vec::new();
-    let mut production_effects: HashMap<u32, Vec<(ResourceType, i32)>> = HashMap::new();
+    let _base_costs: Vec<(ResourceType, u32)> = Vec::new();
+    let _production_effects: HashMap<u32, Vec<(ResourceType, i32)>> = HashMap::new();
     
     for attr in &input.attrs {
         if attr.path().is_ident("building") {
@@ -631,8 +749,8 @@ pub fn derive_building_type(input: TokenStream) -> TokenStream {
     if building_name.is_empty() {
         building_name = name.to_string();
     }
-    if building_id.is_empty() {
-        building_id = name.to_string().to_lowercase();
+    if _building_id.is_empty() {
+        _building_id = name.to_string().to_lowercase();
     }
     if building_description.is_empty() {
         building_description = format!("Здание {}", name);
@@ -708,11 +826,11 @@ pub fn derive_world_generator(input: TokenStream) -> TokenStream {
     let name = &input.ident;
     
     // Извлекаем атрибуты генератора
-    let mut generator_name = String::new();
-    let mut generator_description = String::new();
-    let mut biomes: HashMap<String, f32> = HashMap::new();
-    let mut resources: HashMap<String, ResourceConfig> = HashMap::new();
-    let mut structures: HashMap<String, StructureConfig> = HashMap::new();
+    let mut _generator_name = String::new();
+    let mut _generator_description = String::new();
+    let _biomes: HashMap<String, f32> = HashMap::new();
+    let _resources: HashMap<String, ResourceConfig> = HashMap::new();
+    let _structures: HashMap<String, StructureConfig> = HashMap::new();
     
     for attr in &input.attrs {
         if attr.path().is_ident("generator") {
@@ -731,11 +849,11 @@ pub fn derive_world_generator(input: TokenStream) -> TokenStream {
     }
     
     // Если атрибуты не указаны, используем имя структуры
-    if generator_name.is_empty() {
-        generator_name = name.to_string();
+    if _generator_name.is_empty() {
+        _generator_name = name.to_string();
     }
-    if generator_description.is_empty() {
-        generator_description = format!("Генератор мира {}", name);
+    if _generator_description.is_empty() {
+        _generator_description = format!("Генератор мира {}", name);
     }
     
     // Генерируем трейты и имплементации для генератора мира
@@ -768,7 +886,7 @@ pub fn derive_world_generator(input: TokenStream) -> TokenStream {
 /// 
 /// # Пример использования
 /// 
-/// ```
+/// ```ignore
 /// use cityrade_macros::event_handler;
 /// 
 /// event_handler! {
@@ -790,7 +908,7 @@ pub fn derive_world_generator(input: TokenStream) -> TokenStream {
 /// ```
 #[proc_macro]
 pub fn event_handler(input: TokenStream) -> TokenStream {
-    let input_str = input.to_string();
+    let _input_str = input.to_string();
     
     // Простая демонстрационная реализация
     let expanded = quote! {
@@ -837,7 +955,7 @@ pub fn event_handler(input: TokenStream) -> TokenStream {
 /// 
 /// # Пример использования
 /// 
-/// ```
+/// ```ignore
 /// use cityrade_macros::command;
 /// 
 /// command! {
@@ -866,10 +984,10 @@ pub fn command(input: TokenStream) -> TokenStream {
     // Извлекаем поля из макроса
     let mut name = String::new();
     let mut aliases: Vec<String> = Vec::new();
-    let mut permission = "default.permission".to_string();
-    let mut usage = "".to_string();
-    let mut description = "".to_string();
-    let mut min_args = 0;
+    let permission = "default.permission".to_string();
+    let usage = "".to_string();
+    let description = "".to_string();
+    let min_args = 0;
     let mut handler = None;
     
     for field in &input.fields {
@@ -878,19 +996,19 @@ pub fn command(input: TokenStream) -> TokenStream {
             
             match field_name.as_str() {
                 "name" => {
-                    if let syn::Expr::Lit(expr_lit) = &field.expr {
-                        if let syn::Lit::Str(lit_str) = &expr_lit.lit {
-                            name = lit_str.value();
-                        }
+                    if let syn::Expr::Lit(expr_lit) = &field.expr
+                        && let syn::Lit::Str(lit_str) = &expr_lit.lit
+                    {
+                        name = lit_str.value();
                     }
                 },
                 "aliases" => {
                     if let syn::Expr::Array(array) = &field.expr {
                         for elem in &array.elems {
-                            if let syn::Expr::Lit(expr_lit) = elem {
-                                if let syn::Lit::Str(lit_str) = &expr_lit.lit {
-                                    aliases.push(lit_str.value());
-                                }
+                            if let syn::Expr::Lit(expr_lit) = elem
+                                && let syn::Lit::Str(lit_str) = &expr_lit.lit
+                            {
+                                aliases.push(lit_str.value());
                             }
                         }
                     }
@@ -965,7 +1083,7 @@ pub fn command(input: TokenStream) -> TokenStream {
 /// 
 /// # Пример использования
 /// 
-/// ```
+/// ```ignore
 /// use cityrade_macros::config;
 /// 
 /// config! {
@@ -0,0 +1,105 @@
+//! Сравнение чанковой карты мира с прежним хранением клеток в `HashMap`.
+//!
+//! Запуск: `cargo bench -p cityrade-types --bench world_map`. Перед замерами
+//! печатается примерный объем памяти обеих карт размера `DEFAULT_WORLD_SIZE`.
+
+use std::collections::HashMap;
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use cityrade_types::constants::DEFAULT_WORLD_SIZE;
+use cityrade_types::world::{TerrainTile, WorldMap};
+
+/// Прежнее хранение карты: каждая клетка — отдельная запись `HashMap`
+struct HashWorld {
+    terrain: HashMap<(i32, i32), TerrainTile>,
+}
+
+impl HashWorld {
+    fn new(width: u32, height: u32) -> Self {
+        let mut terrain = HashMap::new();
+        for x in 0..width as i32 {
+            for y in 0..height as i32 {
+                terrain.insert((x, y), TerrainTile::Land);
+            }
+        }
+        HashWorld { terrain }
+    }
+
+    /// Ключ, клетка и байт управления на запись, с учетом свободных мест таблицы
+    fn memory_usage(&self) -> usize {
+        self.terrain.capacity() * (std::mem::size_of::<((i32, i32), TerrainTile)>() + 1)
+    }
+}
+
+/// Псевдослучайные координаты для чтения и записи
+fn positions(count: usize, width: u32, height: u32) -> Vec<(i32, i32)> {
+    let mut state: u64 = 0x2545_F491_4F6C_DD1D;
+    (0..count)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            ((state % width as u64) as i32, ((state >> 32) % height as u64) as i32)
+        })
+        .collect()
+}
+
+fn bench_world_map(c: &mut Criterion) {
+    let (width, height) = DEFAULT_WORLD_SIZE;
+    let mut chunked = WorldMap::new(width as u64, height as u64);
+    let hashed = HashWorld::new(width, height);
+    let positions = positions(10_000, width, height);
+    for (index, (x, y)) in positions.iter().enumerate() {
+        chunked.add_building(*x, *y, format!("Здание {}", index % 100));
+    }
+
+    println!(
+        "Память карты {}x{}: чанки — {} КБ ({} из {} чанков заняты), HashMap — {} КБ",
+        width,
+        height,
+        chunked.memory_usage() / 1024,
+        chunked.allocated_chunks(),
+        chunked.chunk_count(),
+        hashed.memory_usage() / 1024
+    );
+
+    let mut group = c.benchmark_group("new");
+    group.sample_size(10);
+    group.bench_function("chunked", |b| b.iter(|| WorldMap::new(black_box(width as u64), black_box(height as u64))));
+    group.bench_function("hashmap", |b| b.iter(|| HashWorld::new(black_box(width), black_box(height))));
+    group.finish();
+
+    let mut group = c.benchmark_group("get_tile");
+    group.bench_function("chunked", |b| {
+        b.iter(|| positions.iter().filter(|(x, y)| chunked.get_tile(*x, *y) == Some(TerrainTile::Land)).count())
+    });
+    group.bench_function("hashmap", |b| {
+        b.iter(|| positions.iter().filter(|position| hashed.terrain.get(position) == Some(&TerrainTile::Land)).count())
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("set_tile");
+    group.bench_function("chunked", |b| {
+        b.iter(|| {
+            for (x, y) in &positions {
+                chunked.set_tile(*x, *y, TerrainTile::Forest);
+            }
+        })
+    });
+    let mut hashed = hashed;
+    group.bench_function("hashmap", |b| {
+        b.iter(|| {
+            for position in &positions {
+                hashed.terrain.insert(*position, TerrainTile::Forest);
+            }
+        })
+    });
+    group.finish();
+
+    c.bench_function("region_64x64", |b| b.iter(|| chunked.region(black_box((100, 100)), (163, 163)).count()));
+}
+
+criterion_group!(benches, bench_world_map);
+criterion_main!(benches);
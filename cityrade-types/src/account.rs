@@ -35,13 +35,13 @@ impl Account {
             sub: self.id.clone(),
             exp: (Utc::now() + chrono::Duration::days(1)).timestamp() as usize,
         };
-        let token = encode(
+
+        encode(
             &Header::default(),
             &claims,
             &EncodingKey::from_secret(std::env::var("KEY").unwrap().as_bytes()),
         )
-        .unwrap();
-        token
+        .unwrap()
     }
 
     pub fn update_last_login(&mut self) {
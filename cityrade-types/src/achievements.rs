@@ -1,6 +1,7 @@
 use serde::{Serialize, Deserialize};
 use std::collections::{HashSet, HashMap};
-use super::resources::{ResourceType, BuildingType};
+use super::building::BuildingType;
+use super::resources::ResourceType;
 use super::diplomacy::RelationType;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -1,10 +1,24 @@
+use crate::building_registry::BuildingRegistry;
+use crate::city::Terrain;
+use crate::constants::MAX_BUILDING_LEVEL;
 use crate::resources::{ResourceType, Resource};
+use crate::technology::TechnologyType;
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Тип здания.
+///
+/// Единственная модель зданий: ее используют города, квесты, технологии и
+/// достижения. Названия из прежнего набора `resources::BuildingType`
+/// (`House`, `Sawmill`) принимаются при десериализации и в `from_str`,
+/// поэтому старые сохранения продолжают загружаться.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum BuildingType {
+    #[serde(alias = "House")]
     Residential, // Увеличивает лимит населения
     Farm,        // Производит еду
+    #[serde(alias = "Sawmill")]
     LumberMill,  // Производит дерево
     Mine,        // Производит камень и железо
     Market,      // Увеличивает доход золота
@@ -16,9 +30,50 @@ pub enum BuildingType {
     Wall,        // Защита города
     Workshop,    // Улучшает производство предметов
     CrystalMine, // Производит кристаллы
+    Bank,        // Хранит золото и приносит проценты
+    School,      // Повышает культуру
+    Warehouse,   // Склад для строительных материалов и товаров
+    Granary,     // Хранилище еды
+    Vault,       // Хранилище золота и ценностей
+}
+
+/// Имена вариантов прежнего `resources::BuildingType`
+#[allow(non_upper_case_globals)]
+impl BuildingType {
+    #[deprecated(note = "используйте BuildingType::Residential")]
+    pub const House: BuildingType = BuildingType::Residential;
+    #[deprecated(note = "используйте BuildingType::LumberMill")]
+    pub const Sawmill: BuildingType = BuildingType::LumberMill;
 }
 
 impl BuildingType {
+    /// Все типы зданий
+    pub const ALL: [BuildingType; 18] = [
+        BuildingType::Residential,
+        BuildingType::Farm,
+        BuildingType::LumberMill,
+        BuildingType::Mine,
+        BuildingType::Market,
+        BuildingType::Barracks,
+        BuildingType::PowerPlant,
+        BuildingType::Laboratory,
+        BuildingType::Temple,
+        BuildingType::WaterMill,
+        BuildingType::Wall,
+        BuildingType::Workshop,
+        BuildingType::CrystalMine,
+        BuildingType::Bank,
+        BuildingType::School,
+        BuildingType::Warehouse,
+        BuildingType::Granary,
+        BuildingType::Vault,
+    ];
+
+    /// Добывает ли здание ресурсы из месторождений на карте мира
+    pub fn extracts_deposits(&self) -> bool {
+        matches!(self, BuildingType::Mine | BuildingType::CrystalMine | BuildingType::LumberMill)
+    }
+
     pub fn display_name(&self) -> &str {
         match self {
             BuildingType::Residential => "Жилой дом",
@@ -34,6 +89,11 @@ impl BuildingType {
             BuildingType::Wall => "Стена",
             BuildingType::Workshop => "Мастерская",
             BuildingType::CrystalMine => "Кристальная шахта",
+            BuildingType::Bank => "Банк",
+            BuildingType::School => "Школа",
+            BuildingType::Warehouse => "Склад",
+            BuildingType::Granary => "Амбар",
+            BuildingType::Vault => "Хранилище",
         }
     }
 
@@ -42,7 +102,7 @@ impl BuildingType {
             BuildingType::Residential => "Увеличивает максимальное население города",
             BuildingType::Farm => "Производит еду для населения",
             BuildingType::LumberMill => "Добывает дерево из окрестных лесов",
-            BuildingType::Mine => "Добывает камень и железо из недр земли",
+            BuildingType::Mine => "Добывает камень, железо и уголь из недр земли",
             BuildingType::Market => "Увеличивает доход золота в городе",
             BuildingType::Barracks => "Позволяет тренировать военные отряды",
             BuildingType::PowerPlant => "Вырабатывает энергию для города",
@@ -50,109 +110,157 @@ impl BuildingType {
             BuildingType::Temple => "Повышает счастье и мораль населения",
             BuildingType::WaterMill => "Увеличивает общую продуктивность",
             BuildingType::Wall => "Защищает город от нападений",
-            BuildingType::Workshop => "Улучшает производство и ремесло",
+            BuildingType::Workshop => "Изготавливает инструменты и роскошные товары",
             BuildingType::CrystalMine => "Добывает редкие магические кристаллы",
+            BuildingType::Bank => "Приумножает золотой запас города",
+            BuildingType::School => "Обучает жителей и развивает культуру",
+            BuildingType::Warehouse => "Увеличивает запас дерева, камня, железа, угля и инструментов",
+            BuildingType::Granary => "Увеличивает запас еды",
+            BuildingType::Vault => "Увеличивает запас золота, кристаллов и роскошных товаров",
         }
     }
+}
 
-    pub fn base_cost(&self) -> Vec<(ResourceType, u32)> {
-        match self {
-            BuildingType::Residential => vec![(ResourceType::Wood, 50), (ResourceType::Stone, 30)],
-            BuildingType::Farm => vec![(ResourceType::Wood, 30), (ResourceType::Gold, 20)],
-            BuildingType::LumberMill => vec![
-                (ResourceType::Wood, 20),
-                (ResourceType::Stone, 50),
-                (ResourceType::Gold, 30),
-            ],
-            BuildingType::Mine => vec![
-                (ResourceType::Wood, 40),
-                (ResourceType::Stone, 20),
-                (ResourceType::Gold, 50),
-            ],
-            BuildingType::Market => vec![
-                (ResourceType::Wood, 60),
-                (ResourceType::Stone, 40),
-                (ResourceType::Gold, 100),
-            ],
-            BuildingType::Barracks => vec![
-                (ResourceType::Wood, 80),
-                (ResourceType::Stone, 100),
-                (ResourceType::Iron, 50),
-            ],
-            BuildingType::PowerPlant => vec![
-                (ResourceType::Stone, 150),
-                (ResourceType::Iron, 80),
-                (ResourceType::Gold, 200),
-            ],
-            BuildingType::Laboratory => vec![
-                (ResourceType::Stone, 100),
-                (ResourceType::Crystal, 30),
-                (ResourceType::Gold, 250),
-            ],
-            BuildingType::Temple => vec![
-                (ResourceType::Stone, 200),
-                (ResourceType::Wood, 100),
-                (ResourceType::Gold, 150),
-                (ResourceType::Crystal, 20),
-            ],
-            BuildingType::WaterMill => vec![
-                (ResourceType::Wood, 120),
-                (ResourceType::Stone, 80),
-                (ResourceType::Gold, 100),
-            ],
-            BuildingType::Wall => vec![(ResourceType::Stone, 300), (ResourceType::Iron, 100)],
-            BuildingType::Workshop => vec![
-                (ResourceType::Wood, 150),
-                (ResourceType::Stone, 100),
-                (ResourceType::Iron, 50),
-                (ResourceType::Gold, 120),
-            ],
-            BuildingType::CrystalMine => vec![
-                (ResourceType::Stone, 200),
-                (ResourceType::Iron, 150),
-                (ResourceType::Gold, 300),
-            ],
+impl FromStr for BuildingType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "residential" | "house" => Ok(BuildingType::Residential),
+            "farm" => Ok(BuildingType::Farm),
+            "lumbermill" | "sawmill" => Ok(BuildingType::LumberMill),
+            "mine" => Ok(BuildingType::Mine),
+            "market" => Ok(BuildingType::Market),
+            "barracks" => Ok(BuildingType::Barracks),
+            "powerplant" => Ok(BuildingType::PowerPlant),
+            "laboratory" => Ok(BuildingType::Laboratory),
+            "temple" => Ok(BuildingType::Temple),
+            "watermill" => Ok(BuildingType::WaterMill),
+            "wall" => Ok(BuildingType::Wall),
+            "workshop" => Ok(BuildingType::Workshop),
+            "crystalmine" => Ok(BuildingType::CrystalMine),
+            "bank" => Ok(BuildingType::Bank),
+            "school" => Ok(BuildingType::School),
+            "warehouse" => Ok(BuildingType::Warehouse),
+            "granary" => Ok(BuildingType::Granary),
+            "vault" => Ok(BuildingType::Vault),
+            _ => Err(format!("Неизвестный тип здания: {}", s)),
         }
     }
+}
+
+/// Причина, по которой здание нельзя построить или улучшить
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuildingError {
+    /// Город не найден
+    CityNotFound,
+    /// Здание не найдено
+    BuildingNotFound,
+    /// Тип здания отсутствует в реестре
+    UnknownBuildingType(BuildingType),
+    /// Здание нельзя строить на местности города
+    TerrainNotAllowed { building_type: BuildingType, terrain: Terrain },
+    /// Достигнут предел количества зданий в городе
+    BuildingLimitReached { limit: u32 },
+    /// Позиция занята другим зданием, дорогой или площадью
+    PositionOccupied((i32, i32)),
+    /// Позиция за пределами сетки города
+    OutOfBounds((i32, i32)),
+    /// Здание нельзя строить на местности клетки
+    TileNotAllowed { building_type: BuildingType, terrain: Terrain },
+    /// Здание должно граничить с одной из местностей
+    AdjacentTerrainRequired { building_type: BuildingType, terrain: Vec<Terrain> },
+    /// Здание должно граничить с дорогой, связанной с центром города
+    RoadRequired,
+    /// Дорогу нельзя проложить по местности клетки
+    RoadNotAllowed(Terrain),
+    /// Не хватает ресурсов
+    NotEnoughResources { cost: Vec<(ResourceType, u32)> },
+    /// Здание достигло максимального уровня своего типа
+    MaxLevelReached { building_type: BuildingType, max_level: u32 },
+    /// Здание уже улучшается
+    AlreadyUpgrading,
+    /// Не исследована нужная технология
+    MissingTechnology(TechnologyType),
+    /// В городе нет здания нужного типа и уровня
+    MissingBuilding { building_type: BuildingType, level: u32 },
+    /// Недостаточно населения
+    NotEnoughPopulation { required: u32, actual: u32 },
+    /// В очереди строительства нет задания для здания
+    ConstructionNotFound,
+    /// Город банкрот и не может строить
+    Bankrupt { debt: u32 },
+}
 
-    pub fn production_effect(&self, level: u32) -> Vec<(ResourceType, i32)> {
-        let base_effect = match self {
-            BuildingType::Residential => vec![(ResourceType::Population, 10 + level as i32 * 5)],
-            BuildingType::Farm => vec![(ResourceType::Food, 10 + level as i32 * 3)],
-            BuildingType::LumberMill => vec![(ResourceType::Wood, 8 + level as i32 * 2)],
-            BuildingType::Mine => vec![
-                (ResourceType::Stone, 5 + level as i32),
-                (ResourceType::Iron, 2 + (level as i32) / 2),
-            ],
-            BuildingType::Market => vec![(ResourceType::Gold, 15 + level as i32 * 5)],
-            BuildingType::Barracks => vec![
-                (ResourceType::Gold, -(10 + level as i32 * 2)),
-                (ResourceType::Food, -(5 + level as i32)),
-            ],
-            BuildingType::PowerPlant => vec![(ResourceType::Energy, 20 + level as i32 * 10)],
-            BuildingType::Laboratory => vec![
-                (ResourceType::Gold, -(20 + level as i32 * 5)),
-                (ResourceType::Energy, -(5 + level as i32 * 2)),
-            ],
-            BuildingType::Temple => vec![(ResourceType::Gold, -(10 + level as i32 * 3))],
-            BuildingType::WaterMill => vec![
-                (ResourceType::Food, 5 + level as i32),
-                (ResourceType::Wood, 5 + level as i32),
-            ],
-            BuildingType::Wall => vec![],
-            BuildingType::Workshop => vec![
-                (ResourceType::Gold, 10 + level as i32 * 3),
-                (ResourceType::Energy, -(3 + level as i32)),
-            ],
-            BuildingType::CrystalMine => vec![
-                (ResourceType::Crystal, 1 + level as i32 / 3),
-                (ResourceType::Energy, -(10 + level as i32 * 2)),
-            ],
-        };
-        base_effect
+impl fmt::Display for BuildingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BuildingError::CityNotFound => write!(f, "Город не найден"),
+            BuildingError::BuildingNotFound => write!(f, "Здание не найдено"),
+            BuildingError::UnknownBuildingType(building_type) => {
+                write!(f, "Нет описания здания {:?}", building_type)
+            }
+            BuildingError::TerrainNotAllowed { building_type, terrain } => write!(
+                f,
+                "{} нельзя построить на местности «{}»",
+                building_type.display_name(),
+                terrain.display_name()
+            ),
+            BuildingError::BuildingLimitReached { limit } => {
+                write!(f, "Достигнут предел количества зданий ({})", limit)
+            }
+            BuildingError::PositionOccupied(_) => write!(f, "В этой позиции уже есть здание"),
+            BuildingError::OutOfBounds(position) => {
+                write!(f, "Позиция {:?} за пределами города", position)
+            }
+            BuildingError::TileNotAllowed { building_type, terrain } => write!(
+                f,
+                "{} нельзя построить на клетке «{}»",
+                building_type.display_name(),
+                terrain.display_name()
+            ),
+            BuildingError::AdjacentTerrainRequired { building_type, terrain } => write!(
+                f,
+                "{} должно граничить с местностью «{}»",
+                building_type.display_name(),
+                terrain.iter().map(Terrain::display_name).collect::<Vec<_>>().join("», «")
+            ),
+            BuildingError::RoadRequired => {
+                write!(f, "Здание должно граничить с дорогой, ведущей к центру города")
+            }
+            BuildingError::RoadNotAllowed(terrain) => {
+                write!(f, "Дорогу нельзя проложить по местности «{}»", terrain.display_name())
+            }
+            BuildingError::NotEnoughResources { .. } => write!(f, "Недостаточно ресурсов"),
+            BuildingError::MaxLevelReached { building_type, max_level } => write!(
+                f,
+                "{} достигло максимального уровня {}",
+                building_type.display_name(),
+                max_level
+            ),
+            BuildingError::AlreadyUpgrading => write!(f, "Здание уже улучшается"),
+            BuildingError::MissingTechnology(technology) => {
+                write!(f, "Требуется технология {:?}", technology)
+            }
+            BuildingError::MissingBuilding { building_type, level } => write!(
+                f,
+                "Требуется здание «{}» уровня {}",
+                building_type.display_name(),
+                level
+            ),
+            BuildingError::NotEnoughPopulation { required, actual } => {
+                write!(f, "Требуется население {} (сейчас {})", required, actual)
+            }
+            BuildingError::ConstructionNotFound => write!(f, "Задание строительства не найдено"),
+            BuildingError::Bankrupt { debt } => {
+                write!(f, "Город банкрот: долг казны {} золота", debt)
+            }
+        }
     }
 }
 
+impl std::error::Error for BuildingError {}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Building {
     pub id: String,
@@ -178,8 +286,14 @@ impl Building {
         }
     }
 
-    pub fn upgrade(&mut self) {
+    /// Повышает уровень здания на единицу, не выше `MAX_BUILDING_LEVEL`.
+    /// Возвращает `false`, если здание уже на предельном уровне.
+    pub fn upgrade(&mut self) -> bool {
+        if self.level >= MAX_BUILDING_LEVEL {
+            return false;
+        }
         self.level += 1;
+        true
     }
 
     pub fn get_info(&self) -> String {
@@ -193,22 +307,24 @@ impl Building {
         )
     }
 
-    pub fn upgrade_cost(&self) -> Vec<(ResourceType, u32)> {
-        let base_costs = self.building_type.base_cost();
-        let multiplier = (1.5f32).powi(self.level as i32);
-
-        base_costs
-            .iter()
-            .map(|(rt, amount)| (rt.clone(), (*amount as f32 * multiplier) as u32))
-            .collect()
+    /// Стоимость улучшения до следующего уровня.
+    /// `None`, если здание уже достигло максимального уровня.
+    pub fn upgrade_cost(&self, registry: &BuildingRegistry) -> Option<Vec<(ResourceType, u32)>> {
+        registry
+            .level(&self.building_type, self.level + 1)
+            .map(|level| level.cost_list())
     }
 
-    pub fn production_effect(&self) -> Vec<(ResourceType, i32)> {
-        self.building_type.production_effect(self.level)
+    /// Изменение ресурсов за ход на текущем уровне здания
+    pub fn production_effect(&self, registry: &BuildingRegistry) -> Vec<(ResourceType, i32)> {
+        registry
+            .level(&self.building_type, self.level)
+            .map(|level| level.net_production())
+            .unwrap_or_default()
     }
 
-    pub fn apply_production_to_resources(&self, resources: &mut Resource) {
-        for (resource, amount) in self.production_effect() {
+    pub fn apply_production_to_resources(&self, registry: &BuildingRegistry, resources: &mut Resource) {
+        for (resource, amount) in self.production_effect(registry) {
             if amount > 0 {
                 resources.add(&resource, amount as u32);
             } else if amount < 0 {
@@ -0,0 +1,389 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+use crate::building::BuildingType;
+use crate::city::Terrain;
+use crate::constants::MAX_BUILDING_LEVEL;
+use crate::population::PopulationClass;
+use crate::resources::ResourceType;
+use crate::technology::TechnologyType;
+
+/// Описания зданий, поставляемые вместе с игрой
+const BUILTIN_BUILDINGS: &str = include_str!("../data/buildings.ron");
+
+/// Вклад здания в статистику города
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatEffects {
+    pub happiness: i32,
+    pub defense: i32,
+    pub culture: i32,
+    pub max_population: i32,
+    /// Дополнительные места для одновременного строительства
+    pub construction_slots: i32,
+}
+
+/// Условия постройки или улучшения здания до уровня
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BuildingRequirements {
+    /// Исследованные технологии
+    pub technologies: Vec<TechnologyType>,
+    /// Другие здания города и их минимальный уровень
+    pub buildings: BTreeMap<BuildingType, u32>,
+    /// Минимальное население города
+    pub min_population: u32,
+}
+
+impl BuildingRequirements {
+    pub fn is_empty(&self) -> bool {
+        self.technologies.is_empty() && self.buildings.is_empty() && self.min_population == 0
+    }
+}
+
+/// Правила размещения здания на сетке города
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlacementRules {
+    /// Допустимая местность клетки; пустой список — любая клетка, кроме воды
+    pub tile: Vec<Terrain>,
+    /// Здание должно граничить с клеткой одной из этих местностей
+    pub adjacent: Vec<Terrain>,
+    /// Здание должно граничить с дорогой, связанной с центральной площадью
+    pub road: bool,
+}
+
+impl PlacementRules {
+    pub fn is_empty(&self) -> bool {
+        self.tile.is_empty() && self.adjacent.is_empty() && !self.road
+    }
+}
+
+/// Рецепт переработки: за ход здание расходует `inputs` и выпускает `outputs`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Recipe {
+    pub inputs: BTreeMap<ResourceType, u32>,
+    pub outputs: BTreeMap<ResourceType, u32>,
+}
+
+/// Параметры одного уровня здания
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BuildingLevel {
+    /// Длительность постройки или улучшения до этого уровня в ходах
+    pub build_time: u32,
+    /// Стоимость постройки (для первого уровня) или улучшения до этого уровня
+    pub cost: BTreeMap<ResourceType, u32>,
+    /// Производство ресурсов за ход
+    pub production: BTreeMap<ResourceType, u32>,
+    /// Потребление ресурсов за ход
+    pub consumption: BTreeMap<ResourceType, u32>,
+    /// Вклад в статистику города
+    pub stats: StatEffects,
+    /// Рецепты переработки, выполняемые по порядку
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub recipes: Vec<Recipe>,
+    /// Дополнительная вместимость складов по ресурсам
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub storage: BTreeMap<ResourceType, u32>,
+    /// Рабочие места по классам населения; производство и рецепты
+    /// масштабируются долей занятых мест
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub workers: BTreeMap<PopulationClass, u32>,
+    /// Условия постройки или улучшения до этого уровня
+    #[serde(skip_serializing_if = "BuildingRequirements::is_empty")]
+    pub requirements: BuildingRequirements,
+}
+
+impl BuildingLevel {
+    /// Стоимость уровня в формате `Resource::can_afford`/`Resource::pay`
+    pub fn cost_list(&self) -> Vec<(ResourceType, u32)> {
+        self.cost
+            .iter()
+            .map(|(resource, amount)| (resource.clone(), *amount))
+            .collect()
+    }
+
+    /// Изменение ресурсов за ход: производство минус потребление
+    pub fn net_production(&self) -> Vec<(ResourceType, i32)> {
+        let mut net: BTreeMap<ResourceType, i32> = BTreeMap::new();
+
+        for (resource, amount) in &self.production {
+            *net.entry(resource.clone()).or_insert(0) += *amount as i32;
+        }
+        for (resource, amount) in &self.consumption {
+            *net.entry(resource.clone()).or_insert(0) -= *amount as i32;
+        }
+
+        net.into_iter().collect()
+    }
+}
+
+/// Описание типа здания
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BuildingDefinition {
+    pub id: BuildingType,
+    pub display_name: String,
+    pub description: String,
+    pub max_level: u32,
+    /// Допустимая местность города; пустой список — любая местность
+    #[serde(default)]
+    pub terrain: Vec<Terrain>,
+    /// Правила размещения на сетке города
+    #[serde(default, skip_serializing_if = "PlacementRules::is_empty")]
+    pub placement: PlacementRules,
+    /// Бонус к производству здания в процентах за каждое соседнее здание указанного типа
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub adjacency: BTreeMap<BuildingType, u32>,
+    /// Параметры уровней: `levels[0]` — первый уровень
+    pub levels: Vec<BuildingLevel>,
+}
+
+impl BuildingDefinition {
+    /// Параметры указанного уровня (начиная с 1)
+    pub fn level(&self, level: u32) -> Option<&BuildingLevel> {
+        level
+            .checked_sub(1)
+            .and_then(|index| self.levels.get(index as usize))
+    }
+
+    /// Стоимость постройки здания
+    pub fn build_cost(&self) -> Vec<(ResourceType, u32)> {
+        self.level(1).map(BuildingLevel::cost_list).unwrap_or_default()
+    }
+
+    /// Можно ли строить здание на указанной местности
+    pub fn allows_terrain(&self, terrain: &Terrain) -> bool {
+        self.terrain.is_empty() || self.terrain.contains(terrain)
+    }
+
+    fn validate(&self, errors: &mut Vec<String>) {
+        if self.display_name.trim().is_empty() {
+            errors.push(format!("Здание {:?}: не задано название", self.id));
+        }
+
+        if self.max_level == 0 || self.max_level > MAX_BUILDING_LEVEL {
+            errors.push(format!(
+                "Здание {:?}: максимальный уровень должен быть от 1 до {}",
+                self.id, MAX_BUILDING_LEVEL
+            ));
+        }
+
+        if self.levels.len() != self.max_level as usize {
+            errors.push(format!(
+                "Здание {:?}: описано уровней {}, а максимальный уровень {}",
+                self.id,
+                self.levels.len(),
+                self.max_level
+            ));
+        }
+
+        for (index, level) in self.levels.iter().enumerate() {
+            if level.requirements.buildings.contains_key(&self.id) {
+                errors.push(format!(
+                    "Здание {:?}, уровень {}: здание не может требовать само себя",
+                    self.id,
+                    index + 1
+                ));
+            }
+
+            for (recipe_index, recipe) in level.recipes.iter().enumerate() {
+                if recipe.inputs.is_empty() || recipe.outputs.is_empty() {
+                    errors.push(format!(
+                        "Здание {:?}, уровень {}: у рецепта {} нет входов или выходов",
+                        self.id,
+                        index + 1,
+                        recipe_index + 1
+                    ));
+                }
+                if recipe.inputs.values().chain(recipe.outputs.values()).any(|amount| *amount == 0) {
+                    errors.push(format!(
+                        "Здание {:?}, уровень {}: в рецепте {} нулевое количество ресурса",
+                        self.id,
+                        index + 1,
+                        recipe_index + 1
+                    ));
+                }
+                for resource in recipe.inputs.keys() {
+                    if recipe.outputs.contains_key(resource) {
+                        errors.push(format!(
+                            "Здание {:?}, уровень {}: ресурс {} одновременно вход и выход рецепта {}",
+                            self.id,
+                            index + 1,
+                            resource,
+                            recipe_index + 1
+                        ));
+                    }
+                }
+            }
+
+            for resource in level.production.keys() {
+                if level.consumption.contains_key(resource) {
+                    errors.push(format!(
+                        "Здание {:?}, уровень {}: ресурс {} одновременно производится и потребляется",
+                        self.id,
+                        index + 1,
+                        resource
+                    ));
+                }
+            }
+        }
+    }
+}
+
+/// Формат файла с описаниями зданий
+#[derive(Debug, Serialize, Deserialize)]
+struct BuildingFile {
+    buildings: Vec<BuildingDefinition>,
+}
+
+/// Реестр зданий.
+///
+/// Стоимость, производство, влияние на статистику и ограничения зданий
+/// загружаются из файлов данных (RON, JSON или TOML), поэтому баланс можно
+/// менять без пересборки игры. Реестр проверяется при загрузке: каждое здание
+/// должно быть описано ровно один раз и иметь параметры для всех уровней.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuildingRegistry {
+    definitions: BTreeMap<BuildingType, BuildingDefinition>,
+}
+
+impl Default for BuildingRegistry {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl BuildingRegistry {
+    /// Создает реестр из описаний, проверяя их
+    pub fn new(definitions: Vec<BuildingDefinition>) -> Result<Self, String> {
+        let mut errors = Vec::new();
+        let mut registry = BTreeMap::new();
+
+        for definition in definitions {
+            definition.validate(&mut errors);
+
+            if registry.contains_key(&definition.id) {
+                errors.push(format!("Здание {:?} описано несколько раз", definition.id));
+                continue;
+            }
+            registry.insert(definition.id.clone(), definition);
+        }
+
+        let defined: BTreeSet<&BuildingType> = registry.keys().collect();
+        for building_type in BuildingType::ALL.iter() {
+            if !defined.contains(building_type) {
+                errors.push(format!("Нет описания здания {:?}", building_type));
+            }
+        }
+
+        // Требуемый уровень другого здания должен быть достижим
+        for definition in registry.values() {
+            for (index, level) in definition.levels.iter().enumerate() {
+                for (required, required_level) in &level.requirements.buildings {
+                    let max_level = registry.get(required).map_or(0, |d| d.max_level);
+                    if *required_level > max_level {
+                        errors.push(format!(
+                            "Здание {:?}, уровень {}: требуется {:?} уровня {}, а его максимальный уровень {}",
+                            definition.id,
+                            index + 1,
+                            required,
+                            required_level,
+                            max_level
+                        ));
+                    }
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(BuildingRegistry {
+                definitions: registry,
+            })
+        } else {
+            Err(errors.join("\n"))
+        }
+    }
+
+    /// Описания зданий, поставляемые вместе с игрой
+    pub fn builtin() -> Self {
+        Self::from_ron(BUILTIN_BUILDINGS).expect("встроенные описания зданий некорректны")
+    }
+
+    /// Загружает реестр из строки в формате RON
+    pub fn from_ron(data: &str) -> Result<Self, String> {
+        let file: BuildingFile =
+            ron::from_str(data).map_err(|e| format!("Ошибка разбора RON: {}", e))?;
+        Self::new(file.buildings)
+    }
+
+    /// Загружает реестр из строки в формате JSON
+    pub fn from_json(data: &str) -> Result<Self, String> {
+        let file: BuildingFile =
+            serde_json::from_str(data).map_err(|e| format!("Ошибка разбора JSON: {}", e))?;
+        Self::new(file.buildings)
+    }
+
+    /// Загружает реестр из строки в формате TOML
+    pub fn from_toml(data: &str) -> Result<Self, String> {
+        let file: BuildingFile =
+            toml::from_str(data).map_err(|e| format!("Ошибка разбора TOML: {}", e))?;
+        Self::new(file.buildings)
+    }
+
+    /// Загружает реестр из файла; формат определяется по расширению
+    /// (`.ron`, `.json` или `.toml`)
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let data = fs::read_to_string(path)
+            .map_err(|e| format!("Не удалось прочитать {}: {}", path.display(), e))?;
+
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("ron") => Self::from_ron(&data),
+            Some("json") => Self::from_json(&data),
+            Some("toml") => Self::from_toml(&data),
+            _ => Err(format!("Неизвестный формат файла зданий: {}", path.display())),
+        }
+    }
+
+    /// Описание здания
+    pub fn get(&self, building_type: &BuildingType) -> Option<&BuildingDefinition> {
+        self.definitions.get(building_type)
+    }
+
+    /// Все описания зданий
+    pub fn definitions(&self) -> impl Iterator<Item = &BuildingDefinition> {
+        self.definitions.values()
+    }
+
+    /// Параметры здания указанного уровня
+    pub fn level(&self, building_type: &BuildingType, level: u32) -> Option<&BuildingLevel> {
+        self.get(building_type).and_then(|definition| definition.level(level))
+    }
+
+    /// Сохраняет реестр в формате RON
+    pub fn to_ron(&self) -> Result<String, String> {
+        ron::ser::to_string_pretty(&self.to_file(), ron::ser::PrettyConfig::default())
+            .map_err(|e| format!("Ошибка записи RON: {}", e))
+    }
+
+    /// Сохраняет реестр в формате JSON
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(&self.to_file())
+            .map_err(|e| format!("Ошибка записи JSON: {}", e))
+    }
+
+    /// Сохраняет реестр в формате TOML
+    pub fn to_toml(&self) -> Result<String, String> {
+        toml::to_string_pretty(&self.to_file()).map_err(|e| format!("Ошибка записи TOML: {}", e))
+    }
+
+    fn to_file(&self) -> BuildingFile {
+        BuildingFile {
+            buildings: self.definitions.values().cloned().collect(),
+        }
+    }
+}
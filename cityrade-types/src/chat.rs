@@ -5,6 +5,12 @@ pub struct GlobalChat {
     pub messages: Vec<ChatMessage>,
 }
 
+impl Default for GlobalChat {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl GlobalChat {
     pub fn new() -> GlobalChat {
         GlobalChat {
@@ -1,14 +1,37 @@
 use chrono::{DateTime, Utc};
+use rand::Rng;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use uuid::Uuid;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 use crate::{
-    building::{Building, BuildingType},
+    building::{Building, BuildingError, BuildingType},
+    building_registry::{BuildingDefinition, BuildingRegistry, BuildingRequirements},
+    city_grid::{self, CityGrid, ROAD_COST},
+    clock::GameClock,
+    constants::MAX_BUILDING_LEVEL,
+    construction::{self, ConstructionJob, ConstructionKind, ConstructionQueue},
+    events::{
+        BuildingConstructedEvent, BuildingUpgradedEvent, EventSystem, PopulationChangedEvent,
+        BankruptcyEvent, PolicyLapsedEvent, ResourceShortageEvent, UnrestEvent,
+    },
+    jobs::{self, JobAssignment, JobOverrides},
+    ledger::{EconomicLedger, LedgerSource},
+    policies::CityPolicies,
+    population::{self, Population, PopulationClass, Unrest},
+    production::{self, ProductionModifiers, ProductionStatus},
     resources::{ResourceType, Resource},
+    rng::random_id,
+    storage::{self, StoragePolicy},
+    taxation::{self, FiscalReport, FiscalStatus, TaxPolicy},
+    technology::TechnologyTree,
+    upkeep::{self, UpkeepEntry, UpkeepPriority},
+    world::CitySite,
 };
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Вероятность того, что бунт класса за ход повредит здание
+pub const RIOT_DAMAGE_CHANCE: f32 = 0.25;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Terrain {
     Plain,
     Forest,
@@ -78,9 +101,18 @@ impl Terrain {
 pub struct CityStats {
     pub happiness: u32,      // Счастье населения (влияет на рост)
     pub defense: u32,        // Защита от нападений
-    pub culture: u32,        // Культурный уровень (влияет на технологии)
+    pub culture: u32,        // Культурный уровень (влияет на технологии и границы)
     pub max_population: u32, // Максимальное население
     pub max_buildings: u32,  // Максимальное количество зданий
+    #[serde(default = "default_construction_slots")]
+    pub construction_slots: u32, // Сколько заданий строительства выполняется одновременно
+    /// Модификатор прироста населения в процентах
+    #[serde(default)]
+    pub growth: i32,
+}
+
+fn default_construction_slots() -> u32 {
+    1
 }
 
 impl Default for CityStats {
@@ -91,232 +123,1224 @@ impl Default for CityStats {
             culture: 0,
             max_population: 50,
             max_buildings: 5,
+            construction_slots: default_construction_slots(),
+            growth: 0,
         }
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "CityData")]
 pub struct City {
     pub id: String,
     pub name: String,
     pub owner_id: String,
-    pub population: u32,
+    /// Жители по классам; старые сохранения хранили здесь число жителей
+    pub population: Population,
     pub buildings: HashMap<String, Building>,
+    pub construction: ConstructionQueue,
+    /// Загрузка перерабатывающих зданий за последний ход
+    pub production_status: Vec<ProductionStatus>,
+    /// Журнал содержания зданий за последний ход
+    pub upkeep_ledger: Vec<UpkeepEntry>,
+    /// Экономический журнал: доходы и расходы ресурсов за последние ходы
+    pub ledger: EconomicLedger,
+    /// Занятость жителей на зданиях за последний ход
+    pub jobs: Vec<JobAssignment>,
+    /// Ручная настройка занятости по зданиям
+    pub job_overrides: JobOverrides,
+    /// Порядок обеспечения зданий содержанием при нехватке ресурсов
+    pub upkeep_priority: UpkeepPriority,
     pub resources: Resource,
+    /// Что делать с ресурсами, не поместившимися на склады
+    pub storage_policy: StoragePolicy,
+    /// Налоги, пошлины и содержание зданий из казны
+    pub tax_policy: TaxPolicy,
+    /// Долг казны в золоте
+    pub treasury_debt: u32,
+    /// Итоги налогов за последний ход
+    pub fiscal_report: FiscalReport,
+    /// Политики и указы города
+    pub policies: CityPolicies,
+    /// Накопленные очки культуры на расширение границ
+    pub border_progress: u32,
     pub stats: CityStats,
     pub terrain: Terrain,
+    /// Модификаторы ресурсов по окрестностям города на карте мира;
+    /// если пусто, используются модификаторы местности `terrain`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub terrain_modifiers: BTreeMap<ResourceType, f32>,
+    /// Сетка клеток города с местностью и дорогами; старые сохранения без
+    /// сетки получают сетку, созданную из местности и позиции города
+    pub grid: CityGrid,
     pub position: (i32, i32),
     pub created_at: DateTime<Utc>,
     pub last_updated: DateTime<Utc>,
 }
 
+/// Город в сохранении: старые сохранения могут не содержать сетку
+#[derive(Deserialize)]
+struct CityData {
+    id: String,
+    name: String,
+    owner_id: String,
+    #[serde(deserialize_with = "population::deserialize_population")]
+    population: Population,
+    buildings: HashMap<String, Building>,
+    #[serde(default)]
+    construction: ConstructionQueue,
+    #[serde(default)]
+    production_status: Vec<ProductionStatus>,
+    #[serde(default)]
+    upkeep_ledger: Vec<UpkeepEntry>,
+    #[serde(default)]
+    ledger: EconomicLedger,
+    #[serde(default)]
+    jobs: Vec<JobAssignment>,
+    #[serde(default)]
+    job_overrides: JobOverrides,
+    #[serde(default)]
+    upkeep_priority: UpkeepPriority,
+    resources: Resource,
+    #[serde(default)]
+    storage_policy: StoragePolicy,
+    #[serde(default)]
+    tax_policy: TaxPolicy,
+    #[serde(default)]
+    treasury_debt: u32,
+    #[serde(default)]
+    fiscal_report: FiscalReport,
+    #[serde(default)]
+    policies: CityPolicies,
+    #[serde(default)]
+    border_progress: u32,
+    stats: CityStats,
+    terrain: Terrain,
+    #[serde(default)]
+    terrain_modifiers: BTreeMap<ResourceType, f32>,
+    #[serde(default)]
+    grid: Option<CityGrid>,
+    position: (i32, i32),
+    created_at: DateTime<Utc>,
+    last_updated: DateTime<Utc>,
+}
+
+impl From<CityData> for City {
+    fn from(data: CityData) -> Self {
+        let grid = data
+            .grid
+            .unwrap_or_else(|| CityGrid::generate(&data.terrain, city_grid::grid_seed(data.position)));
+        City {
+            id: data.id,
+            name: data.name,
+            owner_id: data.owner_id,
+            population: data.population,
+            buildings: data.buildings,
+            construction: data.construction,
+            production_status: data.production_status,
+            upkeep_ledger: data.upkeep_ledger,
+            ledger: data.ledger,
+            jobs: data.jobs,
+            job_overrides: data.job_overrides,
+            upkeep_priority: data.upkeep_priority,
+            resources: data.resources,
+            storage_policy: data.storage_policy,
+            tax_policy: data.tax_policy,
+            treasury_debt: data.treasury_debt,
+            fiscal_report: data.fiscal_report,
+            policies: data.policies,
+            border_progress: data.border_progress,
+            stats: data.stats,
+            terrain: data.terrain,
+            terrain_modifiers: data.terrain_modifiers,
+            grid,
+            position: data.position,
+            created_at: data.created_at,
+            last_updated: data.last_updated,
+        }
+    }
+}
+
 impl City {
-    pub fn new(name: String, owner_id: String, terrain: Terrain, position: (i32, i32)) -> City {
+    pub fn new(
+        id: String,
+        name: String,
+        owner_id: String,
+        terrain: Terrain,
+        position: (i32, i32),
+        clock: &GameClock,
+    ) -> City {
+        let now = clock.now();
+        let mut resources = Resource::new();
+        for resource in storage::STORED_RESOURCES {
+            let capacity = storage::base_capacity(&resource);
+            resources.set_capacity(resource, capacity);
+        }
+
         City {
-            id: Uuid::new_v4().to_string(),
+            id,
             name,
             owner_id,
-            population: 10,
+            population: Population::with_total(10),
             buildings: HashMap::new(),
-            resources: Resource::new(),
+            construction: ConstructionQueue::new(),
+            production_status: Vec::new(),
+            upkeep_ledger: Vec::new(),
+            ledger: EconomicLedger::default(),
+            jobs: Vec::new(),
+            job_overrides: JobOverrides::default(),
+            upkeep_priority: UpkeepPriority::default(),
+            resources,
+            storage_policy: StoragePolicy::default(),
+            tax_policy: TaxPolicy::default(),
+            treasury_debt: 0,
+            fiscal_report: FiscalReport::default(),
+            policies: CityPolicies::new(),
+            border_progress: 0,
             stats: CityStats::default(),
+            grid: CityGrid::generate(&terrain, city_grid::grid_seed(position)),
             terrain,
+            terrain_modifiers: BTreeMap::new(),
             position,
-            created_at: Utc::now(),
-            last_updated: Utc::now(),
+            created_at: now,
+            last_updated: now,
         }
     }
 
-    pub fn update(&mut self) {
+    /// Город на месте, найденном на карте мира (`WorldMap::survey_site`):
+    /// местность и модификаторы ресурсов берутся из окрестностей
+    pub fn from_site(id: String, name: String, owner_id: String, site: &CitySite, clock: &GameClock) -> City {
+        let mut city = City::new(id, name, owner_id, site.terrain.clone(), site.position, clock);
+        city.terrain_modifiers = site.modifiers.clone();
+        city
+    }
+
+    /// Модификаторы ресурсов местности города
+    pub fn resource_modifiers(&self) -> BTreeMap<ResourceType, f32> {
+        if self.terrain_modifiers.is_empty() {
+            self.terrain.resource_modifier().into_iter().collect()
+        } else {
+            self.terrain_modifiers.clone()
+        }
+    }
+
+    pub fn update<R: Rng + ?Sized>(
+        &mut self,
+        registry: &BuildingRegistry,
+        modifiers: &ProductionModifiers,
+        clock: &GameClock,
+        rng: &mut R,
+        events: &EventSystem,
+    ) {
+        let old_population = self.population.total();
+        self.ledger.begin_turn(clock.turn());
+
+        // Вместимость складов зависит от построенных зданий
+        self.update_storage_capacity(registry);
+
+        // Политики оплачиваются до производства; неоплаченные отменяются
+        self.pay_policy_upkeep(events);
+
         // Обновляем ресурсы на основе зданий
-        self.update_resource_production();
+        self.update_resource_production(registry, modifiers);
+        for entry in self.starved_buildings() {
+            let mut event = ResourceShortageEvent::new(
+                self.id.clone(),
+                entry.building_id.clone(),
+                entry.building_type.clone(),
+                entry.missing(),
+                entry.efficiency,
+            );
+            events.call_event(&mut event);
+        }
+
+        // Часть скоропортящихся запасов пропадает
+        for (resource, amount) in self.resources.apply_spoilage() {
+            self.ledger.record(LedgerSource::Spoilage, resource, -(amount as i64));
+        }
+
+        // Налоги, содержание зданий из казны и долг
+        self.collect_taxes(events);
 
         // Обновляем статистику
-        self.update_stats();
+        self.update_stats(registry);
 
         // Обновляем население
-        self.update_population();
+        self.update_population(rng);
+        let new_population = self.population.total();
+        if new_population != old_population {
+            let mut event = PopulationChangedEvent::new(self.id.clone(), old_population, new_population);
+            events.call_event(&mut event);
+        }
+
+        // Недовольные классы бастуют и бунтуют
+        self.resolve_unrest(rng, events);
 
         // Обновляем временную метку
-        self.last_updated = Utc::now();
+        self.last_updated = clock.now();
     }
 
-    pub fn update_resource_production(&mut self) {
+    pub fn update_resource_production(&mut self, registry: &BuildingRegistry, modifiers: &ProductionModifiers) {
+        // Здания в порядке очереди на содержание, при равном приоритете — по ID,
+        // чтобы распределение ресурсов не зависело от хеширования
+        let mut building_ids: Vec<String> = self.buildings.keys().cloned().collect();
+        building_ids.sort_by_cached_key(|id| {
+            (self.upkeep_priority.rank(&self.buildings[id].building_type), id.clone())
+        });
+
+        // Жители занимают рабочие места в том же порядке;
+        // бастующие классы на работу не выходят
+        let assignments = self.assign_jobs(registry, &building_ids);
+        let striking = self.population.striking_classes();
+
+        // Сначала здания получают содержание из накопленных запасов;
+        // недополучившие работают с пониженной отдачей
+        let mut upkeep_entries = Vec::new();
+        for building_id in &building_ids {
+            let building = &self.buildings[building_id];
+            let Some(level) = registry.level(&building.building_type, building.level) else {
+                continue;
+            };
+            if !level.consumption.is_empty() {
+                let entry = upkeep::pay_upkeep(
+                    building_id,
+                    &building.building_type,
+                    &level.consumption,
+                    &mut self.resources,
+                );
+                for (resource, amount) in &entry.supplied {
+                    self.ledger.record(
+                        LedgerSource::Upkeep {
+                            building_id: building_id.clone(),
+                            building_type: building.building_type.clone(),
+                        },
+                        resource.clone(),
+                        -(*amount as i64),
+                    );
+                }
+                upkeep_entries.push(entry);
+            }
+        }
+        // Отдача здания: доля полученного содержания, умноженная на долю
+        // занятых рабочих мест
+        let efficiency = |building_id: &str| {
+            let upkeep = upkeep_entries
+                .iter()
+                .find(|entry| entry.building_id == building_id)
+                .map_or(1.0, |entry| entry.efficiency);
+            let staffing = assignments
+                .iter()
+                .find(|assignment| assignment.building_id == building_id)
+                .map_or(1.0, |assignment| assignment.working_staffing(&striking));
+            upkeep * staffing
+        };
+
         // Сбрасываем производство к нулю
-        let mut production_rates = HashMap::new();
-        for resource_type in [
-            ResourceType::Gold,
-            ResourceType::Wood,
-            ResourceType::Stone,
-            ResourceType::Food,
-            ResourceType::Iron,
-            ResourceType::Crystal,
-            ResourceType::Energy,
+        let mut production_rates: HashMap<ResourceType, i32> = self
+            .resources
+            .get_all_production_rates()
+            .into_iter()
+            .map(|(resource, _)| (resource, 0))
+            .collect();
+
+        // Базовое производство
+        for (resource_type, base_rate) in [
+            (ResourceType::Gold, 5),
+            (ResourceType::Wood, 3),
+            (ResourceType::Stone, 2),
+            (ResourceType::Food, 8),
         ] {
-            // Базовое производство
-            let base_rate = match resource_type {
-                ResourceType::Gold => 5,
-                ResourceType::Wood => 3,
-                ResourceType::Stone => 2,
-                ResourceType::Food => 8,
-                _ => 0,
-            };
+            self.ledger.record(LedgerSource::BaseProduction, resource_type.clone(), base_rate as i64);
             production_rates.insert(resource_type, base_rate);
         }
 
+        // Обрабатываемые клетки территории
+        for (resource, amount) in &modifiers.worked_tiles {
+            self.ledger.record(LedgerSource::WorkedTiles, resource.clone(), *amount as i64);
+            *production_rates.entry(resource.clone()).or_insert(0) += amount;
+        }
+
         // Добавляем производство от зданий
-        for building in self.buildings.values() {
-            for (resource, amount) in building.production_effect() {
-                *production_rates.entry(resource).or_insert(0) += amount;
+        for building_id in &building_ids {
+            let building = &self.buildings[building_id];
+            if let Some(level) = registry.level(&building.building_type, building.level) {
+                let efficiency = efficiency(building_id);
+                let adjacency = self.adjacency_bonus(building_id, registry) as i32;
+                for (resource, amount) in &level.production {
+                    let produced = (*amount as f32 * efficiency) as i32;
+                    let bonus = produced * adjacency / 100;
+                    let deposit = if building.building_type.extracts_deposits() {
+                        produced * modifiers.deposit_bonus(resource) / 100
+                    } else {
+                        0
+                    };
+                    self.ledger.record(
+                        LedgerSource::Building {
+                            building_id: building_id.clone(),
+                            building_type: building.building_type.clone(),
+                        },
+                        resource.clone(),
+                        produced as i64,
+                    );
+                    self.ledger.record(
+                        LedgerSource::Adjacency {
+                            building_id: building_id.clone(),
+                            building_type: building.building_type.clone(),
+                        },
+                        resource.clone(),
+                        bonus as i64,
+                    );
+                    if deposit != 0 {
+                        self.ledger.record(
+                            LedgerSource::Deposit {
+                                building_id: building_id.clone(),
+                                building_type: building.building_type.clone(),
+                            },
+                            resource.clone(),
+                            deposit as i64,
+                        );
+                    }
+                    *production_rates.entry(resource.clone()).or_insert(0) += produced + bonus + deposit;
+                }
             }
         }
 
         // Применяем модификаторы местности
-        let terrain_modifiers = self.terrain.resource_modifier();
-        for (resource, modifier) in terrain_modifiers {
+        for (resource, modifier) in self.resource_modifiers() {
             if let Some(amount) = production_rates.get_mut(&resource) {
-                *amount = (*amount as f32 * modifier) as i32;
+                let modified = (*amount as f32 * modifier) as i32;
+                self.ledger.record(LedgerSource::Terrain, resource.clone(), (modified - *amount) as i64);
+                *amount = modified;
             }
         }
 
-        // Устанавливаем новые значения производства
-        for (resource, rate) in production_rates {
-            self.resources.set_production_rate(resource, rate);
+        // Бонусы фракции, технологий, состава населения и политик
+        // (в процентах от производства с учетом местности)
+        let policy_effects = self.policies.effects();
+        let mut resources_by_type: Vec<ResourceType> = production_rates.keys().cloned().collect();
+        resources_by_type.sort();
+        for resource in resources_by_type {
+            let Some(amount) = production_rates.get_mut(&resource) else {
+                continue;
+            };
+            let base = *amount;
+            let faction = (base * modifiers.faction_bonus(&resource) / 100).max(-base);
+            let technology = (base * modifiers.technology_bonus(&resource) / 100).max(-(base + faction));
+            let population_percent = (self.population.get_production_bonus(&resource) * 100.0).round() as i32;
+            let population = base * population_percent / 100;
+            let policy_percent = policy_effects.production.get(&resource).copied().unwrap_or(0);
+            let policy = (base * policy_percent / 100).max(-(base + faction + technology + population));
+            self.ledger.record(LedgerSource::FactionBonus, resource.clone(), faction as i64);
+            self.ledger.record(LedgerSource::TechnologyBonus, resource.clone(), technology as i64);
+            self.ledger.record(LedgerSource::PopulationBonus, resource.clone(), population as i64);
+            self.ledger.record(LedgerSource::PolicyBonus, resource.clone(), policy as i64);
+            *amount = base + faction + technology + population + policy;
+        }
+
+        // Скорость производства показывается за вычетом уплаченного содержания
+        let mut upkeep_paid: HashMap<ResourceType, i32> = HashMap::new();
+        for entry in &upkeep_entries {
+            for (resource, amount) in &entry.supplied {
+                *upkeep_paid.entry(resource.clone()).or_insert(0) += *amount as i32;
+            }
+        }
+        for (resource, rate) in &production_rates {
+            let paid = upkeep_paid.get(resource).copied().unwrap_or(0);
+            self.resources.set_production_rate(resource.clone(), rate - paid);
         }
 
         // Применяем производство к текущим ресурсам
-        self.resources.update_production();
+        for (resource, rate) in production_rates {
+            if rate > 0 {
+                self.resources.add(&resource, rate as u32);
+            }
+        }
+
+        // Перерабатывающие здания расходуют входы уже после добычи за ход
+        let mut statuses = Vec::new();
+        for building_id in &building_ids {
+            let building = &self.buildings[building_id];
+            let Some(level) = registry.level(&building.building_type, building.level) else {
+                continue;
+            };
+            if level.recipes.is_empty() {
+                continue;
+            }
+            let status = production::run_recipes(
+                building_id,
+                &building.building_type,
+                &level.recipes,
+                &mut self.resources,
+                efficiency(building_id),
+            );
+            let source = LedgerSource::Recipe {
+                building_id: building_id.clone(),
+                building_type: building.building_type.clone(),
+            };
+            for (resource, amount) in &status.consumed {
+                self.ledger.record(source.clone(), resource.clone(), -(*amount as i64));
+            }
+            for (resource, amount) in &status.produced {
+                self.ledger.record(source.clone(), resource.clone(), *amount as i64);
+            }
+            statuses.push(status);
+        }
+        self.production_status = statuses;
+        self.upkeep_ledger = upkeep_entries;
+        self.jobs = assignments;
+    }
+
+    /// Распределяет жителей по рабочим местам зданий в порядке `building_ids`
+    fn assign_jobs(&self, registry: &BuildingRegistry, building_ids: &[String]) -> Vec<JobAssignment> {
+        let buildings: Vec<_> = building_ids
+            .iter()
+            .filter_map(|building_id| {
+                let building = &self.buildings[building_id];
+                let level = registry.level(&building.building_type, building.level)?;
+                if level.workers.is_empty() {
+                    return None;
+                }
+                Some((building_id.clone(), building.building_type.clone(), level.workers.clone()))
+            })
+            .collect();
+        jobs::assign_jobs(&buildings, &self.population, &self.job_overrides)
+    }
+
+    /// Задает число жителей класса, работающих на здании; остальные классы
+    /// распределяются автоматически. Пересчет — в следующем ходу.
+    pub fn set_job_override(&mut self, building_id: &str, class: PopulationClass, workers: u32) -> Result<(), String> {
+        if !self.buildings.contains_key(building_id) {
+            return Err("Здание не найдено".to_string());
+        }
+        self.job_overrides.set(building_id, class, workers);
+        Ok(())
+    }
+
+    /// Возвращает здание к автоматическому распределению жителей
+    pub fn clear_job_override(&mut self, building_id: &str) {
+        self.job_overrides.clear(building_id);
+    }
+
+    /// Доля занятых рабочих мест здания в прошлом ходу
+    pub fn staffing(&self, building_id: &str) -> f32 {
+        self.jobs
+            .iter()
+            .find(|assignment| assignment.building_id == building_id)
+            .map_or(1.0, JobAssignment::staffing)
+    }
+
+    /// Жители без работы по классам
+    pub fn unemployment(&self) -> BTreeMap<PopulationClass, u32> {
+        jobs::unemployed(&self.population, &self.jobs)
+    }
+
+    /// Общее число безработных
+    pub fn unemployed_total(&self) -> u32 {
+        self.unemployment().values().sum()
+    }
+
+    /// Здания, недополучившие содержание в прошлом ходу
+    pub fn starved_buildings(&self) -> Vec<&UpkeepEntry> {
+        self.upkeep_ledger
+            .iter()
+            .filter(|entry| entry.is_starved())
+            .collect()
     }
 
-    pub fn update_stats(&mut self) {
-        // Сбрасываем статистику к базовым значениям
-        self.stats = CityStats::default();
+    /// Перерабатывающие здания, которые в прошлом ходу работали
+    /// не на полную мощность из-за нехватки входов
+    pub fn production_bottlenecks(&self) -> Vec<&ProductionStatus> {
+        self.production_status
+            .iter()
+            .filter(|status| status.is_bottleneck())
+            .collect()
+    }
+
+    /// Пересчитывает вместимость складов: базовая вместимость плюс
+    /// вместимость складских зданий. Запасы сверх новой вместимости
+    /// переходят в излишки.
+    pub fn update_storage_capacity(&mut self, registry: &BuildingRegistry) {
+        let mut capacity: HashMap<ResourceType, u32> = HashMap::new();
+        for resource in storage::STORED_RESOURCES {
+            if let Some(base) = storage::base_capacity(&resource) {
+                capacity.insert(resource, base);
+            }
+        }
 
-        // Обновляем статистику на основе зданий
         for building in self.buildings.values() {
-            match building.building_type {
-                BuildingType::Residential => {
-                    self.stats.max_population += 10 * building.level;
-                }
-                BuildingType::Temple => {
-                    self.stats.happiness += 5 * building.level;
-                    self.stats.culture += 3 * building.level;
-                }
-                BuildingType::Wall => {
-                    self.stats.defense += 20 * building.level;
-                }
-                BuildingType::Laboratory => {
-                    self.stats.culture += 5 * building.level;
+            if let Some(level) = registry.level(&building.building_type, building.level) {
+                for (resource, amount) in &level.storage {
+                    let total = capacity.entry(resource.clone()).or_insert(0);
+                    *total = total.saturating_add(*amount);
                 }
-                BuildingType::Barracks => {
-                    self.stats.defense += 10 * building.level;
-                    self.stats.happiness -= 2 * building.level;
+            }
+        }
+
+        for (resource, amount) in capacity {
+            self.resources.set_capacity(resource, Some(amount));
+        }
+    }
+
+    pub fn update_stats(&mut self, registry: &BuildingRegistry) {
+        // Суммируем вклад зданий в статистику
+        let base = CityStats::default();
+        let mut happiness = base.happiness as i64;
+        let mut defense = base.defense as i64;
+        let mut culture = base.culture as i64;
+        let mut max_population = base.max_population as i64;
+        let mut construction_slots = base.construction_slots as i64;
+
+        for building in self.buildings.values() {
+            if let Some(level) = registry.level(&building.building_type, building.level) {
+                // Здание без содержания не влияет на счастье, защиту и культуру
+                let disabled = self
+                    .upkeep_ledger
+                    .iter()
+                    .any(|entry| entry.building_id == building.id && entry.is_disabled());
+                if !disabled {
+                    happiness += level.stats.happiness as i64;
+                    defense += level.stats.defense as i64;
+                    culture += level.stats.culture as i64;
                 }
-                _ => {}
+                max_population += level.stats.max_population as i64;
+                construction_slots += level.stats.construction_slots as i64;
             }
         }
 
+        // Нехватка содержания раздражает жителей, как и налоги с долгами
+        happiness -= upkeep::happiness_penalty(&self.upkeep_ledger) as i64;
+        happiness += self.tax_policy.happiness_modifier(&self.population) as i64;
+        happiness -= taxation::debt_happiness_penalty(self.treasury_debt) as i64;
+
+        // Политики города
+        let policy_effects = self.policies.effects();
+        happiness += policy_effects.happiness as i64;
+        defense += policy_effects.defense as i64;
+        culture += policy_effects.culture as i64;
+
+        let clamp = |value: i64| value.clamp(0, u32::MAX as i64) as u32;
+        self.stats = CityStats {
+            happiness: clamp(happiness),
+            defense: clamp(defense),
+            culture: clamp(culture),
+            max_population: clamp(max_population),
+            construction_slots: clamp(construction_slots).max(1),
+            growth: self.tax_policy.growth_modifier() + policy_effects.growth,
+            ..base
+        };
+
         // Увеличиваем максимальное количество зданий на основе населения
-        self.stats.max_buildings = 5 + (self.population / 20);
+        self.stats.max_buildings = 5 + (self.population.total() / 20);
     }
 
-    pub fn update_population(&mut self) {
-        // Рост населения зависит от счастья и наличия еды
-        let food = self.resources.get(&ResourceType::Food);
+    pub fn update_population<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        // Жители потребляют еду (солдатам и знати нужно больше),
+        // знать — еще и роскошные товары. При нормировании еды выдают
+        // меньше: голода нет, но жители недовольны.
+        let mut demand = self.population.demand();
+        if let Some(ration) = self.policies.effects().food_ration
+            && let Some(food) = demand.get_mut(&ResourceType::Food)
+        {
+            *food = *food * ration / 100;
+        }
+        let mut supplied = BTreeMap::new();
+        for (resource, needed) in &demand {
+            let consumed = (*needed).min(self.resources.get(resource));
+            self.resources.subtract(resource, consumed);
+            self.ledger.record(LedgerSource::Consumption, resource.clone(), -(consumed as i64));
+            supplied.insert(resource.clone(), consumed);
+        }
+        self.population.apply_supply(&supplied);
 
         // Если еды недостаточно, уменьшаем население
-        if food < self.population {
+        let food_needed = demand.get(&ResourceType::Food).copied().unwrap_or(0);
+        if supplied.get(&ResourceType::Food).copied().unwrap_or(0) < food_needed {
             self.decrease_population(1);
             self.stats.happiness = self.stats.happiness.saturating_sub(5);
         } else {
-            // Иначе, увеличиваем население с вероятностью, зависящей от счастья
-            let growth_chance = (self.stats.happiness as f32) / 100.0;
+            // Иначе, увеличиваем население с вероятностью, зависящей от счастья;
+            // знать и ученые ускоряют рост
+            let growth_bonus = self.population.get_production_bonus(&ResourceType::Population);
+            let growth_modifier = (100 + self.stats.growth).max(0) as f32 / 100.0;
+            let growth_chance = (self.stats.happiness as f32) / 100.0 * (1.0 + growth_bonus) * growth_modifier;
 
-            if rand::random::<f32>() < growth_chance && self.population < self.stats.max_population
+            if rng.random::<f32>() < growth_chance
+                && self.population.total() < self.stats.max_population
             {
                 self.increase_population(1);
             }
         }
+
+        // Безработные учатся в школах и торгуют на рынках
+        let mut building_levels: BTreeMap<BuildingType, u32> = BTreeMap::new();
+        for building in self.buildings.values() {
+            *building_levels.entry(building.building_type.clone()).or_insert(0) += building.level;
+        }
+        let idle = self.unemployment();
+        self.population.apply_mobility(&building_levels, &idle);
+    }
+
+    /// Собирает подоходный налог, платит содержание зданий из казны и
+    /// обслуживает долг. Пошлины взимаются с торговых маршрутов (`levy_tariff`). Нехватка золота на содержание становится
+    /// долгом, на долг начисляются проценты, а доходы в первую очередь идут
+    /// на его погашение. При переходе в банкротство вызывается `BankruptcyEvent`.
+    pub fn collect_taxes(&mut self, events: &EventSystem) {
+        let was_bankrupt = self.is_bankrupt();
+        let mut report = FiscalReport {
+            interest: taxation::debt_interest(self.treasury_debt),
+            ..FiscalReport::default()
+        };
+        self.treasury_debt = self.treasury_debt.saturating_add(report.interest);
+
+        report.income_tax = self.tax_policy.income_tax_revenue(&self.population);
+        for (class, amount) in &report.income_tax {
+            self.resources.add(&ResourceType::Gold, *amount);
+            self.ledger.record(LedgerSource::IncomeTax(class.clone()), ResourceType::Gold, *amount as i64);
+        }
+
+        let building_levels: u32 = self.buildings.values().map(|building| building.level).sum();
+        report.maintenance = building_levels.saturating_mul(self.tax_policy.maintenance);
+        let paid = report.maintenance.min(self.resources.get(&ResourceType::Gold));
+        self.resources.subtract(&ResourceType::Gold, paid);
+        self.ledger.record(LedgerSource::Maintenance, ResourceType::Gold, -(paid as i64));
+        self.treasury_debt = self.treasury_debt.saturating_add(report.maintenance - paid);
+
+        report.repaid = self.treasury_debt.min(self.resources.get(&ResourceType::Gold));
+        self.resources.subtract(&ResourceType::Gold, report.repaid);
+        self.ledger.record(LedgerSource::DebtRepayment, ResourceType::Gold, -(report.repaid as i64));
+        self.treasury_debt -= report.repaid;
+
+        report.debt = self.treasury_debt;
+        self.fiscal_report = report;
+
+        if self.is_bankrupt() && !was_bankrupt {
+            let mut event = BankruptcyEvent::new(self.id.clone(), self.treasury_debt);
+            events.call_event(&mut event);
+        }
+    }
+
+    /// Пошлина с оборота `deal` золота на рынке города.
+    /// При свободной торговле пошлина не взимается.
+    pub fn tariff_on(&self, deal: u32) -> u32 {
+        if self.policies.effects().disables_tariffs {
+            return 0;
+        }
+        self.tax_policy.tariff_on(deal)
+    }
+
+    /// Взимает пошлину с оборота `deal` золота, который проходит через рынок
+    /// города, но оплачивается не им (торговые маршруты). Пошлина поступает в
+    /// казну; то, что в казну не поместилось, уходит в излишки, как и любой
+    /// другой доход. Возвращает пошлину.
+    pub fn levy_tariff(&mut self, deal: u32) -> u32 {
+        let tariff = self.tariff_on(deal);
+        if tariff > 0 {
+            self.resources.add(&ResourceType::Gold, tariff);
+            self.ledger.record(LedgerSource::Tariff, ResourceType::Gold, tariff as i64);
+            self.fiscal_report.tariffs += tariff;
+        }
+        tariff
+    }
+
+    /// Состояние казны
+    pub fn fiscal_status(&self) -> FiscalStatus {
+        taxation::fiscal_status(self.treasury_debt)
+    }
+
+    /// Банкрот ли город: строительство и улучшения остановлены
+    pub fn is_bankrupt(&self) -> bool {
+        self.fiscal_status() == FiscalStatus::Bankrupt
+    }
+
+    /// Оплачивает действующие политики. Политика, которую город не может
+    /// оплатить полностью, отменяется без задержки и вызывает `PolicyLapsedEvent`.
+    pub fn pay_policy_upkeep(&mut self, events: &EventSystem) {
+        let active: Vec<_> = self.policies.active().copied().collect();
+        for policy in active {
+            let upkeep = policy.upkeep();
+            if self.resources.pay(&upkeep) {
+                for (resource, amount) in upkeep {
+                    self.ledger.record(LedgerSource::Policy(policy), resource, -(amount as i64));
+                }
+            } else {
+                self.policies.lapse(&policy);
+                let mut event = PolicyLapsedEvent::new(self.id.clone(), policy);
+                events.call_event(&mut event);
+            }
+        }
+    }
+
+    /// Вызывает `UnrestEvent` для каждого бастующего или бунтующего класса.
+    /// Бунт с вероятностью `RIOT_DAMAGE_CHANCE` понижает уровень случайного
+    /// здания (здания первого уровня и здания в очереди улучшения не страдают).
+    pub fn resolve_unrest<R: Rng + ?Sized>(&mut self, rng: &mut R, events: &EventSystem) {
+        for class in self.population.striking_classes() {
+            let unrest = self.population.unrest(&class);
+            let mut damaged_building = None;
+
+            // При военном положении бунты подавляются до погромов
+            if unrest == Unrest::Riot
+                && !self.policies.effects().suppresses_riots
+                && rng.random::<f32>() < RIOT_DAMAGE_CHANCE
+            {
+                let mut targets: Vec<&String> = self
+                    .buildings
+                    .values()
+                    // Улучшение в очереди рассчитано на текущий уровень здания
+                    .filter(|building| building.level > 1 && self.construction.get(&building.id).is_none())
+                    .map(|building| &building.id)
+                    .collect();
+                targets.sort();
+                if !targets.is_empty() {
+                    let target = targets[rng.random_range(0..targets.len())].clone();
+                    if let Some(building) = self.buildings.get_mut(&target) {
+                        building.level -= 1;
+                    }
+                    damaged_building = Some(target);
+                }
+            }
+
+            let mut event = UnrestEvent::new(self.id.clone(), class, unrest, damaged_building);
+            events.call_event(&mut event);
+        }
     }
 
-    pub fn add_building(
+    pub fn add_building<R: Rng + ?Sized>(
         &mut self,
         building_type: BuildingType,
         name: String,
         position: (i32, i32),
-    ) -> Result<String, String> {
-        // Проверка, не превышено ли максимальное количество зданий
-        if self.buildings.len() >= self.stats.max_buildings as usize {
-            return Err("Достигнут предел количества зданий".to_string());
+        registry: &BuildingRegistry,
+        technologies: &TechnologyTree,
+        rng: &mut R,
+    ) -> Result<String, BuildingError> {
+        if self.is_bankrupt() {
+            return Err(BuildingError::Bankrupt { debt: self.treasury_debt });
         }
 
-        // Проверка, нет ли уже здания в этой позиции
-        for building in self.buildings.values() {
-            if building.position == position {
-                return Err("В этой позиции уже есть здание".to_string());
-            }
+        let definition = registry
+            .get(&building_type)
+            .ok_or_else(|| BuildingError::UnknownBuildingType(building_type.clone()))?;
+
+        // Проверка местности
+        if !definition.allows_terrain(&self.terrain) {
+            return Err(BuildingError::TerrainNotAllowed {
+                building_type,
+                terrain: self.terrain.clone(),
+            });
+        }
+
+        // Проверка, не превышено ли максимальное количество зданий (с учетом строящихся)
+        if self.buildings.len() + self.construction.pending_buildings() >= self.stats.max_buildings as usize {
+            return Err(BuildingError::BuildingLimitReached {
+                limit: self.stats.max_buildings,
+            });
         }
 
+        // Проверка клетки: свободна, подходит по местности, соседям и дорогам
+        self.check_placement(definition, position)?;
+
+        // Проверка условий постройки
+        let first_level = definition
+            .level(1)
+            .ok_or_else(|| BuildingError::UnknownBuildingType(building_type.clone()))?;
+        self.check_requirements(&first_level.requirements, technologies)?;
+
         // Проверка, хватает ли ресурсов
-        let costs = building_type.base_cost();
+        let costs = first_level.cost_list();
         if !self.resources.can_afford(&costs) {
-            return Err("Недостаточно ресурсов".to_string());
+            return Err(BuildingError::NotEnoughResources { cost: costs });
         }
 
         // Снимаем ресурсы
-        self.resources.pay(&costs);
+        self.pay_construction(&costs);
 
-        // Создаем новое здание
-        let id = Uuid::new_v4().to_string();
-        let building = Building::new(id.clone(), name, building_type, position);
-
-        // Добавляем здание
-        self.buildings.insert(id.clone(), building);
+        // Ставим здание в очередь строительства; ID здание получит сразу
+        let id = random_id(rng);
+        self.construction.push(ConstructionJob::new(
+            id.clone(),
+            building_type,
+            ConstructionKind::Build { name, position },
+            1,
+            costs,
+            first_level.build_time,
+        ));
 
         Ok(id)
     }
 
-    pub fn upgrade_building(&mut self, building_id: &str) -> Result<(), String> {
+    pub fn upgrade_building(
+        &mut self,
+        building_id: &str,
+        registry: &BuildingRegistry,
+        technologies: &TechnologyTree,
+    ) -> Result<(), BuildingError> {
+        if self.is_bankrupt() {
+            return Err(BuildingError::Bankrupt { debt: self.treasury_debt });
+        }
+
         // Проверяем, существует ли здание
-        let building = match self.buildings.get(building_id) {
-            Some(b) => b,
-            None => return Err("Здание не найдено".to_string()),
-        };
+        let building = self
+            .buildings
+            .get(building_id)
+            .ok_or(BuildingError::BuildingNotFound)?;
+
+        // Проверяем, не улучшается ли здание уже
+        if self.construction.get(building_id).is_some() {
+            return Err(BuildingError::AlreadyUpgrading);
+        }
+
+        // Проверяем, есть ли следующий уровень
+        let definition = registry
+            .get(&building.building_type)
+            .ok_or_else(|| BuildingError::UnknownBuildingType(building.building_type.clone()))?;
+        let next_level = definition
+            .level(building.level + 1)
+            .filter(|_| building.level < MAX_BUILDING_LEVEL)
+            .ok_or_else(|| BuildingError::MaxLevelReached {
+                building_type: building.building_type.clone(),
+                max_level: definition.max_level.min(MAX_BUILDING_LEVEL),
+            })?;
+
+        // Проверяем условия улучшения
+        self.check_requirements(&next_level.requirements, technologies)?;
 
         // Проверяем, хватает ли ресурсов
-        let costs = building.upgrade_cost();
+        let costs = next_level.cost_list();
         if !self.resources.can_afford(&costs) {
-            return Err("Недостаточно ресурсов".to_string());
+            return Err(BuildingError::NotEnoughResources { cost: costs });
         }
 
-        // Снимаем ресурсы
-        self.resources.pay(&costs);
+        let job = ConstructionJob::new(
+            building_id.to_string(),
+            building.building_type.clone(),
+            ConstructionKind::Upgrade,
+            building.level + 1,
+            costs,
+            next_level.build_time,
+        );
+
+        // Снимаем ресурсы и ставим улучшение в очередь строительства
+        self.pay_construction(&job.cost);
+        self.construction.push(job);
+
+        Ok(())
+    }
+
+    /// Можно ли поставить здание на клетку `position`: клетка свободна,
+    /// ее местность и соседи отвечают правилам размещения из реестра
+    pub fn can_place(
+        &self,
+        building_type: &BuildingType,
+        position: (i32, i32),
+        registry: &BuildingRegistry,
+    ) -> Result<(), BuildingError> {
+        let definition = registry
+            .get(building_type)
+            .ok_or_else(|| BuildingError::UnknownBuildingType(building_type.clone()))?;
+        self.check_placement(definition, position)
+    }
+
+    /// Клетки, на которые можно поставить здание, по возрастанию координат.
+    /// Технологии, стоимость и предел зданий не проверяются.
+    pub fn valid_build_spots(&self, building_type: &BuildingType, registry: &BuildingRegistry) -> Vec<(i32, i32)> {
+        let Some(definition) = registry.get(building_type) else {
+            return Vec::new();
+        };
+        let connected = self.grid.connected_roads();
+        self.grid
+            .positions()
+            .filter(|position| self.check_placement_with(definition, *position, &connected).is_ok())
+            .collect()
+    }
+
+    /// Прокладывает дорогу по клетке за `ROAD_COST` камня
+    pub fn build_road(&mut self, position: (i32, i32)) -> Result<(), BuildingError> {
+        if self.is_bankrupt() {
+            return Err(BuildingError::Bankrupt { debt: self.treasury_debt });
+        }
+        let terrain = self.grid.terrain(position).ok_or(BuildingError::OutOfBounds(position))?;
+        if *terrain == Terrain::Water {
+            return Err(BuildingError::RoadNotAllowed(terrain.clone()));
+        }
+        if self.is_occupied(position) {
+            return Err(BuildingError::PositionOccupied(position));
+        }
+
+        let costs = vec![(ResourceType::Stone, ROAD_COST)];
+        if !self.resources.can_afford(&costs) {
+            return Err(BuildingError::NotEnoughResources { cost: costs });
+        }
+        self.pay_construction(&costs);
+        self.grid.set_road(position, true);
+        Ok(())
+    }
+
+    /// Убирает дорогу с клетки (без возврата камня); площадь в центре убрать нельзя
+    pub fn remove_road(&mut self, position: (i32, i32)) -> bool {
+        if position == city_grid::CITY_CENTER || !self.grid.has_road(position) {
+            return false;
+        }
+        self.grid.set_road(position, false);
+        true
+    }
+
+    /// Занята ли клетка зданием, строительством или дорогой
+    fn is_occupied(&self, position: (i32, i32)) -> bool {
+        self.grid.has_road(position)
+            || self.buildings.values().any(|building| building.position == position)
+            || self.construction.is_position_reserved(position)
+    }
 
-        // Улучшаем здание
-        let building = self.buildings.get_mut(building_id).unwrap();
-        building.upgrade();
+    fn check_placement(&self, definition: &BuildingDefinition, position: (i32, i32)) -> Result<(), BuildingError> {
+        self.check_placement_with(definition, position, &self.grid.connected_roads())
+    }
+
+    fn check_placement_with(
+        &self,
+        definition: &BuildingDefinition,
+        position: (i32, i32),
+        connected_roads: &BTreeSet<(i32, i32)>,
+    ) -> Result<(), BuildingError> {
+        let terrain = self.grid.terrain(position).ok_or(BuildingError::OutOfBounds(position))?;
+        if self.is_occupied(position) {
+            return Err(BuildingError::PositionOccupied(position));
+        }
+
+        let rules = &definition.placement;
+        let tile_allowed = if rules.tile.is_empty() {
+            *terrain != Terrain::Water
+        } else {
+            rules.tile.contains(terrain)
+        };
+        if !tile_allowed {
+            return Err(BuildingError::TileNotAllowed {
+                building_type: definition.id.clone(),
+                terrain: terrain.clone(),
+            });
+        }
+
+        if !rules.adjacent.is_empty() && !self.grid.is_adjacent_to(position, &rules.adjacent) {
+            return Err(BuildingError::AdjacentTerrainRequired {
+                building_type: definition.id.clone(),
+                terrain: rules.adjacent.clone(),
+            });
+        }
+
+        if rules.road
+            && !self
+                .grid
+                .neighbors(position)
+                .iter()
+                .any(|neighbor| connected_roads.contains(neighbor))
+        {
+            return Err(BuildingError::RoadRequired);
+        }
+
+        Ok(())
+    }
+
+    /// Бонус соседства здания в процентах: сумма бонусов из реестра
+    /// за каждое здание на соседних клетках
+    pub fn adjacency_bonus(&self, building_id: &str, registry: &BuildingRegistry) -> u32 {
+        let Some(building) = self.buildings.get(building_id) else {
+            return 0;
+        };
+        let Some(definition) = registry.get(&building.building_type) else {
+            return 0;
+        };
+        if definition.adjacency.is_empty() {
+            return 0;
+        }
+
+        self.grid
+            .neighbors(building.position)
+            .into_iter()
+            .flat_map(|neighbor| self.buildings.values().filter(move |other| other.position == neighbor))
+            .filter_map(|neighbor| definition.adjacency.get(&neighbor.building_type))
+            .sum()
+    }
+
+    /// Сколько ресурсов добыли за текущий ход здания, работающие
+    /// с месторождениями (`BuildingType::extracts_deposits`)
+    pub fn deposit_extraction(&self) -> BTreeMap<ResourceType, u32> {
+        let mut extracted = BTreeMap::new();
+        let Some(turn) = self.ledger.current() else {
+            return extracted;
+        };
+        for entry in &turn.entries {
+            if let LedgerSource::Building { building_type, .. } | LedgerSource::Deposit { building_type, .. } =
+                &entry.source
+                && building_type.extracts_deposits()
+                && entry.amount > 0
+            {
+                *extracted.entry(entry.resource.clone()).or_insert(0) += entry.amount as u32;
+            }
+        }
+        extracted
+    }
+
+    /// Проверяет условия постройки или улучшения здания.
+    /// Возвращает первое невыполненное условие: технологии, другие здания, население.
+    pub fn check_requirements(
+        &self,
+        requirements: &BuildingRequirements,
+        technologies: &TechnologyTree,
+    ) -> Result<(), BuildingError> {
+        if let Some(technology) = requirements
+            .technologies
+            .iter()
+            .find(|technology| !technologies.is_researched(technology))
+        {
+            return Err(BuildingError::MissingTechnology(technology.clone()));
+        }
+
+        for (building_type, level) in &requirements.buildings {
+            let built = self
+                .buildings
+                .values()
+                .any(|building| building.building_type == *building_type && building.level >= *level);
+            if !built {
+                return Err(BuildingError::MissingBuilding {
+                    building_type: building_type.clone(),
+                    level: *level,
+                });
+            }
+        }
+
+        if self.population.total() < requirements.min_population {
+            return Err(BuildingError::NotEnoughPopulation {
+                required: requirements.min_population,
+                actual: self.population.total(),
+            });
+        }
 
         Ok(())
     }
 
+    /// Отменяет задание строительства и возвращает часть его стоимости.
+    /// Возвращает ресурсы, зачисленные городу.
+    pub fn cancel_construction(&mut self, building_id: &str) -> Result<Vec<(ResourceType, u32)>, BuildingError> {
+        let job = self
+            .construction
+            .remove(building_id)
+            .ok_or(BuildingError::ConstructionNotFound)?;
+
+        Ok(self.refund_construction(job.refund_unfinished()))
+    }
+
+    /// Продвигает очередь строительства на один ход.
+    ///
+    /// `speed_percent` — суммарный модификатор скорости строительства
+    /// (бонусы фракции и технологий). Для каждой завершенной постройки
+    /// вызывается `BuildingConstructedEvent`, для улучшения —
+    /// `BuildingUpgradedEvent`; если событие отменено, задание
+    /// отменяется с возвратом `CONSTRUCTION_REFUND_RATE` от его стоимости. Возвращает ID зданий,
+    /// работы над которыми завершены. У банкрота очередь не продвигается.
+    pub fn update_construction(&mut self, speed_percent: i32, events: &EventSystem) -> Vec<String> {
+        // У банкрота стройка стоит
+        if self.is_bankrupt() {
+            return Vec::new();
+        }
+
+        let slots = self.stats.construction_slots.max(1) as usize;
+        let finished = self
+            .construction
+            .advance(slots, construction::work_per_turn(speed_percent));
+
+        let mut completed = Vec::new();
+        for job in finished {
+            let accepted = match &job.kind {
+                ConstructionKind::Build { position, .. } => {
+                    let mut event = BuildingConstructedEvent::new(
+                        self.id.clone(),
+                        job.building_id.clone(),
+                        job.building_type.clone(),
+                        job.target_level,
+                        self.owner_id.clone(),
+                        *position,
+                    );
+                    events.call_event(&mut event)
+                }
+                ConstructionKind::Upgrade => {
+                    let Some(building) = self.buildings.get(&job.building_id) else {
+                        // Здание снесено во время улучшения
+                        continue;
+                    };
+                    let mut event = BuildingUpgradedEvent::new(
+                        self.id.clone(),
+                        job.building_id.clone(),
+                        job.building_type.clone(),
+                        building.level,
+                        job.target_level,
+                    );
+                    events.call_event(&mut event)
+                }
+            };
+            if !accepted {
+                self.refund_construction(job.refund_cancelled());
+                continue;
+            }
+
+            match job.kind {
+                ConstructionKind::Build { name, position } => {
+                    let building = Building::new(job.building_id.clone(), name, job.building_type, position);
+                    self.buildings.insert(job.building_id.clone(), building);
+                }
+                ConstructionKind::Upgrade => {
+                    if let Some(building) = self.buildings.get_mut(&job.building_id) {
+                        building.level = job.target_level;
+                    }
+                }
+            }
+            completed.push(job.building_id);
+        }
+
+        completed
+    }
+
+    fn pay_construction(&mut self, costs: &[(ResourceType, u32)]) {
+        self.resources.pay(costs);
+        for (resource, amount) in costs {
+            self.ledger.record(LedgerSource::Construction, resource.clone(), -(*amount as i64));
+        }
+    }
+
+    fn refund_construction(&mut self, refund: Vec<(ResourceType, u32)>) -> Vec<(ResourceType, u32)> {
+        for (resource, amount) in &refund {
+            self.resources.add(resource, *amount);
+            self.ledger.record(LedgerSource::Construction, resource.clone(), *amount as i64);
+        }
+        refund
+    }
+
     pub fn remove_building(&mut self, building_id: &str) -> Result<(), String> {
         // Проверяем, существует ли здание
         if !self.buildings.contains_key(building_id) {
             return Err("Здание не найдено".to_string());
         }
 
+        // Отменяем начатое улучшение
+        if let Some(job) = self.construction.remove(building_id) {
+            self.refund_construction(job.refund_unfinished());
+        }
+
         // Удаляем здание
         self.buildings.remove(building_id);
+        self.job_overrides.clear(building_id);
 
         Ok(())
     }
 
     pub fn increase_population(&mut self, amount: u32) {
-        self.population = (self.population + amount).min(self.stats.max_population);
+        let room = self.stats.max_population.saturating_sub(self.population.total());
+        self.population.grow(amount.min(room));
+        self.resources.set(ResourceType::Population, self.population.total());
     }
 
     pub fn decrease_population(&mut self, amount: u32) {
-        self.population = self.population.saturating_sub(amount);
+        self.population.shrink(amount);
+        self.resources.set(ResourceType::Population, self.population.total());
     }
 
     pub fn get_resource_report(&self) -> String {
@@ -366,7 +1390,7 @@ impl City {
              Местность: {}\n\
              Основан: {}\n",
             self.name,
-            self.population,
+            self.population.total(),
             self.stats.max_population,
             self.stats.happiness,
             self.stats.defense,
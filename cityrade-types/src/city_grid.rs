@@ -0,0 +1,224 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, VecDeque};
+
+use crate::city::Terrain;
+use crate::rng;
+
+/// Радиус сетки города: клетки от `-CITY_GRID_RADIUS` до `CITY_GRID_RADIUS` по каждой оси
+pub const CITY_GRID_RADIUS: i32 = 8;
+/// Радиус ядра города: в ядре нет воды, гор и лесов, только основная местность
+pub const CITY_CORE_RADIUS: i32 = 4;
+/// Центральная площадь города, от которой расходятся дороги
+pub const CITY_CENTER: (i32, i32) = (0, 0);
+/// Стоимость дороги в камне за клетку
+pub const ROAD_COST: u32 = 5;
+
+/// Клетка сетки города
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CityTile {
+    pub terrain: Terrain,
+    pub road: bool,
+}
+
+/// Сетка клеток города, на которой стоят здания и дороги.
+///
+/// Позиции зданий (`Building::position`) — координаты клеток относительно
+/// центральной площади `CITY_CENTER`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "CityGridData")]
+pub struct CityGrid {
+    radius: i32,
+    /// Клетки по строкам, начиная с `(-radius, -radius)`
+    tiles: Vec<CityTile>,
+}
+
+/// Формат сохранения сетки
+#[derive(Deserialize)]
+struct CityGridData {
+    radius: i32,
+    tiles: Vec<CityTile>,
+}
+
+impl TryFrom<CityGridData> for CityGrid {
+    type Error = String;
+
+    /// Проверяет сохранение: число клеток соответствует радиусу сетки
+    fn try_from(data: CityGridData) -> Result<Self, Self::Error> {
+        let side = 2 * data.radius.max(0) as u64 + 1;
+        if data.radius < 0 || data.tiles.len() as u64 != side * side {
+            return Err(format!(
+                "Сетка радиуса {} не может содержать {} клеток",
+                data.radius,
+                data.tiles.len()
+            ));
+        }
+        Ok(CityGrid {
+            radius: data.radius,
+            tiles: data.tiles,
+        })
+    }
+}
+
+impl Default for CityGrid {
+    fn default() -> Self {
+        Self::new(&Terrain::Plain)
+    }
+}
+
+impl CityGrid {
+    /// Ровная сетка одной местности; на центральной площади — дорога
+    pub fn new(terrain: &Terrain) -> Self {
+        let side = (2 * CITY_GRID_RADIUS + 1) as usize;
+        let tile = CityTile {
+            terrain: terrain.clone(),
+            road: false,
+        };
+        let mut grid = CityGrid {
+            radius: CITY_GRID_RADIUS,
+            tiles: vec![tile; side * side],
+        };
+        grid.set_road(CITY_CENTER, true);
+        grid
+    }
+
+    /// Сетка города на местности `terrain`: ядро покрыто основной местностью,
+    /// а на окраинах из зерна `seed` разбросаны леса, горы и водоемы.
+    /// Прибрежный город (`Terrain::Water`) стоит на равнине у большой воды.
+    pub fn generate(terrain: &Terrain, seed: u64) -> Self {
+        let mut rng = rng::seeded(seed);
+        let land = match terrain {
+            Terrain::Water => Terrain::Plain,
+            other => other.clone(),
+        };
+        let mut grid = Self::new(&land);
+
+        let water = if *terrain == Terrain::Water { 4 } else { 1 };
+        for (feature, count) in [(Terrain::Forest, 2), (Terrain::Mountain, 2), (Terrain::Water, water)] {
+            for _ in 0..count {
+                let center = loop {
+                    let position = (
+                        rng.random_range(-grid.radius..=grid.radius),
+                        rng.random_range(-grid.radius..=grid.radius),
+                    );
+                    if !is_core(position) {
+                        break position;
+                    }
+                };
+                let size: i32 = rng.random_range(1..=2);
+                for dx in -size..=size {
+                    for dy in -size..=size {
+                        let position = (center.0 + dx, center.1 + dy);
+                        if dx.abs() + dy.abs() <= size && !is_core(position) {
+                            grid.set_terrain(position, feature.clone());
+                        }
+                    }
+                }
+            }
+        }
+
+        grid
+    }
+
+    pub fn radius(&self) -> i32 {
+        self.radius
+    }
+
+    /// Входит ли клетка в сетку
+    pub fn contains(&self, position: (i32, i32)) -> bool {
+        position.0.abs() <= self.radius && position.1.abs() <= self.radius
+    }
+
+    pub fn tile(&self, position: (i32, i32)) -> Option<&CityTile> {
+        self.index(position).map(|index| &self.tiles[index])
+    }
+
+    /// Местность клетки
+    pub fn terrain(&self, position: (i32, i32)) -> Option<&Terrain> {
+        self.tile(position).map(|tile| &tile.terrain)
+    }
+
+    /// Меняет местность клетки; клетки вне сетки игнорируются
+    pub fn set_terrain(&mut self, position: (i32, i32), terrain: Terrain) {
+        if let Some(index) = self.index(position) {
+            self.tiles[index].terrain = terrain;
+        }
+    }
+
+    pub fn has_road(&self, position: (i32, i32)) -> bool {
+        self.tile(position).is_some_and(|tile| tile.road)
+    }
+
+    /// Прокладывает или убирает дорогу; клетки вне сетки игнорируются
+    pub fn set_road(&mut self, position: (i32, i32), road: bool) {
+        if let Some(index) = self.index(position) {
+            self.tiles[index].road = road;
+        }
+    }
+
+    /// Все клетки сетки по возрастанию координат
+    pub fn positions(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        (-self.radius..=self.radius).flat_map(move |x| (-self.radius..=self.radius).map(move |y| (x, y)))
+    }
+
+    /// Соседние клетки (по сторонам) в пределах сетки
+    pub fn neighbors(&self, position: (i32, i32)) -> Vec<(i32, i32)> {
+        let (x, y) = position;
+        [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)]
+            .into_iter()
+            .filter(|neighbor| self.contains(*neighbor))
+            .collect()
+    }
+
+    /// Граничит ли клетка с местностью из списка
+    pub fn is_adjacent_to(&self, position: (i32, i32), terrains: &[Terrain]) -> bool {
+        self.neighbors(position)
+            .into_iter()
+            .any(|neighbor| self.terrain(neighbor).is_some_and(|terrain| terrains.contains(terrain)))
+    }
+
+    /// Дороги, по которым можно добраться до центральной площади
+    pub fn connected_roads(&self) -> BTreeSet<(i32, i32)> {
+        let mut connected = BTreeSet::new();
+        if !self.has_road(CITY_CENTER) {
+            return connected;
+        }
+
+        let mut queue = VecDeque::from([CITY_CENTER]);
+        connected.insert(CITY_CENTER);
+        while let Some(position) = queue.pop_front() {
+            for neighbor in self.neighbors(position) {
+                if self.has_road(neighbor) && connected.insert(neighbor) {
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        connected
+    }
+
+    /// Граничит ли клетка с дорогой, связанной с центральной площадью
+    pub fn is_connected(&self, position: (i32, i32)) -> bool {
+        let connected = self.connected_roads();
+        self.neighbors(position)
+            .into_iter()
+            .any(|neighbor| connected.contains(&neighbor))
+    }
+
+    fn index(&self, position: (i32, i32)) -> Option<usize> {
+        if !self.contains(position) {
+            return None;
+        }
+        let side = 2 * self.radius + 1;
+        Some(((position.1 + self.radius) * side + position.0 + self.radius) as usize)
+    }
+}
+
+/// Зерно сетки города в позиции `position`: одна и та же позиция
+/// всегда дает одну и ту же сетку
+pub fn grid_seed(position: (i32, i32)) -> u64 {
+    ((position.0 as u32 as u64) << 32) | position.1 as u32 as u64
+}
+
+fn is_core(position: (i32, i32)) -> bool {
+    position.0.abs() <= CITY_CORE_RADIUS && position.1.abs() <= CITY_CORE_RADIUS
+}
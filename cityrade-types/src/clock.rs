@@ -0,0 +1,146 @@
+use chrono::{DateTime, Duration as ChronoDuration, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Год, с которого начинается игровой календарь
+pub const CALENDAR_START_YEAR: i32 = 1000;
+/// Сколько игровых часов проходит за один ход
+pub const DEFAULT_HOURS_PER_TURN: u32 = 24;
+
+/// Источник реального времени
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimeSource {
+    /// Системные часы (`Utc::now`)
+    System,
+    /// Время, управляемое вручную (тесты, повторы, догоняющая симуляция)
+    Manual(DateTime<Utc>),
+}
+
+impl TimeSource {
+    /// Возвращает текущее время источника
+    pub fn now(&self) -> DateTime<Utc> {
+        match self {
+            TimeSource::System => Utc::now(),
+            TimeSource::Manual(now) => *now,
+        }
+    }
+}
+
+/// Игровые часы.
+///
+/// Объединяют номер хода, игровой календарь (дата, вычисляемая из номера хода)
+/// и источник реального времени. Код городов, игроков, квестов и рынков
+/// получает время только через `GameClock`, поэтому тесты могут перематывать
+/// время, а сервер — догонять пропущенные ходы после простоя.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameClock {
+    turn: u64,
+    calendar_start: DateTime<Utc>,
+    hours_per_turn: u32,
+    source: TimeSource,
+}
+
+impl Default for GameClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GameClock {
+    /// Создает часы на системном времени, начиная с нулевого хода
+    pub fn new() -> Self {
+        Self::with_source(TimeSource::System)
+    }
+
+    /// Создает часы с ручным источником времени, начиная с момента `now`
+    pub fn manual(now: DateTime<Utc>) -> Self {
+        Self::with_source(TimeSource::Manual(now))
+    }
+
+    /// Создает часы с указанным источником времени
+    pub fn with_source(source: TimeSource) -> Self {
+        GameClock {
+            turn: 0,
+            calendar_start: Utc
+                .with_ymd_and_hms(CALENDAR_START_YEAR, 1, 1, 0, 0, 0)
+                .unwrap(),
+            hours_per_turn: DEFAULT_HOURS_PER_TURN,
+            source,
+        }
+    }
+
+    /// Устанавливает начало игрового календаря и длительность хода в игровых часах
+    pub fn with_calendar(mut self, calendar_start: DateTime<Utc>, hours_per_turn: u32) -> Self {
+        self.calendar_start = calendar_start;
+        self.hours_per_turn = hours_per_turn;
+        self
+    }
+
+    /// Номер текущего хода
+    pub fn turn(&self) -> u64 {
+        self.turn
+    }
+
+    /// Текущее реальное время
+    pub fn now(&self) -> DateTime<Utc> {
+        self.source.now()
+    }
+
+    /// Источник реального времени
+    pub fn source(&self) -> &TimeSource {
+        &self.source
+    }
+
+    /// Игровая дата текущего хода
+    pub fn date(&self) -> DateTime<Utc> {
+        self.date_of_turn(self.turn)
+    }
+
+    /// Игровая дата указанного хода
+    pub fn date_of_turn(&self, turn: u64) -> DateTime<Utc> {
+        let hours = turn.saturating_mul(self.hours_per_turn as u64);
+        i64::try_from(hours)
+            .ok()
+            .and_then(ChronoDuration::try_hours)
+            .and_then(|offset| self.calendar_start.checked_add_signed(offset))
+            .unwrap_or(DateTime::<Utc>::MAX_UTC)
+    }
+
+    /// Переходит к следующему ходу и возвращает его номер
+    pub fn advance_turn(&mut self) -> u64 {
+        self.turn += 1;
+        self.turn
+    }
+
+    /// Перематывает реальное время вперед.
+    /// Действует только на ручной источник: системные часы идут сами.
+    pub fn advance_time(&mut self, elapsed: Duration) {
+        if let TimeSource::Manual(now) = &mut self.source {
+            let elapsed = ChronoDuration::from_std(elapsed).unwrap_or(ChronoDuration::MAX);
+            *now = now.checked_add_signed(elapsed).unwrap_or(DateTime::<Utc>::MAX_UTC);
+        }
+    }
+
+    /// Устанавливает время ручного источника
+    pub fn set_time(&mut self, now: DateTime<Utc>) {
+        self.source = TimeSource::Manual(now);
+    }
+
+    /// Сколько реального времени прошло с момента `since` (не меньше нуля)
+    pub fn elapsed_since(&self, since: DateTime<Utc>) -> Duration {
+        self.now()
+            .signed_duration_since(since)
+            .to_std()
+            .unwrap_or(Duration::ZERO)
+    }
+
+    /// Сколько ходов длительностью `turn_interval` пропущено с момента `since`.
+    /// Используется для догоняющей симуляции после простоя сервера.
+    pub fn turns_since(&self, since: DateTime<Utc>, turn_interval: Duration) -> u64 {
+        if turn_interval.is_zero() {
+            return 0;
+        }
+
+        (self.elapsed_since(since).as_nanos() / turn_interval.as_nanos()) as u64
+    }
+}
@@ -54,6 +54,12 @@ pub struct CommandManager {
     commands: Vec<Box<dyn Command>>,
 }
 
+impl Default for CommandManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl CommandManager {
     /// Создать новый менеджер команд
     pub fn new() -> Self {
@@ -68,13 +74,11 @@ impl CommandManager {
     }
     
     /// Получить команду по имени или псевдониму
+    #[allow(clippy::borrowed_box)]
     pub fn get_command(&self, name: &str) -> Option<&Box<dyn Command>> {
-        for command in &self.commands {
-            if command.name() == name || command.aliases().contains(&name.to_string()) {
-                return Some(command);
-            }
-        }
-        None
+        self.commands
+            .iter()
+            .find(|command| command.name() == name || command.aliases().contains(&name.to_string()))
     }
     
     /// Выполнить команду
@@ -98,19 +102,21 @@ impl CommandManager {
                 return false;
             }
             
-            return command.execute(sender, args);
+            command.execute(sender, args)
         } else {
             sender.send_message(&format!("Команда '{}' не найдена", command_name));
-            return false;
+            false
         }
     }
     
     /// Получить список всех команд
+    #[allow(clippy::borrowed_box)]
     pub fn get_all_commands(&self) -> Vec<&Box<dyn Command>> {
         self.commands.iter().collect()
     }
     
     /// Получить список команд, доступных отправителю
+    #[allow(clippy::borrowed_box)]
     pub fn get_available_commands(&self, sender: &dyn CommandSender) -> Vec<&Box<dyn Command>> {
         self.commands.iter()
             .filter(|cmd| cmd.can_execute(sender))
@@ -0,0 +1,173 @@
+use serde::{Deserialize, Serialize};
+
+use crate::building::BuildingType;
+use crate::resources::ResourceType;
+
+/// Очков работы за ход без бонусов скорости; один ход `build_time` стоит столько же
+pub const WORK_PER_TURN: u32 = 100;
+/// Минимум очков работы за ход, как бы ни были велики штрафы к скорости
+pub const MIN_WORK_PER_TURN: u32 = 10;
+/// Доля стоимости, возвращаемая при отмене строительства
+pub const CONSTRUCTION_REFUND_RATE: f32 = 0.75;
+
+/// Что строится
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConstructionKind {
+    /// Новое здание
+    Build { name: String, position: (i32, i32) },
+    /// Улучшение существующего здания
+    Upgrade,
+}
+
+/// Задание очереди строительства
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConstructionJob {
+    /// ID здания: для новой постройки — ID, который получит здание
+    pub building_id: String,
+    pub building_type: BuildingType,
+    pub kind: ConstructionKind,
+    /// Уровень здания после завершения работ
+    pub target_level: u32,
+    /// Уплаченная стоимость
+    pub cost: Vec<(ResourceType, u32)>,
+    /// Необходимые очки работы
+    pub work_required: u32,
+    /// Выполненные очки работы
+    pub work_done: u32,
+}
+
+impl ConstructionJob {
+    /// Создает задание длительностью `build_time` ходов
+    pub fn new(
+        building_id: String,
+        building_type: BuildingType,
+        kind: ConstructionKind,
+        target_level: u32,
+        cost: Vec<(ResourceType, u32)>,
+        build_time: u32,
+    ) -> Self {
+        ConstructionJob {
+            building_id,
+            building_type,
+            kind,
+            target_level,
+            cost,
+            work_required: build_time.saturating_mul(WORK_PER_TURN),
+            work_done: 0,
+        }
+    }
+
+    /// Завершены ли работы
+    pub fn is_complete(&self) -> bool {
+        self.work_done >= self.work_required
+    }
+
+    /// Доля выполненных работ (от 0 до 1)
+    pub fn progress(&self) -> f32 {
+        if self.work_required == 0 {
+            return 1.0;
+        }
+        (self.work_done as f32 / self.work_required as f32).min(1.0)
+    }
+
+    /// Сколько ресурсов вернется при отмене игроком: `CONSTRUCTION_REFUND_RATE`
+    /// от стоимости невыполненной части работ
+    pub fn refund_unfinished(&self) -> Vec<(ResourceType, u32)> {
+        self.refund_share(1.0 - self.progress())
+    }
+
+    /// Сколько ресурсов вернется, если завершение работ отменено событием:
+    /// `CONSTRUCTION_REFUND_RATE` от всей стоимости, независимо от выполненных работ
+    pub fn refund_cancelled(&self) -> Vec<(ResourceType, u32)> {
+        self.refund_share(1.0)
+    }
+
+    fn refund_share(&self, share: f32) -> Vec<(ResourceType, u32)> {
+        self.cost
+            .iter()
+            .map(|(resource, amount)| {
+                let refund = (*amount as f32 * share * CONSTRUCTION_REFUND_RATE) as u32;
+                (resource.clone(), refund)
+            })
+            .filter(|(_, amount)| *amount > 0)
+            .collect()
+    }
+}
+
+/// Сколько очков работы дает один ход при модификаторе скорости `speed_percent`
+pub fn work_per_turn(speed_percent: i32) -> u32 {
+    let work = WORK_PER_TURN as i64 + WORK_PER_TURN as i64 * speed_percent as i64 / 100;
+    work.clamp(MIN_WORK_PER_TURN as i64, u32::MAX as i64) as u32
+}
+
+/// Очередь строительства города.
+///
+/// Задания выполняются по порядку постановки; одновременно продвигаются
+/// только первые задания по числу мест строительства, остальные ждут.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConstructionQueue {
+    jobs: Vec<ConstructionJob>,
+}
+
+impl ConstructionQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ставит задание в конец очереди
+    pub fn push(&mut self, job: ConstructionJob) {
+        self.jobs.push(job);
+    }
+
+    /// Все задания в порядке очереди
+    pub fn jobs(&self) -> &[ConstructionJob] {
+        &self.jobs
+    }
+
+    /// Задание по ID здания
+    pub fn get(&self, building_id: &str) -> Option<&ConstructionJob> {
+        self.jobs.iter().find(|job| job.building_id == building_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.jobs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.jobs.is_empty()
+    }
+
+    /// Количество новых зданий в очереди
+    pub fn pending_buildings(&self) -> usize {
+        self.jobs
+            .iter()
+            .filter(|job| matches!(job.kind, ConstructionKind::Build { .. }))
+            .count()
+    }
+
+    /// Занята ли позиция строящимся зданием
+    pub fn is_position_reserved(&self, position: (i32, i32)) -> bool {
+        self.jobs.iter().any(|job| match &job.kind {
+            ConstructionKind::Build { position: reserved, .. } => *reserved == position,
+            ConstructionKind::Upgrade => false,
+        })
+    }
+
+    /// Убирает задание из очереди
+    pub fn remove(&mut self, building_id: &str) -> Option<ConstructionJob> {
+        let index = self.jobs.iter().position(|job| job.building_id == building_id)?;
+        Some(self.jobs.remove(index))
+    }
+
+    /// Продвигает первые `slots` заданий на `work` очков и возвращает
+    /// завершенные задания, убирая их из очереди
+    pub fn advance(&mut self, slots: usize, work: u32) -> Vec<ConstructionJob> {
+        for job in self.jobs.iter_mut().take(slots) {
+            job.work_done = job.work_done.saturating_add(work);
+        }
+
+        let (finished, remaining) = self.jobs.drain(..).partition(ConstructionJob::is_complete);
+        self.jobs = remaining;
+        finished
+    }
+}
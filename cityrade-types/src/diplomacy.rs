@@ -1,11 +1,28 @@
 // cityrade-types/src/diplomacy.rs
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use super::events::{EventSystem, RelationChangedEvent};
 use super::resources::ResourceType;
 
+/// Сколько клеток общей границы добавляют единицу напряженности за ход
+pub const BORDER_TENSION_TILES: usize = 5;
+/// Наибольшее падение репутации за ход из-за общей границы
+pub const MAX_BORDER_TENSION: i32 = 3;
+/// Ниже этой репутации общая граница отношения не портит
+pub const BORDER_TENSION_FLOOR: i32 = -50;
+
+/// Напряженность за ход от общей границы длиной `border_tiles` клеток
+pub fn border_tension(border_tiles: usize) -> i32 {
+    if border_tiles == 0 {
+        return 0;
+    }
+    ((border_tiles / BORDER_TENSION_TILES) as i32 + 1).min(MAX_BORDER_TENSION)
+}
+
 /// Типы дипломатических отношений между фракциями
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
 pub enum RelationType {
+    #[default]
     Neutral,
     Friendly,
     Tense,
@@ -14,12 +31,6 @@ pub enum RelationType {
     TradePartner,
 }
 
-impl Default for RelationType {
-    fn default() -> Self {
-        RelationType::Neutral
-    }
-}
-
 /// Представляет дипломатические действия, которые могут быть выполнены между фракциями
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum DiplomaticAction {
@@ -49,6 +60,12 @@ pub struct DiplomaticRelation {
     pub last_actions: Vec<(DiplomaticAction, u64)>, // действие и время действия
 }
 
+impl Default for DiplomaticRelation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl DiplomaticRelation {
     pub fn new() -> Self {
         DiplomaticRelation {
@@ -64,9 +81,9 @@ impl DiplomaticRelation {
     pub fn update_relation_type(&mut self) {
         self.relation_type = match self.reputation {
             i if i >= 75 => RelationType::Alliance,
-            i if i >= 25 => RelationType::TradePartner,
+            i if i >= 25 => RelationType::Friendly,
             i if i > -25 => RelationType::Neutral,
-            i if i > -75 => RelationType::Conflict,
+            i if i > -75 => RelationType::Tense,
             _ => RelationType::Conflict,
         };
 
@@ -74,7 +91,7 @@ impl DiplomaticRelation {
         self.trade_modifier = match self.relation_type {
             RelationType::Alliance => 1.5,
             RelationType::TradePartner => 1.2,
-            RelationType::Friendly => 1.1,
+            RelationType::Friendly => 1.2,
             RelationType::Neutral => 1.0,
             RelationType::Tense => 0.8,
             RelationType::Conflict => 0.0, // Эмбарго - торговля запрещена
@@ -136,18 +153,54 @@ impl DiplomacyManager {
         self.relations.insert(key, relation);
     }
 
-    /// Изменяет репутацию между двумя фракциями
-    pub fn change_reputation(&mut self, faction1: &str, faction2: &str, amount: i32) {
+    /// Изменяет репутацию между двумя фракциями.
+    /// Возвращает `false`, если изменение отменено обработчиком `RelationChangedEvent`.
+    pub fn change_reputation(&mut self, faction1: &str, faction2: &str, amount: i32, events: &EventSystem) -> bool {
         let key = if faction1 < faction2 {
             (faction1.to_string(), faction2.to_string())
         } else {
             (faction2.to_string(), faction1.to_string())
         };
         
-        self.relations
-            .entry(key)
-            .or_insert_with(DiplomaticRelation::new)
-            .change_reputation(amount);
+        let relation = self.relations.get(&key).cloned().unwrap_or_default();
+        let mut changed = relation.clone();
+        changed.change_reputation(amount);
+        
+        let mut event = RelationChangedEvent::new(
+            key.0.clone(),
+            key.1.clone(),
+            relation.reputation,
+            changed.reputation,
+            relation.relation_type,
+            changed.relation_type.clone(),
+        );
+        if !events.call_event(&mut event) {
+            return false;
+        }
+        
+        self.relations.insert(key, changed);
+        true
+    }
+
+    /// Снижает репутацию фракций с общей границей длиной `border_tiles`
+    /// клеток (`border_tension`), но не ниже `BORDER_TENSION_FLOOR`.
+    /// Союзники границу не замечают. Возвращает, на сколько упала репутация.
+    pub fn apply_border_tension(
+        &mut self,
+        faction1: &str,
+        faction2: &str,
+        border_tiles: usize,
+        events: &EventSystem,
+    ) -> i32 {
+        let relation = self.get_relation(faction1, faction2).cloned().unwrap_or_default();
+        if relation.relation_type == RelationType::Alliance {
+            return 0;
+        }
+        let tension = border_tension(border_tiles).min((relation.reputation - BORDER_TENSION_FLOOR).max(0));
+        if tension == 0 || !self.change_reputation(faction1, faction2, -tension, events) {
+            return 0;
+        }
+        tension
     }
 
     /// Регистрирует дипломатическое действие
@@ -160,7 +213,7 @@ impl DiplomacyManager {
         
         self.relations
             .entry(key)
-            .or_insert_with(DiplomaticRelation::new)
+            .or_default()
             .add_action(action, time);
     }
 }
@@ -0,0 +1,372 @@
+use futures::future::{self, BoxFuture, FutureExt};
+use std::any::{Any, TypeId};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::future::Future;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use crate::events::{
+    BankruptcyEvent, BuildingConstructedEvent, BuildingUpgradedEvent, Event, EventPriority, EventResult, EventSystem,
+    MarketTradeEvent, PlayerJoinEvent, PlayerLeaveEvent, PolicyLapsedEvent, PopulationChangedEvent,
+    QuestCompletedEvent, RandomEventTriggeredEvent, RelationChangedEvent, ResearchCompletedEvent,
+    ResourceShortageEvent, UnrestEvent,
+};
+
+/// Событие в очереди асинхронной шины
+type Payload = Arc<dyn Any + Send + Sync>;
+
+/// Тип асинхронного обработчика событий
+pub type AsyncHandlerFn = Arc<dyn Fn(Payload) -> BoxFuture<'static, ()> + Send + Sync>;
+
+/// Что делать, когда очередь обработчика переполнена
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    /// Отбросить самое старое недоставленное событие
+    #[default]
+    DropOldest,
+    /// Отбросить новое событие
+    DropNewest,
+}
+
+/// Настройки обработчика асинхронной шины
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HandlerOptions {
+    /// Максимальное число недоставленных событий в очереди обработчика
+    pub capacity: usize,
+    /// Сколько событий обработчик получает за один `flush`
+    pub max_per_flush: usize,
+    /// Поведение при переполнении очереди
+    pub overflow: OverflowPolicy,
+}
+
+impl Default for HandlerOptions {
+    fn default() -> Self {
+        Self {
+            capacity: 1024,
+            max_per_flush: usize::MAX,
+            overflow: OverflowPolicy::DropOldest,
+        }
+    }
+}
+
+impl HandlerOptions {
+    /// Устанавливает размер очереди обработчика
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Устанавливает число событий, доставляемых за один `flush`
+    pub fn with_max_per_flush(mut self, max_per_flush: usize) -> Self {
+        self.max_per_flush = max_per_flush;
+        self
+    }
+
+    /// Устанавливает поведение при переполнении очереди
+    pub fn with_overflow(mut self, overflow: OverflowPolicy) -> Self {
+        self.overflow = overflow;
+        self
+    }
+}
+
+/// Асинхронный обработчик со своей очередью
+struct AsyncHandler {
+    id: String,
+    options: HandlerOptions,
+    mailbox: VecDeque<Payload>,
+    dropped: u64,
+    handler: AsyncHandlerFn,
+}
+
+impl AsyncHandler {
+    /// Кладет событие в очередь с учетом политики переполнения
+    fn enqueue(&mut self, payload: Payload) {
+        if self.options.capacity == 0 {
+            self.dropped += 1;
+            return;
+        }
+
+        if self.mailbox.len() >= self.options.capacity {
+            self.dropped += 1;
+            match self.options.overflow {
+                OverflowPolicy::DropNewest => return,
+                OverflowPolicy::DropOldest => {
+                    self.mailbox.pop_front();
+                }
+            }
+        }
+
+        self.mailbox.push_back(payload);
+    }
+}
+
+/// Событие, ожидающее доставки
+struct QueuedEvent {
+    type_id: TypeId,
+    payload: Payload,
+}
+
+impl QueuedEvent {
+    fn new<E: Event + 'static>(event: E) -> Self {
+        QueuedEvent {
+            type_id: TypeId::of::<E>(),
+            payload: Arc::new(event),
+        }
+    }
+}
+
+/// События, опубликованные в текущем тике; общие для шины и её издателей
+type Pending = Arc<Mutex<Vec<QueuedEvent>>>;
+
+fn lock(pending: &Pending) -> MutexGuard<'_, Vec<QueuedEvent>> {
+    pending.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Издатель событий в асинхронную шину.
+///
+/// Нужен там, где шина недоступна по `&mut`, например в обработчиках
+/// синхронной `EventSystem`. События попадают в ту же очередь, что и
+/// `AsyncEventBus::publish`, и доставляются в конце текущего тика.
+#[derive(Clone)]
+pub struct EventPublisher {
+    pending: Pending,
+}
+
+impl EventPublisher {
+    /// Опубликовать событие: оно будет доставлено в конце текущего тика
+    pub fn publish<E: Event + 'static>(&self, event: E) {
+        lock(&self.pending).push(QueuedEvent::new(event));
+    }
+}
+
+/// Итоги одной доставки событий
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FlushReport {
+    /// Сколько событий получили обработчики
+    pub delivered: usize,
+    /// Сколько событий осталось в очередях обработчиков
+    pub backlog: usize,
+}
+
+/// Асинхронная шина событий.
+///
+/// Работает рядом с синхронной `EventSystem`: события не доставляются сразу,
+/// а копятся до конца тика. `forward_all` пересылает в шину события,
+/// прошедшие через `EventSystem`. `end_tick` раскладывает накопленные и наступившие
+/// отложенные события по очередям обработчиков, `flush` выполняет обработчики.
+/// Каждый обработчик получает события по порядку публикации; разные
+/// обработчики выполняются конкурентно. Очередь обработчика ограничена
+/// (`HandlerOptions::capacity`), поэтому медленный обработчик теряет события
+/// по своей политике переполнения, не задерживая остальных.
+pub struct AsyncEventBus {
+    handlers: HashMap<TypeId, Vec<AsyncHandler>>,
+    pending: Pending,
+    delayed: BTreeMap<u64, Vec<QueuedEvent>>,
+    turn: u64,
+}
+
+impl Default for AsyncEventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AsyncEventBus {
+    /// Создать новую шину
+    pub fn new() -> Self {
+        Self {
+            handlers: HashMap::new(),
+            pending: Pending::default(),
+            delayed: BTreeMap::new(),
+            turn: 0,
+        }
+    }
+
+    /// Ход, во время которого публикуются события
+    pub fn turn(&self) -> u64 {
+        self.turn
+    }
+
+    /// Зарегистрировать асинхронный обработчик для указанного типа события
+    pub fn register_handler<E, F, Fut>(&mut self, id: String, options: HandlerOptions, handler: F)
+    where
+        E: Event + 'static,
+        F: Fn(Arc<E>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let handler_fn: AsyncHandlerFn = Arc::new(move |payload: Payload| {
+            match payload.downcast::<E>() {
+                Ok(event) => handler(event).boxed(),
+                Err(_) => future::ready(()).boxed(),
+            }
+        });
+
+        self.handlers
+            .entry(TypeId::of::<E>())
+            .or_default()
+            .push(AsyncHandler {
+                id,
+                options,
+                mailbox: VecDeque::new(),
+                dropped: 0,
+                handler: handler_fn,
+            });
+    }
+
+    /// Удалить обработчик по ID вместе с его очередью
+    pub fn unregister_handler(&mut self, id: &str) {
+        for handlers in self.handlers.values_mut() {
+            handlers.retain(|handler| handler.id != id);
+        }
+    }
+
+    /// Опубликовать событие: оно будет доставлено в конце текущего тика
+    pub fn publish<E: Event + 'static>(&mut self, event: E) {
+        lock(&self.pending).push(QueuedEvent::new(event));
+    }
+
+    /// Издатель, публикующий события в эту шину
+    pub fn publisher(&self) -> EventPublisher {
+        EventPublisher {
+            pending: self.pending.clone(),
+        }
+    }
+
+    /// Пересылает в шину события типа `E`, прошедшие через `events`.
+    /// Пересылка регистрируется как обработчик `Monitor`, поэтому отмененные
+    /// события в шину не попадают.
+    pub fn forward<E: Event + Clone + 'static>(&self, events: &mut EventSystem) {
+        let publisher = self.publisher();
+        events.register_handler::<E>(
+            format!("event_bus::forward::{}", std::any::type_name::<E>()),
+            EventPriority::Monitor,
+            true,
+            move |event| {
+                publisher.publish(event.clone());
+                EventResult::Continue
+            },
+        );
+    }
+
+    /// Пересылает в шину все игровые события, прошедшие через `events`
+    pub fn forward_all(&self, events: &mut EventSystem) {
+        self.forward::<PlayerJoinEvent>(events);
+        self.forward::<PlayerLeaveEvent>(events);
+        self.forward::<BuildingConstructedEvent>(events);
+        self.forward::<BuildingUpgradedEvent>(events);
+        self.forward::<PopulationChangedEvent>(events);
+        self.forward::<ResourceShortageEvent>(events);
+        self.forward::<UnrestEvent>(events);
+        self.forward::<BankruptcyEvent>(events);
+        self.forward::<PolicyLapsedEvent>(events);
+        self.forward::<ResearchCompletedEvent>(events);
+        self.forward::<MarketTradeEvent>(events);
+        self.forward::<RelationChangedEvent>(events);
+        self.forward::<QuestCompletedEvent>(events);
+        self.forward::<RandomEventTriggeredEvent>(events);
+    }
+
+    /// Опубликовать событие, которое сработает через `turns` ходов.
+    /// При `turns == 0` событие доставляется в конце текущего тика.
+    pub fn publish_delayed<E: Event + 'static>(&mut self, event: E, turns: u64) {
+        if turns == 0 {
+            self.publish(event);
+            return;
+        }
+
+        self.delayed
+            .entry(self.turn.saturating_add(turns))
+            .or_default()
+            .push(QueuedEvent::new(event));
+    }
+
+    /// Завершает тик `turn`: добавляет наступившие отложенные события к
+    /// накопленным и раскладывает их по очередям обработчиков.
+    /// События без обработчиков отбрасываются.
+    pub fn end_tick(&mut self, turn: u64) {
+        let later = self.delayed.split_off(&turn.saturating_add(1));
+        let due = std::mem::replace(&mut self.delayed, later);
+
+        let mut events: Vec<QueuedEvent> = due.into_values().flatten().collect();
+        events.append(&mut lock(&self.pending));
+
+        for event in events {
+            if let Some(handlers) = self.handlers.get_mut(&event.type_id) {
+                for handler in handlers {
+                    handler.enqueue(event.payload.clone());
+                }
+            }
+        }
+
+        self.turn = turn.saturating_add(1);
+    }
+
+    /// Выполняет обработчики для событий из их очередей.
+    /// Каждый обработчик получает не больше `max_per_flush` событий,
+    /// остальные ждут следующего вызова.
+    pub async fn flush(&mut self) -> FlushReport {
+        let mut tasks = Vec::new();
+        let mut delivered = 0;
+
+        for handlers in self.handlers.values_mut() {
+            for handler in handlers {
+                let count = handler.mailbox.len().min(handler.options.max_per_flush);
+                if count == 0 {
+                    continue;
+                }
+
+                let batch: Vec<Payload> = handler.mailbox.drain(..count).collect();
+                delivered += batch.len();
+
+                let handler_fn = handler.handler.clone();
+                tasks.push(async move {
+                    for payload in batch {
+                        handler_fn(payload).await;
+                    }
+                });
+            }
+        }
+
+        future::join_all(tasks).await;
+
+        FlushReport {
+            delivered,
+            backlog: self.backlog(),
+        }
+    }
+
+    /// Количество событий, ожидающих конца тика
+    pub fn pending_count(&self) -> usize {
+        lock(&self.pending).len()
+    }
+
+    /// Количество отложенных событий
+    pub fn delayed_count(&self) -> usize {
+        self.delayed.values().map(Vec::len).sum()
+    }
+
+    /// Количество событий в очередях всех обработчиков
+    pub fn backlog(&self) -> usize {
+        self.handlers
+            .values()
+            .flatten()
+            .map(|handler| handler.mailbox.len())
+            .sum()
+    }
+
+    /// Длина очереди обработчика
+    pub fn queue_len(&self, id: &str) -> Option<usize> {
+        self.find_handler(id).map(|handler| handler.mailbox.len())
+    }
+
+    /// Сколько событий обработчик потерял из-за переполнения очереди
+    pub fn dropped_count(&self, id: &str) -> Option<u64> {
+        self.find_handler(id).map(|handler| handler.dropped)
+    }
+
+    fn find_handler(&self, id: &str) -> Option<&AsyncHandler> {
+        self.handlers
+            .values()
+            .flatten()
+            .find(|handler| handler.id == id)
+    }
+}
@@ -2,7 +2,14 @@ use serde::{Serialize, Deserialize};
 use std::any::{Any, TypeId};
 use std::collections::HashMap;
 use std::fmt::Debug;
+use super::building::BuildingType;
+use super::diplomacy::RelationType;
+use super::market::TradeDirection;
+use super::policies::PolicyType;
+use super::population::{PopulationClass, Unrest};
+use super::random_events::EventSeverity;
 use super::resources::{ResourceType, BuildingEffect};
+use super::technology::TechnologyType;
 
 /// Трейт для событий
 pub trait Event: Any + Debug + Send + Sync {
@@ -15,6 +22,15 @@ pub trait Event: Any + Debug + Send + Sync {
         false
     }
     
+    /// Проверить, отменено ли событие
+    fn is_cancelled(&self) -> bool {
+        false
+    }
+    
+    /// Установить флаг отмены события.
+    /// Неотменяемые события игнорируют вызов.
+    fn set_cancelled(&mut self, _cancelled: bool) {}
+    
     /// Получить идентификатор типа события
     fn type_id(&self) -> TypeId {
         TypeId::of::<Self>()
@@ -22,6 +38,9 @@ pub trait Event: Any + Debug + Send + Sync {
     
     /// Преобразовать событие в Any для даункастинга
     fn as_any(&self) -> &dyn Any;
+    
+    /// Преобразовать событие в изменяемый Any для даункастинга
+    fn as_any_mut(&mut self) -> &mut dyn Any;
 }
 
 /// Результат обработки события
@@ -32,7 +51,11 @@ pub enum EventResult {
     Cancel,
 }
 
-/// Приоритет обработчика события
+/// Приоритет обработчика события.
+///
+/// Обработчики вызываются от `Lowest` к `Highest`, последними — `Monitor`.
+/// Обработчики `Monitor` только наблюдают за итогом: они не могут отменить
+/// событие или снять отмену.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum EventPriority {
     Lowest = 0,
@@ -44,7 +67,7 @@ pub enum EventPriority {
 }
 
 /// Тип обработчика событий
-pub type EventHandlerFn = Box<dyn Fn(&dyn Event) -> EventResult + Send + Sync>;
+pub type EventHandlerFn = Box<dyn Fn(&mut dyn Event) -> EventResult + Send + Sync>;
 
 /// Обработчик событий
 pub struct EventHandler {
@@ -64,6 +87,12 @@ pub struct EventSystem {
     handlers: HashMap<TypeId, Vec<EventHandler>>,
 }
 
+impl Default for EventSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl EventSystem {
     /// Создать новую систему событий
     pub fn new() -> Self {
@@ -78,14 +107,14 @@ impl EventSystem {
         id: String,
         priority: EventPriority,
         ignore_cancelled: bool,
-        handler: impl Fn(&E) -> EventResult + Send + Sync + 'static,
+        handler: impl Fn(&mut E) -> EventResult + Send + Sync + 'static,
     ) {
         let type_id = TypeId::of::<E>();
-        let handlers = self.handlers.entry(type_id).or_insert_with(Vec::new);
+        let handlers = self.handlers.entry(type_id).or_default();
         
         // Создаем обработчик, который преобразует dyn Event в конкретный тип события
-        let handler_fn: EventHandlerFn = Box::new(move |event: &dyn Event| {
-            if let Some(e) = event.as_any().downcast_ref::<E>() {
+        let handler_fn: EventHandlerFn = Box::new(move |event: &mut dyn Event| {
+            if let Some(e) = event.as_any_mut().downcast_mut::<E>() {
                 handler(e)
             } else {
                 EventResult::Continue
@@ -100,7 +129,7 @@ impl EventSystem {
         });
         
         // Сортируем обработчики по приоритету
-        handlers.sort_by(|a, b| a.priority.cmp(&b.priority));
+        handlers.sort_by_key(|a| a.priority);
     }
     
     /// Удалить обработчик по ID
@@ -110,34 +139,51 @@ impl EventSystem {
         }
     }
     
-    /// Вызвать событие и выполнить все зарегистрированные обработчики
-    pub fn call_event(&self, event: &dyn Event) -> bool {
-        let type_id = TypeId::of::<dyn Event>();
-        let mut cancelled = false;
+    /// Вызвать событие и выполнить все зарегистрированные обработчики.
+    ///
+    /// Обработчики выбираются по конкретному типу события и вызываются в порядке
+    /// приоритета. Отмена записывается в само событие, поэтому следующие
+    /// обработчики видят её через `is_cancelled`. Возвращает `true`, если
+    /// событие не было отменено.
+    pub fn call_event(&self, event: &mut dyn Event) -> bool {
+        let type_id = Event::type_id(event);
         
         if let Some(handlers) = self.handlers.get(&type_id) {
             for handler in handlers {
-                if cancelled && handler.ignore_cancelled {
+                let was_cancelled = event.is_cancelled();
+                if was_cancelled && handler.ignore_cancelled {
                     continue;
                 }
                 
-                match (handler.handler)(event) {
-                    EventResult::Continue => {},
-                    EventResult::Cancel => {
-                        if event.is_cancellable() {
-                            cancelled = true;
-                        }
-                    }
+                let result = (handler.handler)(event);
+                
+                if handler.priority == EventPriority::Monitor {
+                    // Наблюдатели не влияют на исход события
+                    event.set_cancelled(was_cancelled);
+                    continue;
+                }
+                
+                if let EventResult::Cancel = result
+                    && event.is_cancellable()
+                {
+                    event.set_cancelled(true);
                 }
             }
         }
         
-        !cancelled
+        !event.is_cancelled()
+    }
+    
+    /// Проверить, есть ли обработчики для указанного типа события
+    pub fn has_handlers<E: Event + 'static>(&self) -> bool {
+        self.handlers
+            .get(&TypeId::of::<E>())
+            .is_some_and(|handlers| !handlers.is_empty())
     }
 }
 
 /// Событие подключения игрока
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct PlayerJoinEvent {
     /// ID игрока
     pub player_id: String,
@@ -161,11 +207,6 @@ impl PlayerJoinEvent {
     pub fn cancel(&mut self) {
         self.cancelled = true;
     }
-    
-    /// Проверить, отменено ли событие
-    pub fn is_cancelled(&self) -> bool {
-        self.cancelled
-    }
 }
 
 impl Event for PlayerJoinEvent {
@@ -176,14 +217,26 @@ impl Event for PlayerJoinEvent {
     fn is_cancellable(&self) -> bool {
         true
     }
+    
+    fn is_cancelled(&self) -> bool {
+        self.cancelled
+    }
+    
+    fn set_cancelled(&mut self, cancelled: bool) {
+        self.cancelled = cancelled;
+    }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 /// Событие отключения игрока
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct PlayerLeaveEvent {
     /// ID игрока
     pub player_id: String,
@@ -209,17 +262,27 @@ impl Event for PlayerLeaveEvent {
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
-/// Событие строительства здания
-#[derive(Debug)]
+/// Событие завершения строительства здания.
+/// Вызывается, когда задание очереди строительства выполнено; отмена
+/// отменяет задание с частичным возвратом ресурсов.
+#[derive(Debug, Clone)]
 pub struct BuildingConstructedEvent {
+    /// ID города
+    pub city_id: String,
     /// ID здания
     pub building_id: String,
+    /// Тип здания
+    pub building_type: BuildingType,
+    /// Уровень здания после завершения работ
+    pub level: u32,
     /// ID игрока, построившего здание
     pub player_id: String,
-    /// ID мира
-    pub world_id: String,
     /// Позиция здания
     pub position: (i32, i32),
     /// Отменено ли событие
@@ -228,11 +291,20 @@ pub struct BuildingConstructedEvent {
 
 impl BuildingConstructedEvent {
     /// Создать новое событие строительства здания
-    pub fn new(building_id: String, player_id: String, world_id: String, position: (i32, i32)) -> Self {
+    pub fn new(
+        city_id: String,
+        building_id: String,
+        building_type: BuildingType,
+        level: u32,
+        player_id: String,
+        position: (i32, i32),
+    ) -> Self {
         Self {
+            city_id,
             building_id,
+            building_type,
+            level,
             player_id,
-            world_id,
             position,
             cancelled: false,
         }
@@ -242,25 +314,562 @@ impl BuildingConstructedEvent {
     pub fn cancel(&mut self) {
         self.cancelled = true;
     }
+}
+
+impl Event for BuildingConstructedEvent {
+    fn name(&self) -> &str {
+        "building_constructed"
+    }
     
-    /// Проверить, отменено ли событие
-    pub fn is_cancelled(&self) -> bool {
+    fn is_cancellable(&self) -> bool {
+        true
+    }
+    
+    fn is_cancelled(&self) -> bool {
         self.cancelled
     }
+    
+    fn set_cancelled(&mut self, cancelled: bool) {
+        self.cancelled = cancelled;
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
-impl Event for BuildingConstructedEvent {
+/// Событие улучшения здания.
+/// Вызывается, когда улучшение из очереди строительства завершено, до смены
+/// уровня; отмена отменяет улучшение с частичным возвратом ресурсов.
+#[derive(Debug, Clone)]
+pub struct BuildingUpgradedEvent {
+    /// ID города
+    pub city_id: String,
+    /// ID здания
+    pub building_id: String,
+    /// Тип здания
+    pub building_type: BuildingType,
+    /// Текущий уровень здания
+    pub old_level: u32,
+    /// Уровень после улучшения
+    pub new_level: u32,
+    /// Отменено ли событие
+    cancelled: bool,
+}
+
+impl BuildingUpgradedEvent {
+    /// Создать новое событие улучшения здания
+    pub fn new(city_id: String, building_id: String, building_type: BuildingType, old_level: u32, new_level: u32) -> Self {
+        Self {
+            city_id,
+            building_id,
+            building_type,
+            old_level,
+            new_level,
+            cancelled: false,
+        }
+    }
+    
+    /// Отменить событие
+    pub fn cancel(&mut self) {
+        self.cancelled = true;
+    }
+}
+
+impl Event for BuildingUpgradedEvent {
     fn name(&self) -> &str {
-        "building_constructed"
+        "building_upgraded"
     }
     
     fn is_cancellable(&self) -> bool {
         true
     }
+    
+    fn is_cancelled(&self) -> bool {
+        self.cancelled
+    }
+    
+    fn set_cancelled(&mut self, cancelled: bool) {
+        self.cancelled = cancelled;
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Событие изменения населения города за ход
+#[derive(Debug, Clone)]
+pub struct PopulationChangedEvent {
+    /// ID города
+    pub city_id: String,
+    /// Население до изменения
+    pub old_population: u32,
+    /// Население после изменения
+    pub new_population: u32,
+}
+
+impl PopulationChangedEvent {
+    /// Создать новое событие изменения населения
+    pub fn new(city_id: String, old_population: u32, new_population: u32) -> Self {
+        Self {
+            city_id,
+            old_population,
+            new_population,
+        }
+    }
+}
+
+impl Event for PopulationChangedEvent {
+    fn name(&self) -> &str {
+        "population_changed"
+    }
+    
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Событие нехватки ресурсов на содержание здания.
+/// Вызывается после списания содержания за ход.
+#[derive(Debug, Clone)]
+pub struct ResourceShortageEvent {
+    /// ID города
+    pub city_id: String,
+    /// ID здания
+    pub building_id: String,
+    /// Тип здания
+    pub building_type: BuildingType,
+    /// Недополученные ресурсы
+    pub missing: Vec<(ResourceType, u32)>,
+    /// Доля обеспечения здания (0 — здание отключено)
+    pub efficiency: f32,
+}
+
+impl ResourceShortageEvent {
+    /// Создать новое событие нехватки ресурсов
+    pub fn new(
+        city_id: String,
+        building_id: String,
+        building_type: BuildingType,
+        missing: Vec<(ResourceType, u32)>,
+        efficiency: f32,
+    ) -> Self {
+        Self {
+            city_id,
+            building_id,
+            building_type,
+            missing,
+            efficiency,
+        }
+    }
+}
+
+impl Event for ResourceShortageEvent {
+    fn name(&self) -> &str {
+        "resource_shortage"
+    }
+    
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Событие волнений класса населения: забастовки или бунта.
+/// Вызывается каждый ход, пока волнения продолжаются.
+#[derive(Debug, Clone)]
+pub struct UnrestEvent {
+    /// ID города
+    pub city_id: String,
+    /// Недовольный класс
+    pub class: PopulationClass,
+    /// Забастовка или бунт
+    pub unrest: Unrest,
+    /// ID здания, пострадавшего от бунта
+    pub damaged_building: Option<String>,
+}
+
+impl UnrestEvent {
+    /// Создать новое событие волнений
+    pub fn new(city_id: String, class: PopulationClass, unrest: Unrest, damaged_building: Option<String>) -> Self {
+        Self {
+            city_id,
+            class,
+            unrest,
+            damaged_building,
+        }
+    }
+}
+
+impl Event for UnrestEvent {
+    fn name(&self) -> &str {
+        "unrest"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Событие банкротства города: долг казны достиг `BANKRUPTCY_DEBT`.
+/// Вызывается один раз при переходе в банкротство.
+#[derive(Debug, Clone)]
+pub struct BankruptcyEvent {
+    /// ID города
+    pub city_id: String,
+    /// Долг казны
+    pub debt: u32,
+}
+
+impl BankruptcyEvent {
+    /// Создать новое событие банкротства
+    pub fn new(city_id: String, debt: u32) -> Self {
+        Self { city_id, debt }
+    }
+}
+
+impl Event for BankruptcyEvent {
+    fn name(&self) -> &str {
+        "bankruptcy"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Событие отмены политики, которую казна города не смогла оплатить
+#[derive(Debug, Clone)]
+pub struct PolicyLapsedEvent {
+    /// ID города
+    pub city_id: String,
+    /// Отмененная политика
+    pub policy: PolicyType,
+}
+
+impl PolicyLapsedEvent {
+    /// Создать новое событие отмены политики
+    pub fn new(city_id: String, policy: PolicyType) -> Self {
+        Self { city_id, policy }
+    }
+}
+
+impl Event for PolicyLapsedEvent {
+    fn name(&self) -> &str {
+        "policy_lapsed"
+    }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Событие завершения исследования
+#[derive(Debug, Clone)]
+pub struct ResearchCompletedEvent {
+    /// ID игрока, владеющего деревом технологий
+    pub player_id: String,
+    /// Исследованная технология
+    pub technology: TechnologyType,
+}
+
+impl ResearchCompletedEvent {
+    /// Создать новое событие завершения исследования
+    pub fn new(player_id: String, technology: TechnologyType) -> Self {
+        Self {
+            player_id,
+            technology,
+        }
+    }
+}
+
+impl Event for ResearchCompletedEvent {
+    fn name(&self) -> &str {
+        "research_completed"
+    }
+    
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Событие сделки на рынке города.
+/// Вызывается до изменения запасов рынка; отмена запрещает сделку.
+#[derive(Debug, Clone)]
+pub struct MarketTradeEvent {
+    /// ID города, которому принадлежит рынок
+    pub city_id: String,
+    /// Покупка или продажа
+    pub direction: TradeDirection,
+    /// Ресурс
+    pub resource_type: ResourceType,
+    /// Количество
+    pub amount: u32,
+    /// Итоговая стоимость сделки
+    pub total_price: f32,
+    /// Отменено ли событие
+    cancelled: bool,
+}
+
+impl MarketTradeEvent {
+    /// Создать новое событие сделки
+    pub fn new(city_id: String, direction: TradeDirection, resource_type: ResourceType, amount: u32, total_price: f32) -> Self {
+        Self {
+            city_id,
+            direction,
+            resource_type,
+            amount,
+            total_price,
+            cancelled: false,
+        }
+    }
+    
+    /// Отменить событие
+    pub fn cancel(&mut self) {
+        self.cancelled = true;
+    }
+}
+
+impl Event for MarketTradeEvent {
+    fn name(&self) -> &str {
+        "market_trade"
+    }
+    
+    fn is_cancellable(&self) -> bool {
+        true
+    }
+    
+    fn is_cancelled(&self) -> bool {
+        self.cancelled
+    }
+    
+    fn set_cancelled(&mut self, cancelled: bool) {
+        self.cancelled = cancelled;
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Событие изменения дипломатических отношений.
+/// Вызывается до применения изменения; отмена сохраняет прежние отношения.
+#[derive(Debug, Clone)]
+pub struct RelationChangedEvent {
+    /// Первая фракция
+    pub faction1: String,
+    /// Вторая фракция
+    pub faction2: String,
+    /// Репутация до изменения
+    pub old_reputation: i32,
+    /// Репутация после изменения
+    pub new_reputation: i32,
+    /// Тип отношений до изменения
+    pub old_relation: RelationType,
+    /// Тип отношений после изменения
+    pub new_relation: RelationType,
+    /// Отменено ли событие
+    cancelled: bool,
+}
+
+impl RelationChangedEvent {
+    /// Создать новое событие изменения отношений
+    pub fn new(
+        faction1: String,
+        faction2: String,
+        old_reputation: i32,
+        new_reputation: i32,
+        old_relation: RelationType,
+        new_relation: RelationType,
+    ) -> Self {
+        Self {
+            faction1,
+            faction2,
+            old_reputation,
+            new_reputation,
+            old_relation,
+            new_relation,
+            cancelled: false,
+        }
+    }
+    
+    /// Отменить событие
+    pub fn cancel(&mut self) {
+        self.cancelled = true;
+    }
+}
+
+impl Event for RelationChangedEvent {
+    fn name(&self) -> &str {
+        "relation_changed"
+    }
+    
+    fn is_cancellable(&self) -> bool {
+        true
+    }
+    
+    fn is_cancelled(&self) -> bool {
+        self.cancelled
+    }
+    
+    fn set_cancelled(&mut self, cancelled: bool) {
+        self.cancelled = cancelled;
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Событие завершения квеста.
+/// Вызывается до смены статуса квеста; отмена оставляет квест активным.
+#[derive(Debug, Clone)]
+pub struct QuestCompletedEvent {
+    /// ID игрока
+    pub player_id: String,
+    /// ID квеста
+    pub quest_id: String,
+    /// Отменено ли событие
+    cancelled: bool,
+}
+
+impl QuestCompletedEvent {
+    /// Создать новое событие завершения квеста
+    pub fn new(player_id: String, quest_id: String) -> Self {
+        Self {
+            player_id,
+            quest_id,
+            cancelled: false,
+        }
+    }
+    
+    /// Отменить событие
+    pub fn cancel(&mut self) {
+        self.cancelled = true;
+    }
+}
+
+impl Event for QuestCompletedEvent {
+    fn name(&self) -> &str {
+        "quest_completed"
+    }
+    
+    fn is_cancellable(&self) -> bool {
+        true
+    }
+    
+    fn is_cancelled(&self) -> bool {
+        self.cancelled
+    }
+    
+    fn set_cancelled(&mut self, cancelled: bool) {
+        self.cancelled = cancelled;
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Событие срабатывания случайного события в городе.
+/// Вызывается до активации; отмена предотвращает случайное событие.
+#[derive(Debug, Clone)]
+pub struct RandomEventTriggeredEvent {
+    /// ID города
+    pub city_id: String,
+    /// ID случайного события
+    pub event_id: String,
+    /// Серьезность случайного события
+    pub severity: EventSeverity,
+    /// Отменено ли событие
+    cancelled: bool,
+}
+
+impl RandomEventTriggeredEvent {
+    /// Создать новое событие срабатывания случайного события
+    pub fn new(city_id: String, event_id: String, severity: EventSeverity) -> Self {
+        Self {
+            city_id,
+            event_id,
+            severity,
+            cancelled: false,
+        }
+    }
+    
+    /// Отменить событие
+    pub fn cancel(&mut self) {
+        self.cancelled = true;
+    }
+}
+
+impl Event for RandomEventTriggeredEvent {
+    fn name(&self) -> &str {
+        "random_event_triggered"
+    }
+    
+    fn is_cancellable(&self) -> bool {
+        true
+    }
+    
+    fn is_cancelled(&self) -> bool {
+        self.cancelled
+    }
+    
+    fn set_cancelled(&mut self, cancelled: bool) {
+        self.cancelled = cancelled;
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -5,7 +5,7 @@ use std::collections::HashMap;
 use crate::resources::ResourceType;
 
 /// Специализации фракций, определяющие их основные бонусы
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
 pub enum FactionSpecialization {
     /// Фокус на торговле и коммерции
     Trade,
@@ -18,15 +18,10 @@ pub enum FactionSpecialization {
     /// Фокус на военной мощи
     Military,
     /// Без явной специализации
+    #[default]
     Balanced,
 }
 
-impl Default for FactionSpecialization {
-    fn default() -> Self {
-        FactionSpecialization::Balanced
-    }
-}
-
 /// Бонус или штраф для определенного аспекта игры
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum FactionBonus {
@@ -156,6 +151,14 @@ impl Faction {
             .sum()
     }
 
+    /// Модификатор скорости строительства (процентное изменение)
+    pub fn building_speed_modifier(&self) -> i32 {
+        self.get_modifier_for_type(|bonus| match bonus {
+            FactionBonus::BuildingSpeed(modifier) => Some(*modifier),
+            _ => None,
+        })
+    }
+
     /// Проверяет, имеет ли фракция доступ к указанному уникальному зданию
     pub fn has_unique_building(&self, building_id: &str) -> bool {
         self.bonuses
@@ -258,6 +261,15 @@ impl FactionManager {
         self.factions.values().collect()
     }
 
+    /// Получает фракцию, которой принадлежит город.
+    /// Если город числится за несколькими фракциями, берется фракция с наименьшим ID.
+    pub fn get_city_faction(&self, city_id: &str) -> Option<&Faction> {
+        self.factions
+            .values()
+            .filter(|faction| faction.cities.iter().any(|id| id == city_id))
+            .min_by(|a, b| a.id.cmp(&b.id))
+    }
+
     /// Устанавливает фракцию игрока
     pub fn set_player_faction(&mut self, faction_id: &str) -> bool {
         if let Some(faction) = self.factions.get_mut(faction_id) {
@@ -0,0 +1,139 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::building::BuildingType;
+use crate::population::{Population, PopulationClass};
+
+/// Рабочие места здания и занятые на них жители
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JobAssignment {
+    pub building_id: String,
+    pub building_type: BuildingType,
+    /// Рабочие места по классам
+    pub slots: BTreeMap<PopulationClass, u32>,
+    /// Занятые места по классам
+    pub assigned: BTreeMap<PopulationClass, u32>,
+}
+
+impl JobAssignment {
+    /// Доля занятых мест (от 0 до 1); здание без рабочих мест
+    /// укомплектовано всегда
+    pub fn staffing(&self) -> f32 {
+        self.working_staffing(&[])
+    }
+
+    /// Доля мест, на которых действительно работают: жители
+    /// бастующих классов (`striking`) на работу не выходят
+    pub fn working_staffing(&self, striking: &[PopulationClass]) -> f32 {
+        let slots: u32 = self.slots.values().sum();
+        if slots == 0 {
+            return 1.0;
+        }
+        let working: u32 = self
+            .assigned
+            .iter()
+            .filter(|(class, _)| !striking.contains(class))
+            .map(|(_, assigned)| assigned)
+            .sum();
+        working as f32 / slots as f32
+    }
+
+    /// Свободные места по классам
+    pub fn vacancies(&self) -> BTreeMap<PopulationClass, u32> {
+        self.slots
+            .iter()
+            .map(|(class, slots)| {
+                let assigned = self.assigned.get(class).copied().unwrap_or(0);
+                (class.clone(), slots.saturating_sub(assigned))
+            })
+            .filter(|(_, vacant)| *vacant > 0)
+            .collect()
+    }
+}
+
+/// Ручная настройка занятости: сколько жителей класса держать на здании.
+/// Классы без настройки распределяются автоматически.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JobOverrides {
+    pub buildings: BTreeMap<String, BTreeMap<PopulationClass, u32>>,
+}
+
+impl JobOverrides {
+    /// Настройка для класса на здании
+    pub fn get(&self, building_id: &str, class: &PopulationClass) -> Option<u32> {
+        self.buildings.get(building_id).and_then(|classes| classes.get(class)).copied()
+    }
+
+    /// Задает число жителей класса на здании
+    pub fn set(&mut self, building_id: &str, class: PopulationClass, workers: u32) {
+        self.buildings.entry(building_id.to_string()).or_default().insert(class, workers);
+    }
+
+    /// Возвращает здание к автоматическому распределению
+    pub fn clear(&mut self, building_id: &str) {
+        self.buildings.remove(building_id);
+    }
+}
+
+/// Распределяет жителей по рабочим местам.
+///
+/// Сначала заполняются места с ручной настройкой (не больше числа мест),
+/// затем остальные места — по порядку зданий в `buildings`, пока хватает
+/// жителей нужного класса.
+pub fn assign_jobs(
+    buildings: &[(String, BuildingType, BTreeMap<PopulationClass, u32>)],
+    population: &Population,
+    overrides: &JobOverrides,
+) -> Vec<JobAssignment> {
+    let mut free: BTreeMap<PopulationClass, u32> = population.classes.clone();
+    let mut hire = |class: &PopulationClass, wanted: u32| {
+        let available = free.entry(class.clone()).or_insert(0);
+        let hired = wanted.min(*available);
+        *available -= hired;
+        hired
+    };
+
+    let mut assignments: Vec<JobAssignment> = buildings
+        .iter()
+        .map(|(building_id, building_type, slots)| JobAssignment {
+            building_id: building_id.clone(),
+            building_type: building_type.clone(),
+            slots: slots.clone(),
+            assigned: BTreeMap::new(),
+        })
+        .collect();
+
+    for assignment in &mut assignments {
+        for (class, slots) in &assignment.slots {
+            if let Some(wanted) = overrides.get(&assignment.building_id, class) {
+                let hired = hire(class, wanted.min(*slots));
+                assignment.assigned.insert(class.clone(), hired);
+            }
+        }
+    }
+
+    for assignment in &mut assignments {
+        for (class, slots) in &assignment.slots {
+            if overrides.get(&assignment.building_id, class).is_none() {
+                let hired = hire(class, *slots);
+                assignment.assigned.insert(class.clone(), hired);
+            }
+        }
+    }
+
+    assignments
+}
+
+/// Жители без работы по классам
+pub fn unemployed(population: &Population, assignments: &[JobAssignment]) -> BTreeMap<PopulationClass, u32> {
+    let mut unemployed = population.classes.clone();
+    for assignment in assignments {
+        for (class, assigned) in &assignment.assigned {
+            if let Some(count) = unemployed.get_mut(class) {
+                *count = count.saturating_sub(*assigned);
+            }
+        }
+    }
+    unemployed.retain(|_, count| *count > 0);
+    unemployed
+}
@@ -0,0 +1,290 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+
+use crate::building::BuildingType;
+use crate::policies::PolicyType;
+use crate::population::PopulationClass;
+use crate::resources::ResourceType;
+
+/// Сколько последних ходов хранит журнал по умолчанию
+pub const DEFAULT_LEDGER_HISTORY: usize = 50;
+
+/// Источник или получатель ресурсов
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum LedgerSource {
+    /// Базовое производство города
+    BaseProduction,
+    /// Обрабатываемые клетки территории города на карте мира
+    WorkedTiles,
+    /// Производство здания
+    Building { building_id: String, building_type: BuildingType },
+    /// Бонус соседства здания
+    Adjacency { building_id: String, building_type: BuildingType },
+    /// Добыча зданием из месторождения рядом с городом
+    Deposit { building_id: String, building_type: BuildingType },
+    /// Переработка сырья зданием (входы со знаком минус, выходы со знаком плюс)
+    Recipe { building_id: String, building_type: BuildingType },
+    /// Модификатор местности
+    Terrain,
+    /// Бонус фракции
+    FactionBonus,
+    /// Бонус технологий
+    TechnologyBonus,
+    /// Бонус от состава населения
+    PopulationBonus,
+    /// Модификатор политик города
+    PolicyBonus,
+    /// Случайное событие (ID события)
+    Event(String),
+    /// Торговля на рынке
+    Trade,
+    /// Содержание здания
+    Upkeep { building_id: String, building_type: BuildingType },
+    /// Строительство и возврат ресурсов при отмене
+    Construction,
+    /// Порча запасов
+    Spoilage,
+    /// Потребление еды жителями
+    Consumption,
+    /// Излишки: потерянные или полученные от соседнего города (ID города)
+    Overflow(String),
+    /// Подоходный налог класса
+    IncomeTax(PopulationClass),
+    /// Торговые пошлины с маршрутов, проходящих через город
+    Tariff,
+    /// Содержание зданий из казны
+    Maintenance,
+    /// Погашение долга казны
+    DebtRepayment,
+    /// Стоимость политики
+    Policy(PolicyType),
+}
+
+impl LedgerSource {
+    /// Категория для сводок и выгрузки
+    pub fn category(&self) -> &'static str {
+        match self {
+            LedgerSource::BaseProduction => "base_production",
+            LedgerSource::WorkedTiles => "worked_tiles",
+            LedgerSource::Building { .. } => "building",
+            LedgerSource::Adjacency { .. } => "adjacency",
+            LedgerSource::Deposit { .. } => "deposit",
+            LedgerSource::Recipe { .. } => "recipe",
+            LedgerSource::Terrain => "terrain",
+            LedgerSource::FactionBonus => "faction_bonus",
+            LedgerSource::TechnologyBonus => "technology_bonus",
+            LedgerSource::PopulationBonus => "population_bonus",
+            LedgerSource::PolicyBonus => "policy_bonus",
+            LedgerSource::Event(_) => "event",
+            LedgerSource::Trade => "trade",
+            LedgerSource::Upkeep { .. } => "upkeep",
+            LedgerSource::Construction => "construction",
+            LedgerSource::Spoilage => "spoilage",
+            LedgerSource::Consumption => "consumption",
+            LedgerSource::Overflow(_) => "overflow",
+            LedgerSource::IncomeTax(_) => "income_tax",
+            LedgerSource::Tariff => "tariff",
+            LedgerSource::Maintenance => "maintenance",
+            LedgerSource::DebtRepayment => "debt_repayment",
+            LedgerSource::Policy(_) => "policy",
+        }
+    }
+
+    /// Уточнение источника: ID здания, события или города, класс населения, политика
+    pub fn detail(&self) -> String {
+        match self {
+            LedgerSource::Building { building_id, .. }
+            | LedgerSource::Adjacency { building_id, .. }
+            | LedgerSource::Deposit { building_id, .. }
+            | LedgerSource::Recipe { building_id, .. }
+            | LedgerSource::Upkeep { building_id, .. } => building_id.clone(),
+            LedgerSource::Event(id) | LedgerSource::Overflow(id) => id.clone(),
+            LedgerSource::IncomeTax(class) => format!("{:?}", class),
+            LedgerSource::Policy(policy) => format!("{:?}", policy),
+            _ => String::new(),
+        }
+    }
+}
+
+/// Одна запись журнала: изменение ресурса из одного источника
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    pub source: LedgerSource,
+    pub resource: ResourceType,
+    /// Положительное значение — доход, отрицательное — расход
+    pub amount: i64,
+}
+
+/// Записи журнала за один ход
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TurnLedger {
+    pub turn: u64,
+    pub entries: Vec<LedgerEntry>,
+}
+
+impl TurnLedger {
+    /// Итоговое изменение ресурса за ход
+    pub fn net(&self, resource: &ResourceType) -> i64 {
+        self.entries
+            .iter()
+            .filter(|entry| entry.resource == *resource)
+            .map(|entry| entry.amount)
+            .sum()
+    }
+
+    /// Доходы ресурса за ход
+    pub fn income(&self, resource: &ResourceType) -> i64 {
+        self.entries
+            .iter()
+            .filter(|entry| entry.resource == *resource && entry.amount > 0)
+            .map(|entry| entry.amount)
+            .sum()
+    }
+
+    /// Расходы ресурса за ход (положительное число)
+    pub fn expenses(&self, resource: &ResourceType) -> i64 {
+        self.entries
+            .iter()
+            .filter(|entry| entry.resource == *resource && entry.amount < 0)
+            .map(|entry| -entry.amount)
+            .sum()
+    }
+}
+
+/// Экономический журнал города.
+///
+/// Каждый ход записывает все источники и получателей ресурсов: базовое
+/// производство, здания, местность, бонусы фракции и технологий, события,
+/// торговлю, содержание, строительство, порчу и излишки. Хранит последние
+/// `history` ходов.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EconomicLedger {
+    turns: VecDeque<TurnLedger>,
+    history: usize,
+}
+
+impl Default for EconomicLedger {
+    fn default() -> Self {
+        Self::new(DEFAULT_LEDGER_HISTORY)
+    }
+}
+
+impl EconomicLedger {
+    /// Создает журнал, хранящий `history` последних ходов
+    pub fn new(history: usize) -> Self {
+        EconomicLedger {
+            turns: VecDeque::new(),
+            history: history.max(1),
+        }
+    }
+
+    /// Сколько ходов хранит журнал
+    pub fn history(&self) -> usize {
+        self.history
+    }
+
+    /// Начинает записи хода `turn`. Повторный вызов для того же хода ничего
+    /// не меняет; самые старые ходы сверх `history` удаляются.
+    pub fn begin_turn(&mut self, turn: u64) {
+        if self.turns.back().is_some_and(|ledger| ledger.turn == turn) {
+            return;
+        }
+
+        self.turns.push_back(TurnLedger {
+            turn,
+            entries: Vec::new(),
+        });
+        while self.turns.len() > self.history {
+            self.turns.pop_front();
+        }
+    }
+
+    /// Записывает изменение ресурса в текущий ход. Нулевые изменения
+    /// не записываются.
+    pub fn record(&mut self, source: LedgerSource, resource: ResourceType, amount: i64) {
+        if amount == 0 {
+            return;
+        }
+        if self.turns.is_empty() {
+            self.begin_turn(0);
+        }
+        if let Some(current) = self.turns.back_mut() {
+            current.entries.push(LedgerEntry {
+                source,
+                resource,
+                amount,
+            });
+        }
+    }
+
+    /// Записи текущего хода
+    pub fn current(&self) -> Option<&TurnLedger> {
+        self.turns.back()
+    }
+
+    /// Записи хода `turn`, если он еще хранится
+    pub fn turn(&self, turn: u64) -> Option<&TurnLedger> {
+        self.turns.iter().find(|ledger| ledger.turn == turn)
+    }
+
+    /// Последние `n` ходов, от старых к новым
+    pub fn last(&self, n: usize) -> Vec<&TurnLedger> {
+        let skip = self.turns.len().saturating_sub(n);
+        self.turns.iter().skip(skip).collect()
+    }
+
+    /// Итоговое изменение ресурса за последние `n` ходов
+    pub fn net(&self, resource: &ResourceType, n: usize) -> i64 {
+        self.last(n).iter().map(|ledger| ledger.net(resource)).sum()
+    }
+
+    /// Отчет о доходах и расходах за последние `n` ходов:
+    /// категория источника -> ресурс -> суммарное изменение
+    pub fn income_statement(&self, n: usize) -> BTreeMap<String, BTreeMap<ResourceType, i64>> {
+        let mut statement: BTreeMap<String, BTreeMap<ResourceType, i64>> = BTreeMap::new();
+        for ledger in self.last(n) {
+            for entry in &ledger.entries {
+                *statement
+                    .entry(entry.source.category().to_string())
+                    .or_default()
+                    .entry(entry.resource.clone())
+                    .or_insert(0) += entry.amount;
+            }
+        }
+        statement
+    }
+
+    /// Выгружает последние `n` ходов в JSON
+    pub fn to_json(&self, n: usize) -> Result<String, String> {
+        serde_json::to_string_pretty(&self.last(n))
+            .map_err(|e| format!("Ошибка записи JSON: {}", e))
+    }
+
+    /// Выгружает последние `n` ходов в CSV со столбцами
+    /// `turn,category,detail,resource,amount`
+    pub fn to_csv(&self, n: usize) -> String {
+        let mut csv = String::from("turn,category,detail,resource,amount\n");
+        for ledger in self.last(n) {
+            for entry in &ledger.entries {
+                csv.push_str(&format!(
+                    "{},{},{},{:?},{}\n",
+                    ledger.turn,
+                    entry.source.category(),
+                    csv_field(&entry.source.detail()),
+                    entry.resource,
+                    entry.amount
+                ));
+            }
+        }
+        csv
+    }
+}
+
+/// Экранирует поле CSV, если в нем есть разделители или кавычки
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -1,28 +1,47 @@
 pub mod account;
 pub mod building;
+pub mod building_registry;
 pub mod chat;
 pub mod city;
+pub mod city_grid;
+pub mod clock;
 pub mod commands;
+pub mod construction;
 pub mod diplomacy;
+pub mod event_bus;
 pub mod events;
 pub mod faction;
 pub mod generator;
 pub mod item;
+pub mod jobs;
+pub mod ledger;
 pub mod market;
+pub mod noise;
 pub mod player;
 pub mod plugin;
+pub mod policies;
 pub mod population;
+pub mod production;
 pub mod quest;
 pub mod random_events;
 pub mod resources;
+pub mod rng;
+pub mod storage;
+pub mod taxation;
 pub mod technology;
+pub mod territory;
+pub mod upkeep;
 pub mod world;
 pub mod achievements;
 
 // Модуль prelude для удобного импорта часто используемых типов
 pub mod prelude {
     // Ресурсы
-    pub use crate::resources::{ResourceType, BuildingType, BuildingEffect};
+    pub use crate::resources::{ResourceType, BuildingEffect};
+
+    // Здания
+    pub use crate::building::{Building, BuildingType};
+    pub use crate::building_registry::{BuildingDefinition, BuildingRegistry};
     
     // События
     pub use crate::events::{Event, EventSystem, EventPriority, EventResult};
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use rand::Rng;
+use crate::clock::GameClock;
+use crate::events::{EventSystem, MarketTradeEvent};
 use crate::resources::ResourceType;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,6 +25,12 @@ pub enum StockLevel {
     Surplus,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TradeDirection {
+    Buy,
+    Sell,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Market {
     items: HashMap<ResourceType, MarketItem>,
@@ -32,6 +40,12 @@ pub struct Market {
     last_update: u64,
 }
 
+impl Default for Market {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Market {
     pub fn new() -> Self {
         let mut items = HashMap::new();
@@ -75,14 +89,19 @@ impl Market {
         }
     }
     
-    pub fn update_prices(&mut self, turn: u64) {
-        let mut rng = rand::rng();
-        
+    pub fn update_prices<R: Rng + ?Sized>(&mut self, clock: &GameClock, rng: &mut R) {
+        let turn = clock.turn();
         if self.last_update >= turn {
             return;
         }
         
-        for (resource_type, item) in self.items.iter_mut() {
+        // Обходим товары в фиксированном порядке, чтобы случайные числа
+        // распределялись по ресурсам одинаково при одинаковом зерне
+        let mut resource_types: Vec<ResourceType> = self.items.keys().cloned().collect();
+        resource_types.sort();
+        
+        for resource_type in &resource_types {
+            let item = self.items.get_mut(resource_type).unwrap();
             let demand = self.demand_factor.get(resource_type).cloned().unwrap_or(1.0);
             let supply = self.supply_factor.get(resource_type).cloned().unwrap_or(1.0);
             
@@ -122,8 +141,8 @@ impl Market {
             *self.demand_factor.entry(resource_type.clone()).or_insert(1.0) *= demand_change;
             *self.supply_factor.entry(resource_type.clone()).or_insert(1.0) *= supply_change;
             
-            *self.demand_factor.get_mut(resource_type).unwrap() = self.demand_factor[resource_type].max(0.5).min(2.0);
-            *self.supply_factor.get_mut(resource_type).unwrap() = self.supply_factor[resource_type].max(0.5).min(2.0);
+            *self.demand_factor.get_mut(resource_type).unwrap() = self.demand_factor[resource_type].clamp(0.5, 2.0);
+            *self.supply_factor.get_mut(resource_type).unwrap() = self.supply_factor[resource_type].clamp(0.5, 2.0);
         }
         
         self.last_update = turn;
@@ -286,6 +305,13 @@ pub struct TradeRoute {
     pub duration: u32, // в ходах
 }
 
+impl TradeRoute {
+    /// Стоимость товара, проходящего по маршруту за ход
+    pub fn value(&self) -> u32 {
+        (self.price_per_unit * self.quantity as f32).max(0.0) as u32
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TradeManager {
     pub trade_routes: Vec<TradeRoute>,
@@ -302,7 +328,7 @@ impl TradeManager {
     
     // Создание рынка для города
     pub fn create_city_market(&mut self, city_name: &str) -> &mut Market {
-        self.city_markets.entry(city_name.to_string()).or_insert_with(Market::new)
+        self.city_markets.entry(city_name.to_string()).or_default()
     }
     
     // Добавление торгового маршрута
@@ -320,10 +346,44 @@ impl TradeManager {
         self.city_markets.get_mut(city_name)
     }
     
+    // Покупка на рынке города с вызовом MarketTradeEvent
+    pub fn buy(&mut self, city_id: &str, resource_type: &ResourceType, amount: u32, events: &EventSystem) -> Option<f32> {
+        let market = self.city_markets.get_mut(city_id)?;
+        if !market.has_resource(resource_type, amount) {
+            return None;
+        }
+        
+        let total_price = market.get_buy_price(resource_type)? * amount as f32;
+        let mut event = MarketTradeEvent::new(city_id.to_string(), TradeDirection::Buy, resource_type.clone(), amount, total_price);
+        if !events.call_event(&mut event) {
+            return None;
+        }
+        
+        market.buy(resource_type, amount)
+    }
+    
+    // Продажа на рынке города с вызовом MarketTradeEvent
+    pub fn sell(&mut self, city_id: &str, resource_type: &ResourceType, amount: u32, events: &EventSystem) -> Option<f32> {
+        let market = self.city_markets.get_mut(city_id)?;
+        
+        let total_price = market.get_sell_price(resource_type)? * amount as f32;
+        let mut event = MarketTradeEvent::new(city_id.to_string(), TradeDirection::Sell, resource_type.clone(), amount, total_price);
+        if !events.call_event(&mut event) {
+            return None;
+        }
+        
+        market.sell(resource_type, amount)
+    }
+    
     // Обновление всех рынков городов
-    pub fn update_all_markets(&mut self, turn: u64) {
-        for market in self.city_markets.values_mut() {
-            market.update_prices(turn);
+    pub fn update_all_markets<R: Rng + ?Sized>(&mut self, clock: &GameClock, rng: &mut R) {
+        let mut city_names: Vec<String> = self.city_markets.keys().cloned().collect();
+        city_names.sort();
+        
+        for city_name in &city_names {
+            if let Some(market) = self.city_markets.get_mut(city_name) {
+                market.update_prices(clock, rng);
+            }
         }
         
         // Медленное восстановление здоровья рынков
@@ -0,0 +1,81 @@
+use rand::seq::SliceRandom;
+
+use crate::rng;
+
+/// Двумерный градиентный шум Перлина.
+///
+/// Перестановка строится из зерна, поэтому одно и то же зерно всегда дает
+/// один и тот же шум. Значения плавно меняются между соседними точками,
+/// что позволяет получать связные материки, горные хребты и леса.
+#[derive(Debug, Clone)]
+pub struct PerlinNoise {
+    permutation: Vec<u8>,
+}
+
+impl PerlinNoise {
+    pub fn new(seed: u64) -> Self {
+        let mut values: Vec<u8> = (0..=255).collect();
+        values.shuffle(&mut rng::seeded(seed));
+        let permutation = values.iter().chain(values.iter()).copied().collect();
+        PerlinNoise { permutation }
+    }
+
+    /// Шум в точке, примерно от -1 до 1
+    pub fn get(&self, x: f64, y: f64) -> f64 {
+        let cell_x = x.floor();
+        let cell_y = y.floor();
+        let xi = (cell_x as i64 & 255) as usize;
+        let yi = (cell_y as i64 & 255) as usize;
+        let xf = x - cell_x;
+        let yf = y - cell_y;
+
+        let p = &self.permutation;
+        let aa = p[p[xi] as usize + yi];
+        let ab = p[p[xi] as usize + yi + 1];
+        let ba = p[p[xi + 1] as usize + yi];
+        let bb = p[p[xi + 1] as usize + yi + 1];
+
+        let u = fade(xf);
+        let v = fade(yf);
+        let bottom = lerp(gradient(aa, xf, yf), gradient(ba, xf - 1.0, yf), u);
+        let top = lerp(gradient(ab, xf, yf - 1.0), gradient(bb, xf - 1.0, yf - 1.0), u);
+        lerp(bottom, top, v)
+    }
+
+    /// Сумма `octaves` слоев шума: каждый следующий слой в `lacunarity` раз
+    /// мельче и в `persistence` раз слабее. Результат приведен к диапазону от 0 до 1.
+    pub fn fractal(&self, x: f64, y: f64, octaves: u32, persistence: f64, lacunarity: f64) -> f64 {
+        let mut total = 0.0;
+        let mut amplitude = 1.0;
+        let mut frequency = 1.0;
+        let mut max = 0.0;
+        for _ in 0..octaves.max(1) {
+            total += self.get(x * frequency, y * frequency) * amplitude;
+            max += amplitude;
+            amplitude *= persistence;
+            frequency *= lacunarity;
+        }
+        ((total / max + 1.0) / 2.0).clamp(0.0, 1.0)
+    }
+}
+
+fn fade(t: f64) -> f64 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(a: f64, b: f64, t: f64) -> f64 {
+    a + t * (b - a)
+}
+
+fn gradient(hash: u8, x: f64, y: f64) -> f64 {
+    match hash & 7 {
+        0 => x + y,
+        1 => -x + y,
+        2 => x - y,
+        3 => -x - y,
+        4 => x,
+        5 => -x,
+        6 => y,
+        _ => -y,
+    }
+}
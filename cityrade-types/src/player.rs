@@ -1,8 +1,8 @@
 use crate::city::City;
+use crate::clock::GameClock;
 use crate::resources::{Resource, ResourceType};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use uuid::Uuid;
 
 /// Структура игрока
 /// 
@@ -53,9 +53,9 @@ pub struct Player {
 
 impl Player {
     /// Создает нового игрока
-    pub fn new(name: String, account_id: String) -> Self {
+    pub fn new(id: String, name: String, account_id: String, clock: &GameClock) -> Self {
         Self {
-            id: Uuid::new_v4().to_string(),
+            id,
             name,
             account_id,
             world_id: None,
@@ -66,14 +66,13 @@ impl Player {
             experience: 0,
             achievements: HashMap::new(),
             metadata: HashMap::new(),
-            last_activity: chrono::Utc::now(),
+            last_activity: clock.now(),
             online: false,
         }
     }
     
     /// Добавляет опыт игроку и повышает уровень при необходимости
     pub fn add_experience(&mut self, amount: u64) -> bool {
-        let old_level = self.level;
         self.experience += amount;
         
         // Формула расчета уровня: level = sqrt(experience / 100)
@@ -109,32 +108,32 @@ impl Player {
     }
     
     /// Обновляет позицию игрока
-    pub fn update_position(&mut self, x: i32, y: i32) {
+    pub fn update_position(&mut self, x: i32, y: i32, clock: &GameClock) {
         self.position = (x, y);
-        self.last_activity = chrono::Utc::now();
+        self.last_activity = clock.now();
     }
     
     /// Входит в мир
-    pub fn join_world(&mut self, world_id: String, position: (i32, i32)) {
+    pub fn join_world(&mut self, world_id: String, position: (i32, i32), clock: &GameClock) {
         self.world_id = Some(world_id);
         self.position = position;
         self.online = true;
-        self.last_activity = chrono::Utc::now();
+        self.last_activity = clock.now();
     }
     
     /// Выходит из мира
-    pub fn leave_world(&mut self) {
+    pub fn leave_world(&mut self, clock: &GameClock) {
         self.online = false;
-        self.last_activity = chrono::Utc::now();
+        self.last_activity = clock.now();
     }
     
     /// Добавляет достижение
-    pub fn add_achievement(&mut self, achievement_id: String) -> bool {
+    pub fn add_achievement(&mut self, achievement_id: String, clock: &GameClock) -> bool {
         if self.achievements.contains_key(&achievement_id) {
             return false; // Достижение уже получено
         }
         
-        self.achievements.insert(achievement_id, chrono::Utc::now());
+        self.achievements.insert(achievement_id, clock.now());
         true // Достижение добавлено
     }
     
@@ -164,12 +163,12 @@ impl Player {
     }
     
     /// Проверяет, активен ли игрок (был онлайн в последние N минут)
-    pub fn is_active(&self, minutes: i64) -> bool {
+    pub fn is_active(&self, minutes: i64, clock: &GameClock) -> bool {
         if self.online {
             return true;
         }
         
-        let now = chrono::Utc::now();
+        let now = clock.now();
         let duration = now.signed_duration_since(self.last_activity);
         duration.num_minutes() < minutes
     }
@@ -185,6 +184,12 @@ pub struct PlayerManager {
     account_players: HashMap<String, String>,
 }
 
+impl Default for PlayerManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl PlayerManager {
     /// Создает новый менеджер игроков
     pub fn new() -> Self {
@@ -254,14 +259,14 @@ impl PlayerManager {
     /// Получает список всех игроков в указанном мире
     pub fn get_players_in_world(&self, world_id: &str) -> Vec<&Player> {
         self.players.values()
-            .filter(|p| p.world_id.as_ref().map_or(false, |id| id == world_id))
+            .filter(|p| p.world_id.as_ref().is_some_and(|id| id == world_id))
             .collect()
     }
     
     /// Обновляет последнюю активность игрока
-    pub fn update_activity(&mut self, player_id: &str) -> bool {
+    pub fn update_activity(&mut self, player_id: &str, clock: &GameClock) -> bool {
         if let Some(player) = self.players.get_mut(player_id) {
-            player.last_activity = chrono::Utc::now();
+            player.last_activity = clock.now();
             true
         } else {
             false
@@ -276,7 +281,7 @@ impl PlayerManager {
     /// Получает топ N игроков по уровню
     pub fn get_top_players_by_level(&self, limit: usize) -> Vec<&Player> {
         let mut players: Vec<&Player> = self.players.values().collect();
-        players.sort_by(|a, b| b.level.cmp(&a.level));
+        players.sort_by_key(|p| std::cmp::Reverse(p.level));
         players.truncate(limit);
         players
     }
@@ -284,7 +289,7 @@ impl PlayerManager {
     /// Получает топ N игроков по количеству городов
     pub fn get_top_players_by_cities(&self, limit: usize) -> Vec<&Player> {
         let mut players: Vec<&Player> = self.players.values().collect();
-        players.sort_by(|a, b| b.cities.len().cmp(&a.cities.len()));
+        players.sort_by_key(|p| std::cmp::Reverse(p.cities.len()));
         players.truncate(limit);
         players
     }
@@ -23,6 +23,12 @@ pub struct PluginManager {
     enabled_plugins: Vec<String>,
 }
 
+impl Default for PluginManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl PluginManager {
     pub fn new() -> PluginManager {
         PluginManager {
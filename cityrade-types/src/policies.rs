@@ -0,0 +1,271 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use crate::resources::ResourceType;
+use crate::technology::{TechnologyTree, TechnologyType};
+
+/// Сколько ходов политику нельзя переключить после принятия или отмены
+pub const POLICY_SWITCH_COOLDOWN: u64 = 5;
+/// Доля обычной потребности в еде, которую жители получают при нормировании (в процентах)
+pub const RATION_PERCENT: u32 = 75;
+
+/// Политики и указы города
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum PolicyType {
+    /// Нормирование еды: жители едят меньше, но недовольны
+    Rationing,
+    /// Военное положение: защита выше, бунты не разрушают здания
+    MartialLaw,
+    /// Празднества: счастье и культура за золото и еду
+    Festival,
+    /// Свободная торговля: пошлины отменены, золото и прирост растут
+    FreeTrade,
+}
+
+impl PolicyType {
+    /// Все политики
+    pub const ALL: [PolicyType; 4] = [
+        PolicyType::Rationing,
+        PolicyType::MartialLaw,
+        PolicyType::Festival,
+        PolicyType::FreeTrade,
+    ];
+
+    pub fn display_name(&self) -> &str {
+        match self {
+            PolicyType::Rationing => "Нормирование",
+            PolicyType::MartialLaw => "Военное положение",
+            PolicyType::Festival => "Празднества",
+            PolicyType::FreeTrade => "Свободная торговля",
+        }
+    }
+
+    /// Технология, открывающая политику
+    pub fn required_technology(&self) -> TechnologyType {
+        match self {
+            PolicyType::Rationing | PolicyType::MartialLaw => TechnologyType::Administration,
+            PolicyType::Festival | PolicyType::FreeTrade => TechnologyType::Governance,
+        }
+    }
+
+    /// Политики, которые нельзя проводить одновременно с этой
+    pub fn conflicts_with(&self) -> &'static [PolicyType] {
+        match self {
+            PolicyType::MartialLaw => &[PolicyType::Festival],
+            PolicyType::Festival => &[PolicyType::MartialLaw, PolicyType::Rationing],
+            PolicyType::Rationing => &[PolicyType::Festival],
+            PolicyType::FreeTrade => &[],
+        }
+    }
+
+    /// Стоимость политики за ход
+    pub fn upkeep(&self) -> Vec<(ResourceType, u32)> {
+        match self {
+            PolicyType::Rationing => vec![(ResourceType::Gold, 5)],
+            PolicyType::MartialLaw => vec![(ResourceType::Gold, 15)],
+            PolicyType::Festival => vec![(ResourceType::Food, 15), (ResourceType::Gold, 20)],
+            PolicyType::FreeTrade => vec![(ResourceType::Gold, 5)],
+        }
+    }
+
+    /// Действие политики
+    pub fn effects(&self) -> PolicyEffects {
+        match self {
+            PolicyType::Rationing => PolicyEffects {
+                happiness: -5,
+                food_ration: Some(RATION_PERCENT),
+                ..PolicyEffects::default()
+            },
+            PolicyType::MartialLaw => PolicyEffects {
+                happiness: -10,
+                defense: 10,
+                culture: -5,
+                suppresses_riots: true,
+                ..PolicyEffects::default()
+            },
+            PolicyType::Festival => PolicyEffects {
+                happiness: 15,
+                culture: 5,
+                production: BTreeMap::from([
+                    (ResourceType::Wood, -10),
+                    (ResourceType::Stone, -10),
+                    (ResourceType::Iron, -10),
+                ]),
+                ..PolicyEffects::default()
+            },
+            PolicyType::FreeTrade => PolicyEffects {
+                growth: 5,
+                production: BTreeMap::from([(ResourceType::Gold, 15)]),
+                disables_tariffs: true,
+                ..PolicyEffects::default()
+            },
+        }
+    }
+}
+
+/// Причина, по которой политику нельзя принять или отменить
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolicyError {
+    /// Город не найден
+    CityNotFound,
+    /// Политика уже действует
+    AlreadyActive(PolicyType),
+    /// Политика не действует
+    NotActive(PolicyType),
+    /// Не исследована технология, открывающая политику
+    MissingTechnology { policy: PolicyType, technology: TechnologyType },
+    /// Политика несовместима с действующей
+    Conflict { policy: PolicyType, active: PolicyType },
+    /// Политику переключали недавно
+    Cooldown { policy: PolicyType, remaining: u64 },
+}
+
+impl fmt::Display for PolicyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PolicyError::CityNotFound => write!(f, "Город не найден"),
+            PolicyError::AlreadyActive(policy) => write!(f, "Политика «{}» уже действует", policy.display_name()),
+            PolicyError::NotActive(policy) => write!(f, "Политика «{}» не действует", policy.display_name()),
+            PolicyError::MissingTechnology { policy, technology } => {
+                write!(f, "Политика «{}» требует технологию {:?}", policy.display_name(), technology)
+            }
+            PolicyError::Conflict { policy, active } => write!(
+                f,
+                "Политика «{}» несовместима с «{}»",
+                policy.display_name(),
+                active.display_name()
+            ),
+            PolicyError::Cooldown { policy, remaining } => write!(
+                f,
+                "Политику «{}» можно переключить через {} ход(ов)",
+                policy.display_name(),
+                remaining
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PolicyError {}
+
+/// Суммарное действие политик города
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PolicyEffects {
+    /// Модификаторы производства в процентах
+    pub production: BTreeMap<ResourceType, i32>,
+    pub happiness: i32,
+    pub defense: i32,
+    pub culture: i32,
+    /// Модификатор прироста населения в процентах
+    pub growth: i32,
+    /// Доля потребности в еде, которую выдают жителям (в процентах)
+    pub food_ration: Option<u32>,
+    /// Бунты не повреждают здания
+    pub suppresses_riots: bool,
+    /// Торговые пошлины не взимаются
+    pub disables_tariffs: bool,
+}
+
+impl PolicyEffects {
+    /// Добавляет действие еще одной политики
+    pub fn combine(&mut self, other: &PolicyEffects) {
+        for (resource, percent) in &other.production {
+            *self.production.entry(resource.clone()).or_insert(0) += percent;
+        }
+        self.happiness += other.happiness;
+        self.defense += other.defense;
+        self.culture += other.culture;
+        self.growth += other.growth;
+        self.food_ration = match (self.food_ration, other.food_ration) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        self.suppresses_riots |= other.suppresses_riots;
+        self.disables_tariffs |= other.disables_tariffs;
+    }
+}
+
+/// Политики города и время их последнего переключения
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CityPolicies {
+    active: BTreeSet<PolicyType>,
+    /// Ход последнего принятия или отмены политики
+    last_changed: BTreeMap<PolicyType, u64>,
+}
+
+impl CityPolicies {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Действующие политики
+    pub fn active(&self) -> impl Iterator<Item = &PolicyType> {
+        self.active.iter()
+    }
+
+    pub fn is_active(&self, policy: &PolicyType) -> bool {
+        self.active.contains(policy)
+    }
+
+    /// Сколько ходов еще нельзя переключить политику
+    pub fn cooldown_remaining(&self, policy: &PolicyType, turn: u64) -> u64 {
+        self.last_changed
+            .get(policy)
+            .map_or(0, |changed| (changed + POLICY_SWITCH_COOLDOWN).saturating_sub(turn))
+    }
+
+    /// Принимает политику: она должна быть открыта технологией, не
+    /// конфликтовать с действующими и не переключаться в последние
+    /// `POLICY_SWITCH_COOLDOWN` ходов
+    pub fn enact(&mut self, policy: PolicyType, technologies: &TechnologyTree, turn: u64) -> Result<(), PolicyError> {
+        if self.is_active(&policy) {
+            return Err(PolicyError::AlreadyActive(policy));
+        }
+        let technology = policy.required_technology();
+        if !technologies.is_researched(&technology) {
+            return Err(PolicyError::MissingTechnology { policy, technology });
+        }
+        if let Some(active) = policy.conflicts_with().iter().find(|other| self.is_active(other)) {
+            return Err(PolicyError::Conflict { policy, active: *active });
+        }
+        self.check_cooldown(&policy, turn)?;
+
+        self.active.insert(policy);
+        self.last_changed.insert(policy, turn);
+        Ok(())
+    }
+
+    /// Отменяет политику (с учетом задержки переключения)
+    pub fn revoke(&mut self, policy: PolicyType, turn: u64) -> Result<(), PolicyError> {
+        if !self.is_active(&policy) {
+            return Err(PolicyError::NotActive(policy));
+        }
+        self.check_cooldown(&policy, turn)?;
+
+        self.active.remove(&policy);
+        self.last_changed.insert(policy, turn);
+        Ok(())
+    }
+
+    /// Отменяет политику без задержки, например когда казна не может ее оплатить
+    pub fn lapse(&mut self, policy: &PolicyType) {
+        self.active.remove(policy);
+    }
+
+    /// Суммарное действие действующих политик
+    pub fn effects(&self) -> PolicyEffects {
+        let mut effects = PolicyEffects::default();
+        for policy in &self.active {
+            effects.combine(&policy.effects());
+        }
+        effects
+    }
+
+    fn check_cooldown(&self, policy: &PolicyType, turn: u64) -> Result<(), PolicyError> {
+        let remaining = self.cooldown_remaining(policy, turn);
+        if remaining > 0 {
+            return Err(PolicyError::Cooldown { policy: *policy, remaining });
+        }
+        Ok(())
+    }
+}
@@ -1,8 +1,19 @@
+use crate::building::BuildingType;
 use crate::resources::ResourceType;
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
 
-#[derive(Debug, Clone, Serialize, Deserialize, Eq, Hash, PartialEq)]
+/// Ниже этого счастья класс бастует: его жители не работают
+pub const STRIKE_HAPPINESS: f32 = 0.3;
+/// Ниже этого счастья класс бунтует: бастует и громит здания
+pub const RIOT_HAPPINESS: f32 = 0.15;
+/// Роскошных товаров на одного представителя знати за ход
+pub const LUXURY_PER_NOBLE: f32 = 0.5;
+/// Доля разрыва между счастьем класса и его довольством,
+/// которая сокращается за ход
+const HAPPINESS_DRIFT: f32 = 0.1;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, Hash, PartialEq, PartialOrd, Ord)]
 pub enum PopulationClass {
     Peasant,  // Крестьяне - производят пищу
     Worker,   // Рабочие - работают в производстве
@@ -12,18 +23,120 @@ pub enum PopulationClass {
     Noble,    // Знать - увеличивает престиж и налоги
 }
 
+impl PopulationClass {
+    /// Все классы населения
+    pub const ALL: [PopulationClass; 6] = [
+        PopulationClass::Peasant,
+        PopulationClass::Worker,
+        PopulationClass::Merchant,
+        PopulationClass::Soldier,
+        PopulationClass::Scholar,
+        PopulationClass::Noble,
+    ];
+
+    /// Доля класса в новом и растущем населении
+    pub fn target_share(&self) -> f32 {
+        match self {
+            PopulationClass::Peasant => 0.5,
+            PopulationClass::Worker => 0.25,
+            PopulationClass::Merchant => 0.075,
+            PopulationClass::Soldier => 0.125,
+            PopulationClass::Scholar => 0.025,
+            PopulationClass::Noble => 0.025,
+        }
+    }
+
+    /// Сколько порций еды нужно жителю класса относительно крестьянина
+    pub fn food_need(&self) -> f32 {
+        match self {
+            PopulationClass::Soldier => 1.5,
+            PopulationClass::Noble => 2.0,
+            _ => 1.0,
+        }
+    }
+
+    /// Доход жителя класса за ход, с которого взимается подоходный налог
+    pub fn income(&self) -> u32 {
+        match self {
+            PopulationClass::Peasant => 1,
+            PopulationClass::Worker => 2,
+            PopulationClass::Merchant => 5,
+            PopulationClass::Soldier => 1,
+            PopulationClass::Scholar => 2,
+            PopulationClass::Noble => 10,
+        }
+    }
+
+    /// Потребности класса: ресурс и его вес в довольстве класса (сумма весов — 1)
+    pub fn needs(&self) -> &'static [(ResourceType, f32)] {
+        match self {
+            PopulationClass::Noble => &[(ResourceType::Food, 0.7), (ResourceType::LuxuryGoods, 0.3)],
+            _ => &[(ResourceType::Food, 1.0)],
+        }
+    }
+}
+
+/// Переход жителей из класса в класс, который дает здание города
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassTransition {
+    pub from: PopulationClass,
+    pub to: PopulationClass,
+    pub building: BuildingType,
+    /// Сколько жителей переходит за ход на каждый уровень здания
+    pub per_level: f32,
+}
+
+/// Переходы между классами: школы учат крестьян ремеслу,
+/// рынки превращают рабочих в торговцев
+pub const CLASS_TRANSITIONS: [ClassTransition; 2] = [
+    ClassTransition {
+        from: PopulationClass::Peasant,
+        to: PopulationClass::Worker,
+        building: BuildingType::School,
+        per_level: 0.5,
+    },
+    ClassTransition {
+        from: PopulationClass::Worker,
+        to: PopulationClass::Merchant,
+        building: BuildingType::Market,
+        per_level: 0.25,
+    },
+];
+
+/// Волнения класса
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Unrest {
+    Calm,
+    /// Забастовка: жители класса не работают
+    Strike,
+    /// Бунт: жители класса не работают и громят здания
+    Riot,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Population {
-    pub classes: HashMap<PopulationClass, u32>,
+    pub classes: BTreeMap<PopulationClass, u32>,
     pub happiness: f32,        // от 0.0 до 1.0
     pub growth_rate: f32,      // базовый прирост населения
     pub food_consumption: f32, // сколько пищи потребляет каждая единица населения в день
     pub next_growth_tick: u32, // через сколько тиков произойдет рост
+    /// Счастье классов (от 0.0 до 1.0); для класса без записи — общее счастье
+    #[serde(default)]
+    pub class_happiness: BTreeMap<PopulationClass, f32>,
+    /// Накопленный переход в класс (см. `CLASS_TRANSITIONS`)
+    #[serde(default)]
+    pub mobility_progress: BTreeMap<PopulationClass, f32>,
+}
+
+impl Default for Population {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Population {
     pub fn new() -> Self {
-        let mut classes = HashMap::new();
+        let mut classes = BTreeMap::new();
         classes.insert(PopulationClass::Peasant, 20);
         classes.insert(PopulationClass::Worker, 10);
         classes.insert(PopulationClass::Merchant, 3);
@@ -37,31 +150,167 @@ impl Population {
             growth_rate: 0.02,
             food_consumption: 0.5,
             next_growth_tick: 10,
+            class_happiness: BTreeMap::new(),
+            mobility_progress: BTreeMap::new(),
         }
     }
 
+    /// Население заданной численности, распределенное по классам
+    /// в долях `PopulationClass::target_share`
+    pub fn with_total(total: u32) -> Self {
+        let mut population = Population::new();
+        population.classes = PopulationClass::ALL.iter().map(|class| (class.clone(), 0)).collect();
+        population.distribute_growth(total);
+        population
+    }
+
     pub fn total(&self) -> u32 {
         self.classes.values().sum()
     }
 
+    /// Численность класса
+    pub fn count(&self, class: &PopulationClass) -> u32 {
+        self.classes.get(class).copied().unwrap_or(0)
+    }
+
+    /// Сколько ресурса нужно одному жителю класса за ход
+    pub fn need_per_capita(&self, class: &PopulationClass, resource: &ResourceType) -> f32 {
+        match resource {
+            ResourceType::Food => class.food_need() * self.food_consumption,
+            ResourceType::LuxuryGoods if *class == PopulationClass::Noble => LUXURY_PER_NOBLE,
+            _ => 0.0,
+        }
+    }
+
+    /// Потребности всего населения за ход по ресурсам
+    pub fn demand(&self) -> BTreeMap<ResourceType, u32> {
+        let mut demand: BTreeMap<ResourceType, f32> = BTreeMap::new();
+        for (class, count) in &self.classes {
+            for (resource, _) in class.needs() {
+                *demand.entry(resource.clone()).or_insert(0.0) +=
+                    *count as f32 * self.need_per_capita(class, resource);
+            }
+        }
+        demand
+            .into_iter()
+            .map(|(resource, amount)| (resource, amount.ceil() as u32))
+            .filter(|(_, amount)| *amount > 0)
+            .collect()
+    }
+
+    /// Потребление еды за ход с учетом потребностей классов
     pub fn daily_food_consumption(&self) -> u32 {
-        (self.total() as f32 * self.food_consumption) as u32
+        self.demand().get(&ResourceType::Food).copied().unwrap_or(0)
     }
 
-    pub fn update(&mut self, food_available: u32, housing_capacity: u32) {
-        // Расчет потребности в пище
-        let food_needed = self.daily_food_consumption();
+    /// Счастье класса
+    pub fn class_happiness(&self, class: &PopulationClass) -> f32 {
+        self.class_happiness.get(class).copied().unwrap_or(self.happiness)
+    }
 
-        // Обновление счастья на основе доступности пищи
-        if food_available < food_needed {
-            let food_ratio = food_available as f32 / food_needed as f32;
-            self.happiness -= (1.0 - food_ratio) * 0.1;
+    /// Обновляет счастье классов по тому, какая доля их потребностей
+    /// удовлетворена (`supplied` — выданные за ход ресурсы). Счастье класса
+    /// приближается к его довольству на `HAPPINESS_DRIFT` разрыва за ход;
+    /// общее счастье — среднее по жителям.
+    pub fn apply_supply(&mut self, supplied: &BTreeMap<ResourceType, u32>) {
+        let demand = self.demand();
+        let ratio = |resource: &ResourceType| match demand.get(resource) {
+            Some(needed) => (supplied.get(resource).copied().unwrap_or(0) as f32 / *needed as f32).min(1.0),
+            None => 1.0,
+        };
+
+        let mut weighted = 0.0;
+        for class in PopulationClass::ALL {
+            let count = self.count(&class);
+            if count == 0 {
+                continue;
+            }
+            let satisfaction: f32 = class.needs().iter().map(|(resource, weight)| weight * ratio(resource)).sum();
+            let current = self.class_happiness(&class);
+            let happiness = (current + (satisfaction - current) * HAPPINESS_DRIFT).clamp(0.0, 1.0);
+            weighted += happiness * count as f32;
+            self.class_happiness.insert(class, happiness);
+        }
+
+        if self.total() > 0 {
+            self.happiness = (weighted / self.total() as f32).clamp(0.0, 1.0);
+        }
+    }
+
+    /// Волнения класса по его счастью
+    pub fn unrest(&self, class: &PopulationClass) -> Unrest {
+        if self.count(class) == 0 {
+            return Unrest::Calm;
+        }
+        let happiness = self.class_happiness(class);
+        if happiness < RIOT_HAPPINESS {
+            Unrest::Riot
+        } else if happiness < STRIKE_HAPPINESS {
+            Unrest::Strike
         } else {
-            self.happiness += 0.01;
+            Unrest::Calm
         }
+    }
 
-        // Ограничиваем счастье в диапазоне 0.0-1.0
-        self.happiness = self.happiness.max(0.0).min(1.0);
+    /// Классы, которые бастуют или бунтуют
+    pub fn striking_classes(&self) -> Vec<PopulationClass> {
+        PopulationClass::ALL
+            .into_iter()
+            .filter(|class| self.unrest(class) != Unrest::Calm)
+            .collect()
+    }
+
+    /// Переводит жителей между классами (`CLASS_TRANSITIONS`).
+    ///
+    /// `buildings` — суммарный уровень зданий каждого типа в городе,
+    /// `idle` — безработные по классам: переходят только они. Возвращает
+    /// состоявшиеся переходы (из класса, в класс, сколько жителей).
+    pub fn apply_mobility(
+        &mut self,
+        buildings: &BTreeMap<BuildingType, u32>,
+        idle: &BTreeMap<PopulationClass, u32>,
+    ) -> Vec<(PopulationClass, PopulationClass, u32)> {
+        let mut moved = Vec::new();
+        for transition in &CLASS_TRANSITIONS {
+            let level = buildings.get(&transition.building).copied().unwrap_or(0);
+            if level == 0 {
+                self.mobility_progress.remove(&transition.to);
+                continue;
+            }
+
+            let progress = self.mobility_progress.entry(transition.to.clone()).or_insert(0.0);
+            *progress += transition.per_level * level as f32;
+            let candidates = idle
+                .get(&transition.from)
+                .copied()
+                .unwrap_or(0)
+                .min(self.classes.get(&transition.from).copied().unwrap_or(0));
+            let count = (progress.floor() as u32).min(candidates);
+            // Без кандидатов прогресс не копится дальше одного жителя
+            *progress = (*progress - count as f32).min(1.0);
+
+            if count > 0 {
+                *self.classes.entry(transition.from.clone()).or_insert(0) -= count;
+                *self.classes.entry(transition.to.clone()).or_insert(0) += count;
+                moved.push((transition.from.clone(), transition.to.clone(), count));
+            }
+        }
+        moved
+    }
+
+    /// Добавляет жителей, распределяя их по классам
+    pub fn grow(&mut self, amount: u32) {
+        self.distribute_growth(amount);
+    }
+
+    /// Убирает жителей, начиная с самых многочисленных классов
+    pub fn shrink(&mut self, amount: u32) {
+        self.distribute_decline(amount);
+    }
+
+    pub fn update(&mut self, food_available: u32, housing_capacity: u32) {
+        // Обновление счастья на основе доступности пищи
+        self.apply_supply(&BTreeMap::from([(ResourceType::Food, food_available)]));
 
         // Обновление роста населения
         if self.next_growth_tick > 0 {
@@ -97,56 +346,47 @@ impl Population {
     }
 
     fn distribute_growth(&mut self, growth: u32) {
-        // 60% прироста идет в крестьяне, 30% в рабочие, 10% распределяется по остальным
-        let peasant_growth = (growth as f32 * 0.6) as u32;
-        let worker_growth = (growth as f32 * 0.3) as u32;
-        let other_growth = growth - peasant_growth - worker_growth;
-
-        *self.classes.entry(PopulationClass::Peasant).or_insert(0) += peasant_growth;
-        *self.classes.entry(PopulationClass::Worker).or_insert(0) += worker_growth;
-
-        // Распределяем остальную часть поровну
-        if other_growth > 0 {
-            let classes = vec![
-                PopulationClass::Merchant,
-                PopulationClass::Soldier,
-                PopulationClass::Scholar,
-                PopulationClass::Noble,
-            ];
-
-            let growth_per_class = other_growth / classes.len() as u32;
-            let remainder = other_growth % classes.len() as u32;
-
-            for (i, class) in classes.iter().enumerate() {
-                let extra = if i < remainder as usize { 1 } else { 0 };
-                *self.classes.entry(class.clone()).or_insert(0) += growth_per_class + extra;
-            }
+        // Каждый новый житель попадает в класс, которому больше всего
+        // не хватает до своей доли (`target_share`)
+        for _ in 0..growth {
+            let total = self.total() as f32 + 1.0;
+            let class = PopulationClass::ALL
+                .iter()
+                .max_by(|a, b| {
+                    let deficit_a = a.target_share() * total - self.count(a) as f32;
+                    let deficit_b = b.target_share() * total - self.count(b) as f32;
+                    // При равенстве выигрывает класс, стоящий раньше в списке
+                    deficit_a.total_cmp(&deficit_b).then(b.cmp(a))
+                })
+                .cloned()
+                .unwrap_or(PopulationClass::Peasant);
+            *self.classes.entry(class).or_insert(0) += 1;
         }
     }
 
     fn distribute_decline(&mut self, decline: u32) {
-        // Сначала уменьшаем число крестьян и рабочих
-        let mut remaining_decline = decline;
-
-        // Пытаемся уменьшить население пропорционально их количеству
-        let total = self.total();
-        let mut remaining_population = total;
-
-        for (_class, count) in self.classes.iter_mut() {
-            let class_decline = (remaining_decline as f32 * (*count as f32 / total as f32)) as u32;
-            let actual_decline = class_decline.min(*count);
-
-            *count -= actual_decline;
-            remaining_decline -= actual_decline;
-            remaining_population -= actual_decline;
-
-            if remaining_decline == 0 || remaining_population == 0 {
+        // Уходят жители самых многочисленных классов
+        for _ in 0..decline.min(self.total()) {
+            let Some(class) = self
+                .classes
+                .iter()
+                .filter(|(_, count)| **count > 0)
+                .max_by(|(a, count_a), (b, count_b)| count_a.cmp(count_b).then(b.cmp(a)))
+                .map(|(class, _)| class.clone())
+            else {
                 break;
+            };
+            if let Some(count) = self.classes.get_mut(&class) {
+                *count -= 1;
             }
         }
     }
 
     pub fn get_production_bonus(&self, resource: &ResourceType) -> f32 {
+        if self.total() == 0 {
+            return 0.0;
+        }
+
         match resource {
             ResourceType::Food => 0.5,
             ResourceType::Wood => 0.3,
@@ -173,3 +413,22 @@ impl Population {
         }
     }
 }
+
+/// Читает население в текущем формате (по классам) или в старом формате
+/// сохранений, где население было числом; число распределяется по классам
+pub fn deserialize_population<'de, D>(deserializer: D) -> Result<Population, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum PopulationFormat {
+        Legacy(u32),
+        Classes(Population),
+    }
+
+    Ok(match PopulationFormat::deserialize(deserializer)? {
+        PopulationFormat::Legacy(total) => Population::with_total(total),
+        PopulationFormat::Classes(population) => population,
+    })
+}
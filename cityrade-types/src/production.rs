@@ -0,0 +1,170 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::building::BuildingType;
+use crate::building_registry::Recipe;
+use crate::resources::{Resource, ResourceType};
+
+/// Бонусы к производству города в процентах, приходящие извне города
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProductionModifiers {
+    /// Бонусы фракции города
+    pub faction: BTreeMap<ResourceType, i32>,
+    /// Бонусы технологий владельца города
+    pub technology: BTreeMap<ResourceType, i32>,
+    /// Богатство месторождений рядом с городом: бонус к добыче зданий,
+    /// работающих с месторождениями (`BuildingType::extracts_deposits`)
+    pub deposits: BTreeMap<ResourceType, i32>,
+    /// Ресурсы с обрабатываемых клеток территории города (в единицах, не в процентах)
+    pub worked_tiles: BTreeMap<ResourceType, i32>,
+}
+
+impl ProductionModifiers {
+    /// Бонус фракции к ресурсу
+    pub fn faction_bonus(&self, resource: &ResourceType) -> i32 {
+        self.faction.get(resource).copied().unwrap_or(0)
+    }
+
+    /// Бонус технологий к ресурсу
+    pub fn technology_bonus(&self, resource: &ResourceType) -> i32 {
+        self.technology.get(resource).copied().unwrap_or(0)
+    }
+
+    /// Бонус месторождений к добыче ресурса
+    pub fn deposit_bonus(&self, resource: &ResourceType) -> i32 {
+        self.deposits.get(resource).copied().unwrap_or(0)
+    }
+}
+
+/// Итог выполнения рецептов здания за ход
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProductionStatus {
+    pub building_id: String,
+    pub building_type: BuildingType,
+    /// Средняя загрузка рецептов здания (от 0 до 1)
+    pub efficiency: f32,
+    /// Нехватка входов: сколько ресурса не хватило до полной загрузки
+    pub missing: Vec<(ResourceType, u32)>,
+    /// Израсходованные входы
+    #[serde(default)]
+    pub consumed: Vec<(ResourceType, u32)>,
+    /// Выпущенные товары
+    #[serde(default)]
+    pub produced: Vec<(ResourceType, u32)>,
+}
+
+impl ProductionStatus {
+    /// Здание не произвело ничего
+    pub fn is_stalled(&self) -> bool {
+        self.efficiency <= 0.0
+    }
+
+    /// Здание работает не на полную мощность
+    pub fn is_bottleneck(&self) -> bool {
+        self.efficiency < 1.0
+    }
+}
+
+/// Результат выполнения одного рецепта
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecipeRun {
+    /// Доля выполненного рецепта (от 0 до 1)
+    pub efficiency: f32,
+    /// Недостающие входы
+    pub missing: Vec<(ResourceType, u32)>,
+    /// Израсходованные входы
+    pub consumed: Vec<(ResourceType, u32)>,
+    /// Выпущенные товары
+    pub produced: Vec<(ResourceType, u32)>,
+}
+
+/// Выполняет рецепт на запасах города.
+///
+/// Загрузка рецепта ограничена самым дефицитным входом: при половине нужного
+/// железа рецепт выполняется наполовину, без одного из входов — простаивает.
+/// Выход округляется вниз, расход — вверх, но не больше имеющегося запаса.
+pub fn run_recipe(recipe: &Recipe, resources: &mut Resource) -> RecipeRun {
+    run_recipe_limited(recipe, resources, 1.0)
+}
+
+fn run_recipe_limited(recipe: &Recipe, resources: &mut Resource, limit: f32) -> RecipeRun {
+    let mut efficiency: f32 = limit.clamp(0.0, 1.0);
+    let mut missing = Vec::new();
+
+    for (resource, required) in &recipe.inputs {
+        let available = resources.get(resource);
+        if available < *required {
+            missing.push((resource.clone(), required - available));
+        }
+        if *required > 0 {
+            efficiency = efficiency.min(available as f32 / *required as f32);
+        }
+    }
+
+    let mut consumed = Vec::new();
+    let mut produced = Vec::new();
+    if efficiency > 0.0 {
+        for (resource, required) in &recipe.inputs {
+            let spent = ((*required as f32 * efficiency).ceil() as u32).min(resources.get(resource));
+            resources.subtract(resource, spent);
+            consumed.push((resource.clone(), spent));
+        }
+        for (resource, amount) in &recipe.outputs {
+            let output = (*amount as f32 * efficiency) as u32;
+            if output > 0 {
+                resources.add(resource, output);
+                produced.push((resource.clone(), output));
+            }
+        }
+    }
+
+    RecipeRun {
+        efficiency,
+        missing,
+        consumed,
+        produced,
+    }
+}
+
+/// Выполняет рецепты здания по порядку и сводит их загрузку.
+/// `limit` — наибольшая загрузка, например доля обеспечения здания содержанием.
+pub fn run_recipes(
+    building_id: &str,
+    building_type: &BuildingType,
+    recipes: &[Recipe],
+    resources: &mut Resource,
+    limit: f32,
+) -> ProductionStatus {
+    let mut total = 0.0;
+    let mut missing: Vec<(ResourceType, u32)> = Vec::new();
+    let mut consumed: Vec<(ResourceType, u32)> = Vec::new();
+    let mut produced: Vec<(ResourceType, u32)> = Vec::new();
+
+    for recipe in recipes {
+        let run = run_recipe_limited(recipe, resources, limit);
+        total += run.efficiency;
+        merge_amounts(&mut missing, run.missing);
+        merge_amounts(&mut consumed, run.consumed);
+        merge_amounts(&mut produced, run.produced);
+    }
+
+    ProductionStatus {
+        building_id: building_id.to_string(),
+        building_type: building_type.clone(),
+        efficiency: if recipes.is_empty() { limit } else { total / recipes.len() as f32 },
+        missing,
+        consumed,
+        produced,
+    }
+}
+
+/// Складывает количества одинаковых ресурсов, сохраняя порядок по типу ресурса
+fn merge_amounts(total: &mut Vec<(ResourceType, u32)>, amounts: Vec<(ResourceType, u32)>) {
+    for (resource, amount) in amounts {
+        match total.iter_mut().find(|(r, _)| *r == resource) {
+            Some((_, sum)) => *sum = sum.saturating_add(amount),
+            None => total.push((resource, amount)),
+        }
+    }
+    total.sort();
+}
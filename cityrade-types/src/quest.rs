@@ -2,7 +2,10 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::time::Duration;
-use crate::resources::{ResourceType, BuildingType};
+use crate::clock::GameClock;
+use crate::events::{EventSystem, QuestCompletedEvent};
+use crate::building::BuildingType;
+use crate::resources::ResourceType;
 use crate::diplomacy::FactionAction;
 use std::str::FromStr;
 
@@ -199,8 +202,8 @@ pub struct Quest {
 
 impl Quest {
     /// Создает новый квест
-    pub fn new<S: Into<String>>(id: S, title: S, description: S) -> Self {
-        let now = Utc::now();
+    pub fn new<S: Into<String>>(id: S, title: S, description: S, clock: &GameClock) -> Self {
+        let now = clock.now();
         Self {
             id: id.into(),
             title: title.into(),
@@ -279,10 +282,10 @@ impl Quest {
     }
     
     /// Начинает квест
-    pub fn start(&mut self) -> Result<(), String> {
+    pub fn start(&mut self, clock: &GameClock) -> Result<(), String> {
         if self.status.is_available() {
             self.status = QuestStatus::InProgress;
-            self.updated_at = Utc::now();
+            self.updated_at = clock.now();
             Ok(())
         } else {
             Err(format!("Нельзя начать квест со статусом {:?}", self.status))
@@ -290,11 +293,11 @@ impl Quest {
     }
     
     /// Завершает квест
-    pub fn complete(&mut self) -> Result<(), String> {
+    pub fn complete(&mut self, clock: &GameClock) -> Result<(), String> {
         if self.status == QuestStatus::InProgress {
             if self.all_objectives_completed() {
                 self.status = QuestStatus::Completed;
-                self.updated_at = Utc::now();
+                self.updated_at = clock.now();
                 Ok(())
             } else {
                 Err("Не все цели квеста выполнены".to_string())
@@ -305,10 +308,10 @@ impl Quest {
     }
     
     /// Проваливает квест
-    pub fn fail(&mut self) -> Result<(), String> {
+    pub fn fail(&mut self, clock: &GameClock) -> Result<(), String> {
         if self.status == QuestStatus::InProgress {
             self.status = QuestStatus::Failed;
-            self.updated_at = Utc::now();
+            self.updated_at = clock.now();
             Ok(())
         } else {
             Err(format!("Нельзя провалить квест со статусом {:?}", self.status))
@@ -316,7 +319,7 @@ impl Quest {
     }
     
     /// Сбрасывает статус квеста в начальное состояние
-    pub fn reset(&mut self) -> Result<(), String> {
+    pub fn reset(&mut self, clock: &GameClock) -> Result<(), String> {
         if self.status == QuestStatus::Completed || self.status == QuestStatus::Failed {
             // Сбрасываем статус и прогресс целей
             self.status = QuestStatus::NotStarted;
@@ -324,7 +327,7 @@ impl Quest {
                 objective.current_value = 0;
                 objective.completed = false;
             }
-            self.updated_at = Utc::now();
+            self.updated_at = clock.now();
             Ok(())
         } else {
             Err(format!("Нельзя сбросить квест со статусом {:?}", self.status))
@@ -332,7 +335,7 @@ impl Quest {
     }
     
     /// Обновляет прогресс конкретной цели квеста
-    pub fn update_objective(&mut self, objective_id: &str, value: u32) -> Result<bool, String> {
+    pub fn update_objective(&mut self, objective_id: &str, value: u32, clock: &GameClock) -> Result<bool, String> {
         if self.status != QuestStatus::InProgress {
             return Err(format!("Нельзя обновить цель квеста со статусом {:?}", self.status));
         }
@@ -343,7 +346,7 @@ impl Quest {
             .ok_or_else(|| format!("Цель с id {} не найдена", objective_id))?;
             
         objective.update_progress(value);
-        self.updated_at = Utc::now();
+        self.updated_at = clock.now();
         
         let all_completed = self.all_objectives_completed();
         
@@ -351,7 +354,7 @@ impl Quest {
     }
     
     /// Увеличивает прогресс конкретной цели квеста
-    pub fn increment_objective(&mut self, objective_id: &str, amount: u32) -> Result<bool, String> {
+    pub fn increment_objective(&mut self, objective_id: &str, amount: u32, clock: &GameClock) -> Result<bool, String> {
         if self.status != QuestStatus::InProgress {
             return Err(format!("Нельзя обновить цель квеста со статусом {:?}", self.status));
         }
@@ -362,7 +365,7 @@ impl Quest {
             .ok_or_else(|| format!("Цель с id {} не найдена", objective_id))?;
             
         objective.increment_progress(amount);
-        self.updated_at = Utc::now();
+        self.updated_at = clock.now();
         
         let all_completed = self.all_objectives_completed();
         
@@ -392,25 +395,25 @@ impl Quest {
     
     /// Проверяет, доступен ли квест для игрока указанного уровня
     pub fn is_available_for_level(&self, player_level: u32) -> bool {
-        if let Some(min_level) = self.min_level {
-            if player_level < min_level {
-                return false;
-            }
+        if let Some(min_level) = self.min_level
+            && player_level < min_level
+        {
+            return false;
         }
         
-        if let Some(max_level) = self.max_level {
-            if player_level > max_level {
-                return false;
-            }
+        if let Some(max_level) = self.max_level
+            && player_level > max_level
+        {
+            return false;
         }
         
         true
     }
     
     /// Проверяет, не истек ли срок выполнения квеста
-    pub fn is_expired(&self) -> bool {
+    pub fn is_expired(&self, clock: &GameClock) -> bool {
         if let Some(expires_at) = self.expires_at {
-            Utc::now() > expires_at
+            clock.now() > expires_at
         } else {
             false
         }
@@ -420,6 +423,8 @@ impl Quest {
 /// Структура для управления квестами
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QuestManager {
+    #[serde(default)]
+    pub owner_id: String,
     pub quests: HashMap<String, Quest>,
     pub completed_quests: Vec<String>,
     pub active_quests: Vec<String>,
@@ -428,10 +433,17 @@ pub struct QuestManager {
     pub player_level: u32,
 }
 
+impl Default for QuestManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl QuestManager {
     /// Создает нового менеджера квестов
     pub fn new() -> Self {
         Self {
+            owner_id: String::new(),
             quests: HashMap::new(),
             completed_quests: Vec::new(),
             active_quests: Vec::new(),
@@ -441,6 +453,12 @@ impl QuestManager {
         }
     }
     
+    /// Указывает ID игрока, которому принадлежат квесты
+    pub fn with_owner(mut self, owner_id: String) -> Self {
+        self.owner_id = owner_id;
+        self
+    }
+    
     /// Добавляет новый квест
     pub fn add_quest(&mut self, quest: Quest) {
         let quest_id = quest.id.clone();
@@ -475,7 +493,7 @@ impl QuestManager {
     }
     
     /// Начинает квест
-    pub fn start_quest(&mut self, quest_id: &str) -> Result<(), String> {
+    pub fn start_quest(&mut self, quest_id: &str, clock: &GameClock) -> Result<(), String> {
         let quest = self.get_quest_mut(quest_id)
             .ok_or_else(|| format!("Квест с id {} не найден", quest_id))?;
             
@@ -485,7 +503,7 @@ impl QuestManager {
         }
         
         // Начинаем квест
-        quest.start()?;
+        quest.start(clock)?;
         
         // Обновляем списки
         self.available_quests.retain(|id| id != quest_id);
@@ -495,10 +513,20 @@ impl QuestManager {
     }
     
     /// Завершает квест
-    pub fn complete_quest(&mut self, id: &str) -> Result<Vec<QuestReward>, String> {
+    pub fn complete_quest(&mut self, id: &str, clock: &GameClock, events: &EventSystem) -> Result<Vec<QuestReward>, String> {
         // Клонируем id, чтобы не было проблем с заимствованием
         let quest_id = id.to_string();
         
+        // Даем плагинам возможность отменить завершение готового квеста
+        let quest = self.get_quest(&quest_id)
+            .ok_or_else(|| format!("Квест с id {} не найден", quest_id))?;
+        if quest.status == QuestStatus::InProgress && quest.all_objectives_completed() {
+            let mut event = QuestCompletedEvent::new(self.owner_id.clone(), quest_id.clone());
+            if !events.call_event(&mut event) {
+                return Err("Завершение квеста отменено".to_string());
+            }
+        }
+        
         // Получаем квест, пытаемся завершить его и клонируем награды
         let quest_rewards;
         
@@ -507,7 +535,7 @@ impl QuestManager {
             let quest = self.get_quest_mut(&quest_id)
                 .ok_or_else(|| format!("Квест с id {} не найден", quest_id))?;
             
-            quest.complete()?;
+            quest.complete(clock)?;
             quest_rewards = quest.reward.clone();
         }
         
@@ -516,13 +544,13 @@ impl QuestManager {
         self.completed_quests.push(quest_id);
         
         // Обновляем доступность других квестов, которые могли зависеть от этого
-        self.update_available_quests();
+        self.update_available_quests(clock);
         
         Ok(vec![quest_rewards])
     }
     
     /// Проваливает квест
-    pub fn fail_quest(&mut self, quest_id: &str) -> Result<(), String> {
+    pub fn fail_quest(&mut self, quest_id: &str, clock: &GameClock) -> Result<(), String> {
         let quest = self.get_quest_mut(quest_id)
             .ok_or_else(|| format!("Квест с id {} не найден", quest_id))?;
             
@@ -532,7 +560,7 @@ impl QuestManager {
         }
         
         // Проваливаем квест
-        quest.fail()?;
+        quest.fail(clock)?;
         
         // Обновляем списки
         self.active_quests.retain(|id| id != quest_id);
@@ -542,14 +570,14 @@ impl QuestManager {
     }
     
     /// Обновляет статус всех квестов на основе уровня игрока
-    pub fn update_available_quests(&mut self) {
+    pub fn update_available_quests(&mut self, clock: &GameClock) {
         // Собираем id квестов, которые можно сделать доступными
         let mut quests_to_update = Vec::new();
         
         for (id, quest) in &self.quests {
             if quest.status == QuestStatus::NotStarted 
                 && quest.is_available_for_level(self.player_level) 
-                && !quest.is_expired() 
+                && !quest.is_expired(clock) 
             {
                 let prerequisites_completed = quest.prerequisites.iter()
                     .all(|prereq_id| {
@@ -573,19 +601,19 @@ impl QuestManager {
     }
     
     /// Обновляет прогресс цели квеста
-    pub fn update_objective(&mut self, quest_id: &str, objective_id: &str, value: u32) -> Result<bool, String> {
+    pub fn update_objective(&mut self, quest_id: &str, objective_id: &str, value: u32, clock: &GameClock) -> Result<bool, String> {
         let quest = self.get_quest_mut(quest_id)
             .ok_or_else(|| format!("Квест с id {} не найден", quest_id))?;
             
-        quest.update_objective(objective_id, value)
+        quest.update_objective(objective_id, value, clock)
     }
     
     /// Увеличивает прогресс цели квеста
-    pub fn increment_objective(&mut self, quest_id: &str, objective_id: &str, amount: u32) -> Result<bool, String> {
+    pub fn increment_objective(&mut self, quest_id: &str, objective_id: &str, amount: u32, clock: &GameClock) -> Result<bool, String> {
         let quest = self.get_quest_mut(quest_id)
             .ok_or_else(|| format!("Квест с id {} не найден", quest_id))?;
             
-        quest.increment_objective(objective_id, amount)
+        quest.increment_objective(objective_id, amount, clock)
     }
     
     /// Возвращает список всех активных квестов
@@ -685,11 +713,11 @@ impl Achievement {
         self
     }
     
-    /// Разблокирует достижение
-    pub fn unlock(&mut self) -> bool {
+    /// Разблокирует достижение; дата разблокировки берется из игровых часов
+    pub fn unlock(&mut self, clock: &GameClock) -> bool {
         if !self.unlocked {
             self.unlocked = true;
-            self.unlock_date = Some(Utc::now());
+            self.unlock_date = Some(clock.now());
             true
         } else {
             false
@@ -697,7 +725,7 @@ impl Achievement {
     }
     
     /// Обновляет прогресс достижения
-    pub fn update_progress(&mut self, current: u32) -> bool {
+    pub fn update_progress(&mut self, current: u32, clock: &GameClock) -> bool {
         if self.unlocked {
             return false;
         }
@@ -707,7 +735,7 @@ impl Achievement {
             
             // Проверяем, достигли ли мы цели
             if current >= target {
-                return self.unlock();
+                return self.unlock(clock);
             }
         }
         
@@ -715,7 +743,7 @@ impl Achievement {
     }
     
     /// Увеличивает прогресс достижения
-    pub fn increment_progress(&mut self, amount: u32) -> bool {
+    pub fn increment_progress(&mut self, amount: u32, clock: &GameClock) -> bool {
         if self.unlocked {
             return false;
         }
@@ -726,7 +754,7 @@ impl Achievement {
             
             // Проверяем, достигли ли мы цели
             if new_current >= target {
-                return self.unlock();
+                return self.unlock(clock);
             }
         }
         
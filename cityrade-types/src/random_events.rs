@@ -1,9 +1,11 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use rand::{Rng, rngs::ThreadRng};
+use rand::Rng;
 use rand_distr::weighted::WeightedIndex;
 use crate::resources::{Resource, ResourceType};
 use crate::city::City;
+use crate::events::{EventSystem, RandomEventTriggeredEvent};
+use crate::rng::{self, GameRng};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum EventCategory {
@@ -51,17 +53,22 @@ pub struct RandomEvent {
 pub struct EventManager {
     events: HashMap<String, RandomEvent>,
     active_events: Vec<(String, u32)>, // (event_id, remaining_turns)
-    rng: ThreadRng,
+    rng: GameRng,
 }
 
 impl EventManager {
     pub fn new() -> Self {
+        Self::with_seed(rand::random())
+    }
+    
+    /// Создает менеджер с детерминированным генератором случайных чисел
+    pub fn with_seed(seed: u64) -> Self {
         let events = Self::default_events();
         
         EventManager {
             events,
             active_events: Vec::new(),
-            rng: rand::rng(),
+            rng: rng::seeded(seed),
         }
     }
     
@@ -236,18 +243,19 @@ impl EventManager {
         self.events.get(event_id)
     }
     
-    // Сгенерировать случайное событие с учетом текущего состояния города
-    pub fn generate_event(&mut self, city: &City) -> Option<&RandomEvent> {
-        let population = city.population;
+    // Сгенерировать случайное событие с учетом текущего состояния города.
+    // Обработчики RandomEventTriggeredEvent могут отменить выбранное событие.
+    pub fn generate_event(&mut self, city: &City, events: &EventSystem) -> Option<&RandomEvent> {
+        let population = city.population.total();
         
         // Фильтруем события, которые могут произойти
-        let eligible_events: Vec<&RandomEvent> = self.events.values()
+        let mut eligible_events: Vec<&RandomEvent> = self.events.values()
             .filter(|event| {
                 // Проверка минимального населения
-                if let Some(min_pop) = event.min_population {
-                    if population < min_pop {
-                        return false;
-                    }
+                if let Some(min_pop) = event.min_population
+                    && population < min_pop
+                {
+                    return false;
                 }
                 
                 // Проверка требуемых зданий
@@ -272,6 +280,9 @@ impl EventManager {
             return None;
         }
         
+        // Порядок кандидатов не должен зависеть от хеширования
+        eligible_events.sort_by(|a, b| a.event_id.cmp(&b.event_id));
+        
         // Создаем взвешенное распределение
         let weights: Vec<u32> = eligible_events.iter()
             .map(|event| event.weight)
@@ -283,6 +294,15 @@ impl EventManager {
         let chosen_index = self.rng.sample(dist);
         let chosen_event = eligible_events[chosen_index];
         
+        let mut event = RandomEventTriggeredEvent::new(
+            city.id.clone(),
+            chosen_event.event_id.clone(),
+            chosen_event.severity.clone(),
+        );
+        if !events.call_event(&mut event) {
+            return None;
+        }
+        
         // Добавляем событие в активные, если у него есть длительные эффекты
         let has_duration = chosen_event.effects.iter()
             .any(|effect| effect.duration.is_some() && effect.duration.unwrap() > 0);
@@ -298,19 +318,20 @@ impl EventManager {
         
         // Проверка на события-продолжения
         for (follow_up_id, chance) in &chosen_event.follow_up_events {
-            if self.rng.random_range(1..=100) <= *chance {
-                if let Some(follow_up_event) = self.events.get(follow_up_id) {
-                    println!("Запланировано событие-продолжение: {}", follow_up_event.name);
-                    // Логика планирования событий-продолжений
-                }
+            if self.rng.random_range(1..=100) <= *chance
+                && let Some(follow_up_event) = self.events.get(follow_up_id)
+            {
+                println!("Запланировано событие-продолжение: {}", follow_up_event.name);
+                // Логика планирования событий-продолжений
             }
         }
         
         Some(chosen_event)
     }
     
-    // Применить эффекты события к ресурсам
-    pub fn apply_event_effects(&self, event: &RandomEvent, resources: &mut Resource) {
+    // Применить эффекты события к ресурсам; возвращает фактические изменения
+    pub fn apply_event_effects(&self, event: &RandomEvent, resources: &mut Resource) -> Vec<(ResourceType, i64)> {
+        let mut changes = Vec::new();
         for effect in &event.effects {
             if let Some(resource_type) = &effect.resource_type {
                 let current_amount = resources.get(resource_type);
@@ -327,12 +348,20 @@ impl EventManager {
                     let new_amount = (current_amount as i32 + change).max(0) as u32;
                     resources.set(resource_type.clone(), new_amount);
                 }
+                
+                let change = resources.get(resource_type) as i64 - current_amount as i64;
+                if change != 0 {
+                    changes.push((resource_type.clone(), change));
+                }
             }
         }
+        changes
     }
     
-    // Обновить состояние активных событий (вызывать в конце каждого хода)
-    pub fn update_active_events(&mut self, resources: &mut Resource) {
+    // Обновить состояние активных событий (вызывать в конце каждого хода);
+    // возвращает изменения ресурсов: (ID события, ресурс, изменение)
+    pub fn update_active_events(&mut self, resources: &mut Resource) -> Vec<(String, ResourceType, i64)> {
+        let mut changes = Vec::new();
         // Обновляем счетчики активных событий
         let mut i = 0;
         while i < self.active_events.len() {
@@ -348,17 +377,22 @@ impl EventManager {
                 // Применяем продолжающиеся эффекты
                 if let Some(event) = self.events.get(event_id) {
                     for effect in &event.effects {
-                        if let Some(duration) = effect.duration {
-                            if duration > 0 {
-                                // Применяем только продолжающиеся эффекты
-                                if let Some(resource_type) = &effect.resource_type {
-                                    let current_amount = resources.get(resource_type);
+                        if let Some(duration) = effect.duration
+                            && duration > 0
+                        {
+                            // Применяем только продолжающиеся эффекты
+                            if let Some(resource_type) = &effect.resource_type {
+                                let current_amount = resources.get(resource_type);
+                                
+                                // Применение процентного изменения
+                                if let Some(percent) = effect.percent {
+                                    let change = (current_amount as f32 * percent).round() as i32;
+                                    let new_amount = (current_amount as i32 + change).max(0) as u32;
+                                    resources.set(resource_type.clone(), new_amount);
                                     
-                                    // Применение процентного изменения
-                                    if let Some(percent) = effect.percent {
-                                        let change = (current_amount as f32 * percent).round() as i32;
-                                        let new_amount = (current_amount as i32 + change).max(0) as u32;
-                                        resources.set(resource_type.clone(), new_amount);
+                                    let applied = new_amount as i64 - current_amount as i64;
+                                    if applied != 0 {
+                                        changes.push((event_id.clone(), resource_type.clone(), applied));
                                     }
                                 }
                             }
@@ -369,6 +403,8 @@ impl EventManager {
                 i += 1;
             }
         }
+        
+        changes
     }
 }
 
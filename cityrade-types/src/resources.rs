@@ -5,8 +5,14 @@ use std::fmt;
 use std::default::Default;
 use std::str::FromStr;
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+use crate::storage;
+
+/// Прежнее место типа зданий; тип перенесен в `building`
+pub use crate::building::BuildingType;
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize)]
 pub enum ResourceType {
+    #[default]
     Food,
     Wood,
     Stone,
@@ -20,6 +26,23 @@ pub enum ResourceType {
     Crystal,
 }
 
+impl ResourceType {
+    /// Все типы ресурсов
+    pub const ALL: [ResourceType; 11] = [
+        ResourceType::Food,
+        ResourceType::Wood,
+        ResourceType::Stone,
+        ResourceType::Iron,
+        ResourceType::Gold,
+        ResourceType::Coal,
+        ResourceType::Tools,
+        ResourceType::LuxuryGoods,
+        ResourceType::Population,
+        ResourceType::Energy,
+        ResourceType::Crystal,
+    ];
+}
+
 impl fmt::Display for ResourceType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -38,12 +61,6 @@ impl fmt::Display for ResourceType {
     }
 }
 
-impl Default for ResourceType {
-    fn default() -> Self {
-        ResourceType::Food
-    }
-}
-
 impl FromStr for ResourceType {
     type Err = String;
 
@@ -65,20 +82,6 @@ impl FromStr for ResourceType {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub enum BuildingType {
-    Farm,
-    Mine,
-    Sawmill,
-    House,
-    Market,
-    Bank,
-    School,
-    PowerPlant,
-    Laboratory,
-    CrystalMine,
-}
-
 #[derive(Debug, Serialize, Deserialize)]
 pub enum BuildingEffect {
     ProductionBoost,
@@ -86,10 +89,22 @@ pub enum BuildingEffect {
     StorageCapacity,
 }
 
+/// Запасы ресурсов города.
+///
+/// Ресурс с заданной вместимостью не может превысить ее: то, что не
+/// поместилось, копится как излишек (`take_overflow`) и распределяется по
+/// политике переполнения города. Сложение не переполняет `u32` — лишнее
+/// тоже считается излишком.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Resource {
     resources: HashMap<ResourceType, u32>,
     production_rate: HashMap<ResourceType, i32>,
+    /// Вместимость складов; ресурсы без записи не ограничены
+    #[serde(default)]
+    capacity: HashMap<ResourceType, u32>,
+    /// Излишки, еще не распределенные по политике переполнения
+    #[serde(default)]
+    overflow: HashMap<ResourceType, u32>,
 }
 
 impl Resource {
@@ -120,6 +135,8 @@ impl Resource {
         Resource {
             resources,
             production_rate,
+            capacity: HashMap::new(),
+            overflow: HashMap::new(),
         }
     }
 
@@ -135,13 +152,96 @@ impl Resource {
         *self.resources.get(resource).unwrap_or(&0)
     }
 
+    /// Устанавливает запас без учета вместимости
     pub fn set(&mut self, resource: ResourceType, amount: u32) {
         self.resources.insert(resource, amount);
     }
 
-    pub fn add(&mut self, resource: &ResourceType, amount: u32) {
-        let current = self.get(resource);
-        self.resources.insert(resource.clone(), current + amount);
+    /// Добавляет ресурс. То, что не поместилось, записывается в излишки;
+    /// возвращает размер излишка.
+    pub fn add(&mut self, resource: &ResourceType, amount: u32) -> u32 {
+        let excess = self.try_store(resource, amount);
+        if excess > 0 {
+            let pending = self.overflow.entry(resource.clone()).or_insert(0);
+            *pending = pending.saturating_add(excess);
+        }
+        excess
+    }
+
+    /// Добавляет столько ресурса, сколько помещается, и возвращает остаток.
+    /// Остаток не записывается в излишки.
+    pub fn try_store(&mut self, resource: &ResourceType, amount: u32) -> u32 {
+        let stored = amount.min(self.free_space(resource));
+        if stored > 0 {
+            let current = self.get(resource);
+            self.resources.insert(resource.clone(), current + stored);
+        }
+        amount - stored
+    }
+
+    /// Вместимость склада для ресурса (`None` — без ограничения)
+    pub fn capacity(&self, resource: &ResourceType) -> Option<u32> {
+        self.capacity.get(resource).copied()
+    }
+
+    /// Устанавливает вместимость склада. Если запас больше новой
+    /// вместимости, лишнее переходит в излишки.
+    pub fn set_capacity(&mut self, resource: ResourceType, capacity: Option<u32>) {
+        match capacity {
+            Some(capacity) => {
+                let current = self.get(&resource);
+                if current > capacity {
+                    self.resources.insert(resource.clone(), capacity);
+                    let pending = self.overflow.entry(resource.clone()).or_insert(0);
+                    *pending = pending.saturating_add(current - capacity);
+                }
+                self.capacity.insert(resource, capacity);
+            }
+            None => {
+                self.capacity.remove(&resource);
+            }
+        }
+    }
+
+    /// Сколько ресурса еще помещается на склад
+    pub fn free_space(&self, resource: &ResourceType) -> u32 {
+        let limit = self.capacity(resource).unwrap_or(u32::MAX);
+        limit.saturating_sub(self.get(resource))
+    }
+
+    /// Заполнен ли склад ресурса
+    pub fn is_full(&self, resource: &ResourceType) -> bool {
+        self.free_space(resource) == 0
+    }
+
+    /// Нераспределенный излишек ресурса
+    pub fn pending_overflow(&self, resource: &ResourceType) -> u32 {
+        *self.overflow.get(resource).unwrap_or(&0)
+    }
+
+    /// Забирает накопленные излишки (в порядке типов ресурсов)
+    pub fn take_overflow(&mut self) -> Vec<(ResourceType, u32)> {
+        let mut overflow: Vec<(ResourceType, u32)> = self.overflow.drain().collect();
+        overflow.sort();
+        overflow
+    }
+
+    /// Портит часть скоропортящихся запасов (см. `storage::spoilage_rate`).
+    /// Возвращает потерянное количество по ресурсам.
+    pub fn apply_spoilage(&mut self) -> Vec<(ResourceType, u32)> {
+        let mut spoiled = Vec::new();
+        let mut stored: Vec<(ResourceType, u32)> = self.get_all_resources();
+        stored.sort();
+
+        for (resource, amount) in stored {
+            let lost = (amount as f32 * storage::spoilage_rate(&resource)) as u32;
+            if lost > 0 {
+                self.resources.insert(resource.clone(), amount - lost);
+                spoiled.push((resource, lost));
+            }
+        }
+
+        spoiled
     }
 
     pub fn subtract(&mut self, resource: &ResourceType, amount: u32) -> bool {
@@ -162,16 +262,28 @@ impl Resource {
         self.production_rate.insert(resource, rate);
     }
 
-    pub fn update_production(&mut self) {
-        for (resource, rate) in self.production_rate.clone() {
+    /// Применяет скорости производства к запасам. Отрицательная скорость
+    /// списывает ресурс, но не ниже нуля; возвращает, сколько каждого ресурса
+    /// не удалось списать.
+    pub fn update_production(&mut self) -> Vec<(ResourceType, u32)> {
+        let mut rates = self.get_all_production_rates();
+        rates.sort();
+
+        let mut shortfall = Vec::new();
+        for (resource, rate) in rates {
             if rate > 0 {
                 self.add(&resource, rate as u32);
             } else if rate < 0 {
-                // Игнорируем результат вычитания - если ресурсов не хватает,
-                // они просто становятся равными нулю
-                let _ = self.subtract(&resource, (-rate) as u32);
+                let required = rate.unsigned_abs();
+                let current = self.get(&resource);
+                let paid = required.min(current);
+                self.resources.insert(resource.clone(), current - paid);
+                if paid < required {
+                    shortfall.push((resource, required - paid));
+                }
             }
         }
+        shortfall
     }
 
     pub fn can_afford(&self, costs: &[(ResourceType, u32)]) -> bool {
@@ -0,0 +1,19 @@
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use uuid::Builder;
+
+/// Генератор случайных чисел, используемый симуляцией.
+///
+/// Все случайные решения (рост населения, колебания цен, случайные события)
+/// берутся из генератора, переданного вызывающей стороной, поэтому одинаковое
+/// зерно и одинаковая последовательность команд дают одинаковое состояние мира.
+pub type GameRng = StdRng;
+
+/// Создает генератор из зерна мира
+pub fn seeded(seed: u64) -> GameRng {
+    StdRng::seed_from_u64(seed)
+}
+
+/// Генерирует идентификатор в формате UUID v4 из переданного генератора
+pub fn random_id<R: Rng + ?Sized>(rng: &mut R) -> String {
+    Builder::from_random_bytes(rng.random()).into_uuid().to_string()
+}
@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::resources::ResourceType;
+
+/// Ресурсы, которые хранятся на складах и ограничены вместимостью
+pub const STORED_RESOURCES: [ResourceType; 9] = [
+    ResourceType::Food,
+    ResourceType::Wood,
+    ResourceType::Stone,
+    ResourceType::Iron,
+    ResourceType::Coal,
+    ResourceType::Tools,
+    ResourceType::Gold,
+    ResourceType::Crystal,
+    ResourceType::LuxuryGoods,
+];
+
+/// Вместимость складов города без построек.
+/// `None` — ресурс не хранится на складах и не ограничен (население, энергия).
+pub fn base_capacity(resource: &ResourceType) -> Option<u32> {
+    match resource {
+        ResourceType::Food => Some(1000),
+        ResourceType::Wood => Some(1000),
+        ResourceType::Stone => Some(1000),
+        ResourceType::Iron => Some(500),
+        ResourceType::Coal => Some(500),
+        ResourceType::Tools => Some(300),
+        ResourceType::Gold => Some(2000),
+        ResourceType::Crystal => Some(200),
+        ResourceType::LuxuryGoods => Some(200),
+        ResourceType::Population | ResourceType::Energy => None,
+    }
+}
+
+/// Доля запаса, которая портится за ход
+pub fn spoilage_rate(resource: &ResourceType) -> f32 {
+    match resource {
+        ResourceType::Food => 0.02,
+        _ => 0.0,
+    }
+}
+
+/// Что делать с ресурсом, который не поместился на склад
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum OverflowPolicy {
+    /// Излишек теряется
+    #[default]
+    Waste,
+    /// Излишек продается на рынке города
+    AutoSell,
+    /// Излишек отправляется в связанный город (ID города);
+    /// то, что не поместилось и там, теряется
+    Spill(String),
+}
+
+/// Политика переполнения складов города
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct StoragePolicy {
+    /// Политика для ресурсов без отдельной настройки
+    pub default: OverflowPolicy,
+    /// Политики для отдельных ресурсов
+    pub overrides: BTreeMap<ResourceType, OverflowPolicy>,
+}
+
+impl StoragePolicy {
+    /// Политика для указанного ресурса
+    pub fn policy_for(&self, resource: &ResourceType) -> &OverflowPolicy {
+        self.overrides.get(resource).unwrap_or(&self.default)
+    }
+
+    /// Устанавливает политику для отдельного ресурса
+    pub fn set(&mut self, resource: ResourceType, policy: OverflowPolicy) {
+        self.overrides.insert(resource, policy);
+    }
+}
+
+/// Как был распределен излишек ресурса города за ход
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OverflowResolution {
+    pub city_id: String,
+    pub resource: ResourceType,
+    /// Излишек, не поместившийся на склад
+    pub amount: u32,
+    /// Золото, полученное от продажи излишка
+    pub sold_for: u32,
+    /// Сколько принял связанный город
+    pub spilled: u32,
+    /// Сколько потеряно
+    pub wasted: u32,
+}
@@ -0,0 +1,175 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::population::{Population, PopulationClass};
+
+/// Налог, при котором жители не недовольны и не рады (в процентах)
+pub const NEUTRAL_TAX_RATE: u32 = 10;
+/// Самая высокая допустимая ставка налога или пошлины (в процентах)
+pub const MAX_TAX_RATE: u32 = 100;
+/// Проценты по долгу казны за ход
+pub const DEBT_INTEREST_PERCENT: u32 = 5;
+/// Долг, при котором город объявляется банкротом
+pub const BANKRUPTCY_DEBT: u32 = 500;
+/// Сколько золота долга отнимает единицу счастья
+pub const DEBT_PER_HAPPINESS: u32 = 50;
+/// Наибольший штраф к счастью от долга, пока город не банкрот
+pub const MAX_DEBT_HAPPINESS_PENALTY: u32 = 10;
+/// Штраф к счастью в банкротстве
+pub const BANKRUPTCY_HAPPINESS_PENALTY: u32 = 20;
+
+/// Причина, по которой ставку нельзя установить
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaxError {
+    /// Ставка налога выше `MAX_TAX_RATE`
+    IncomeTaxTooHigh(u32),
+    /// Пошлина выше `MAX_TAX_RATE`
+    TariffTooHigh(u32),
+}
+
+impl fmt::Display for TaxError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TaxError::IncomeTaxTooHigh(_) => write!(f, "Ставка налога не может превышать {}%", MAX_TAX_RATE),
+            TaxError::TariffTooHigh(_) => write!(f, "Пошлина не может превышать {}%", MAX_TAX_RATE),
+        }
+    }
+}
+
+impl std::error::Error for TaxError {}
+
+/// Налоговая политика города
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaxPolicy {
+    /// Подоходный налог по классам (в процентах); для класса без записи — `NEUTRAL_TAX_RATE`
+    pub income_tax: BTreeMap<PopulationClass, u32>,
+    /// Пошлина с торговых маршрутов города (в процентах от их оборота)
+    pub tariff: u32,
+    /// Содержание зданий из казны: золото за уровень здания за ход
+    pub maintenance: u32,
+}
+
+impl Default for TaxPolicy {
+    fn default() -> Self {
+        TaxPolicy {
+            income_tax: BTreeMap::new(),
+            tariff: 5,
+            maintenance: 1,
+        }
+    }
+}
+
+impl TaxPolicy {
+    /// Ставка подоходного налога класса
+    pub fn income_tax(&self, class: &PopulationClass) -> u32 {
+        self.income_tax.get(class).copied().unwrap_or(NEUTRAL_TAX_RATE)
+    }
+
+    /// Задает ставку подоходного налога класса
+    pub fn set_income_tax(&mut self, class: PopulationClass, rate: u32) -> Result<(), TaxError> {
+        if rate > MAX_TAX_RATE {
+            return Err(TaxError::IncomeTaxTooHigh(rate));
+        }
+        self.income_tax.insert(class, rate);
+        Ok(())
+    }
+
+    /// Задает торговую пошлину
+    pub fn set_tariff(&mut self, rate: u32) -> Result<(), TaxError> {
+        if rate > MAX_TAX_RATE {
+            return Err(TaxError::TariffTooHigh(rate));
+        }
+        self.tariff = rate;
+        Ok(())
+    }
+
+    /// Подоходный налог по классам за ход
+    pub fn income_tax_revenue(&self, population: &Population) -> BTreeMap<PopulationClass, u32> {
+        population
+            .classes
+            .iter()
+            .map(|(class, count)| (class.clone(), count * class.income() * self.income_tax(class) / 100))
+            .filter(|(_, revenue)| *revenue > 0)
+            .collect()
+    }
+
+    /// Пошлина со сделки на сумму `deal` золота
+    pub fn tariff_on(&self, deal: u32) -> u32 {
+        (deal as u64 * self.tariff as u64 / 100) as u32
+    }
+
+    /// Влияние налогов на счастье города: каждые 2% сверх `NEUTRAL_TAX_RATE`
+    /// (в среднем по жителям) отнимают единицу счастья, ставка ниже — добавляет
+    pub fn happiness_modifier(&self, population: &Population) -> i32 {
+        let total = population.total();
+        if total == 0 {
+            return 0;
+        }
+        let weighted: u32 = population
+            .classes
+            .iter()
+            .map(|(class, count)| count * self.income_tax(class))
+            .sum();
+        let average = (weighted / total) as i32;
+        (NEUTRAL_TAX_RATE as i32 - average) / 2
+    }
+
+    /// Влияние пошлин на прирост населения (в процентах): купцы и
+    /// переселенцы обходят город с высокими пошлинами
+    pub fn growth_modifier(&self) -> i32 {
+        -(self.tariff as i32) / 2
+    }
+}
+
+/// Состояние казны
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FiscalStatus {
+    Solvent,
+    /// Казна в долгу: счастье падает
+    InDebt,
+    /// Долг не меньше `BANKRUPTCY_DEBT`: строительство остановлено
+    Bankrupt,
+}
+
+/// Состояние казны при долге `debt`
+pub fn fiscal_status(debt: u32) -> FiscalStatus {
+    if debt >= BANKRUPTCY_DEBT {
+        FiscalStatus::Bankrupt
+    } else if debt > 0 {
+        FiscalStatus::InDebt
+    } else {
+        FiscalStatus::Solvent
+    }
+}
+
+/// Штраф к счастью от долга
+pub fn debt_happiness_penalty(debt: u32) -> u32 {
+    match fiscal_status(debt) {
+        FiscalStatus::Solvent => 0,
+        FiscalStatus::InDebt => (debt / DEBT_PER_HAPPINESS).clamp(1, MAX_DEBT_HAPPINESS_PENALTY),
+        FiscalStatus::Bankrupt => BANKRUPTCY_HAPPINESS_PENALTY,
+    }
+}
+
+/// Проценты, начисляемые на долг за ход
+pub fn debt_interest(debt: u32) -> u32 {
+    debt.saturating_mul(DEBT_INTEREST_PERCENT).div_ceil(100)
+}
+
+/// Итоги налогового хода города
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FiscalReport {
+    /// Подоходный налог по классам
+    pub income_tax: BTreeMap<PopulationClass, u32>,
+    /// Пошлины с торговых маршрутов с начала хода налогов
+    pub tariffs: u32,
+    /// Начисленное содержание зданий
+    pub maintenance: u32,
+    /// Проценты, добавленные к долгу
+    pub interest: u32,
+    /// Погашено долга
+    pub repaid: u32,
+    /// Долг после хода
+    pub debt: u32,
+}
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
-use super::resources::{ResourceType, BuildingType};
+use super::building::BuildingType;
+use super::resources::ResourceType;
+use super::events::{EventSystem, ResearchCompletedEvent};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum TechnologyType {
@@ -84,6 +86,8 @@ pub enum ResearchStatus {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TechnologyTree {
+    #[serde(default)]
+    pub owner_id: String,
     pub technologies: HashMap<TechnologyType, Technology>,
     pub research_status: HashMap<TechnologyType, ResearchStatus>,
     pub research_focus: Option<TechnologyType>,
@@ -108,9 +112,16 @@ pub enum TechnologyEffect {
     TradeRouteSlot(u32),
 }
 
+impl Default for TechnologyTree {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TechnologyTree {
     pub fn new() -> Self {
         TechnologyTree {
+            owner_id: String::new(),
             technologies: Self::default_technologies(),
             research_status: HashMap::new(),
             research_focus: None,
@@ -121,6 +132,12 @@ impl TechnologyTree {
         }
     }
     
+    // Указать ID игрока, которому принадлежит дерево
+    pub fn with_owner(mut self, owner_id: String) -> Self {
+        self.owner_id = owner_id;
+        self
+    }
+    
     // Создаем набор стандартных технологий
     fn default_technologies() -> HashMap<TechnologyType, Technology> {
         let mut technologies = HashMap::new();
@@ -179,7 +196,7 @@ impl TechnologyTree {
                 TechnologyEffectData {
                     description: "Увеличивает производство дерева на 20%".to_string(),
                     resource_bonus: Some(("Wood".to_string(), 0.2)),
-                    building_unlock: Some("Sawmill".to_string()),
+                    building_unlock: Some("LumberMill".to_string()),
                     cost_reduction: None,
                     other_bonuses: HashMap::new(),
                 }
@@ -199,9 +216,13 @@ impl TechnologyTree {
                 TechnologyEffectData {
                     description: "Позволяет строить базовые здания".to_string(),
                     resource_bonus: None,
-                    building_unlock: Some("House".to_string()),
+                    building_unlock: Some("Residential".to_string()),
                     cost_reduction: Some(("Construction".to_string(), 0.1)),
-                    other_bonuses: HashMap::new(),
+                    other_bonuses: {
+                        let mut map = HashMap::new();
+                        map.insert("BuildingSpeed".to_string(), 0.1);
+                        map
+                    },
                 }
             ],
         });
@@ -246,7 +267,12 @@ impl TechnologyTree {
                     resource_bonus: None,
                     building_unlock: Some("Apartment".to_string()),
                     cost_reduction: Some(("Construction".to_string(), 0.15)),
-                    other_bonuses: HashMap::new(),
+                    other_bonuses: {
+                        // Заменяет бонус основ строительства
+                        let mut map = HashMap::new();
+                        map.insert("BuildingSpeed".to_string(), 0.25);
+                        map
+                    },
                 }
             ],
         });
@@ -356,37 +382,39 @@ impl TechnologyTree {
     }
     
     // Добавить очки исследования к текущему фокусу
-    pub fn add_research_points(&mut self, points: u32) -> Option<TechnologyType> {
-        if let Some(tech_type) = &self.research_focus {
-            if let Some(tech) = self.technologies.get(tech_type) {
-                if let Some(ResearchStatus::InProgress(current_points)) = self.research_status.get_mut(tech_type) {
-                    *current_points += points;
+    pub fn add_research_points(&mut self, points: u32, events: &EventSystem) -> Option<TechnologyType> {
+        if let Some(tech_type) = &self.research_focus
+            && let Some(tech) = self.technologies.get(tech_type)
+            && let Some(ResearchStatus::InProgress(current_points)) = self.research_status.get_mut(tech_type)
+        {
+            *current_points += points;
+            
+            // Проверяем, завершено ли исследование
+            if *current_points >= tech.cost {
+                let tech_type = tech_type.clone();
+                self.research_status.insert(tech_type.clone(), ResearchStatus::Completed);
+                self.completed_technologies.insert(tech_type.clone());
+                self.research_focus = None;
+                
+                // Применяем эффекты технологии
+                for effect in &tech.unlock_effects {
+                    if let Some((resource, bonus)) = &effect.resource_bonus {
+                        self.tech_bonuses.insert(format!("Resource_{}", resource), *bonus);
+                    }
                     
-                    // Проверяем, завершено ли исследование
-                    if *current_points >= tech.cost {
-                        let tech_type = tech_type.clone();
-                        self.research_status.insert(tech_type.clone(), ResearchStatus::Completed);
-                        self.completed_technologies.insert(tech_type.clone());
-                        self.research_focus = None;
-                        
-                        // Применяем эффекты технологии
-                        for effect in &tech.unlock_effects {
-                            if let Some((resource, bonus)) = &effect.resource_bonus {
-                                self.tech_bonuses.insert(format!("Resource_{}", resource), *bonus);
-                            }
-                            
-                            if let Some((cost_type, reduction)) = &effect.cost_reduction {
-                                self.tech_bonuses.insert(format!("Cost_{}", cost_type), *reduction);
-                            }
-                            
-                            for (bonus_type, value) in &effect.other_bonuses {
-                                self.tech_bonuses.insert(bonus_type.clone(), *value);
-                            }
-                        }
-                        
-                        return Some(tech_type.clone());
+                    if let Some((cost_type, reduction)) = &effect.cost_reduction {
+                        self.tech_bonuses.insert(format!("Cost_{}", cost_type), *reduction);
+                    }
+                    
+                    for (bonus_type, value) in &effect.other_bonuses {
+                        self.tech_bonuses.insert(bonus_type.clone(), *value);
                     }
                 }
+                
+                let mut event = ResearchCompletedEvent::new(self.owner_id.clone(), tech_type.clone());
+                events.call_event(&mut event);
+                
+                return Some(tech_type);
             }
         }
         
@@ -394,18 +422,17 @@ impl TechnologyTree {
     }
     
     // Обновить исследования (вызывать каждый ход)
-    pub fn update_research(&mut self) -> Option<TechnologyType> {
-        self.add_research_points(self.research_rate)
+    pub fn update_research(&mut self, events: &EventSystem) -> Option<TechnologyType> {
+        self.add_research_points(self.research_rate, events)
     }
     
     // Получить текущий прогресс исследования
     pub fn get_research_progress(&self) -> Option<(TechnologyType, u32, u32)> {
-        if let Some(tech_type) = &self.research_focus {
-            if let Some(tech) = self.technologies.get(tech_type) {
-                if let Some(ResearchStatus::InProgress(current_points)) = self.research_status.get(tech_type) {
-                    return Some((tech_type.clone(), *current_points, tech.cost));
-                }
-            }
+        if let Some(tech_type) = &self.research_focus
+            && let Some(tech) = self.technologies.get(tech_type)
+            && let Some(ResearchStatus::InProgress(current_points)) = self.research_status.get(tech_type)
+        {
+            return Some((tech_type.clone(), *current_points, tech.cost));
         }
         
         None
@@ -439,6 +466,11 @@ impl TechnologyTree {
             .collect()
     }
     
+    // Исследована ли технология
+    pub fn is_researched(&self, tech_type: &TechnologyType) -> bool {
+        self.completed_technologies.contains(tech_type)
+    }
+    
     // Получить список завершенных технологий
     pub fn get_completed_technologies(&self) -> Vec<&Technology> {
         self.technologies.values()
@@ -457,4 +489,9 @@ impl TechnologyTree {
     pub fn get_cost_reduction_bonus(&self, cost_type: &str) -> f32 {
         self.get_tech_bonus(&format!("Cost_{}", cost_type))
     }
+    
+    // Получить бонус к скорости строительства (доля, 0.1 = +10%)
+    pub fn get_building_speed_bonus(&self) -> f32 {
+        self.get_tech_bonus("BuildingSpeed")
+    }
 }
@@ -0,0 +1,138 @@
+use std::collections::BTreeMap;
+
+use crate::resources::ResourceType;
+use crate::world::{CITY_TERRITORY_RADIUS, TerrainTile, WorldMap};
+
+/// Стоимость первой клетки, присоединяемой к территории, в очках культуры
+pub const BORDER_TILE_BASE_COST: u32 = 20;
+/// Удорожание клетки за каждую клетку, которая уже есть у города
+pub const BORDER_TILE_COST_PER_TILE: u32 = 2;
+/// Во сколько процентов от культуры владельца должна быть культура соседа,
+/// чтобы тот забрал пограничную клетку
+pub const CULTURE_DOMINANCE_PERCENT: u32 = 200;
+/// Сколько жителей обрабатывают одну клетку территории
+pub const POPULATION_PER_WORKED_TILE: u32 = 5;
+
+/// Сколько очков культуры стоит следующая клетка для города с `territory_size` клетками
+pub fn border_tile_cost(territory_size: usize) -> u32 {
+    BORDER_TILE_BASE_COST + BORDER_TILE_COST_PER_TILE * territory_size as u32
+}
+
+/// Заявка города на новую клетку территории
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BorderClaim {
+    pub city_id: String,
+    /// Культура города (`CityStats::culture`)
+    pub culture: u32,
+}
+
+/// Расширяет границы городов по заявкам: каждый город получает одну клетку.
+///
+/// Заявки обрабатываются по убыванию культуры, поэтому клетку, на которую
+/// претендуют несколько городов, получает самый культурный (при равенстве —
+/// с меньшим ID). Город берет свободную клетку на границе, ближайшую к
+/// центру; если свободных нет — чужую пограничную клетку, если его культура
+/// не меньше `CULTURE_DOMINANCE_PERCENT` процентов культуры владельца
+/// (`culture` — культура всех городов). Клетки в радиусе
+/// `CITY_TERRITORY_RADIUS` от чужого центра не отходят никому.
+///
+/// Возвращает присоединенные клетки: (ID города, позиция).
+pub fn expand_borders(
+    world: &mut WorldMap,
+    claims: &[BorderClaim],
+    culture: &BTreeMap<String, u32>,
+) -> Vec<(String, (i32, i32))> {
+    let mut claims: Vec<&BorderClaim> = claims.iter().collect();
+    claims.sort_by(|a, b| b.culture.cmp(&a.culture).then(a.city_id.cmp(&b.city_id)));
+
+    let mut claimed = Vec::new();
+    for claim in claims {
+        let Some(center) = world.city_position(&claim.city_id) else {
+            continue;
+        };
+        let target = world
+            .frontier(&claim.city_id)
+            .into_iter()
+            .filter(|(x, y)| match world.owner(*x, *y) {
+                None => true,
+                Some(owner) => can_take(world, owner, (*x, *y), claim.culture, culture),
+            })
+            .min_by_key(|(x, y)| {
+                let foreign = world.owner(*x, *y).is_some();
+                let distance = (x - center.0).pow(2) + (y - center.1).pow(2);
+                (foreign, distance, (*x, *y))
+            });
+        if let Some((x, y)) = target {
+            world.claim(x, y, &claim.city_id);
+            claimed.push((claim.city_id.clone(), (x, y)));
+        }
+    }
+    claimed
+}
+
+/// Может ли город с культурой `culture` забрать клетку `position` у `owner`
+fn can_take(
+    world: &WorldMap,
+    owner: &str,
+    position: (i32, i32),
+    culture: u32,
+    cultures: &BTreeMap<String, u32>,
+) -> bool {
+    if let Some(center) = world.city_position(owner)
+        && (position.0 - center.0).abs() <= CITY_TERRITORY_RADIUS
+        && (position.1 - center.1).abs() <= CITY_TERRITORY_RADIUS
+    {
+        return false;
+    }
+    let owner_culture = cultures.get(owner).copied().unwrap_or(0);
+    culture > 0 && culture * 100 >= owner_culture * CULTURE_DOMINANCE_PERCENT
+}
+
+/// Что дает обрабатываемая клетка за ход
+pub fn tile_yield(tile: &TerrainTile) -> Option<(ResourceType, i32)> {
+    match tile {
+        TerrainTile::Land => Some((ResourceType::Food, 2)),
+        TerrainTile::Water => Some((ResourceType::Food, 1)),
+        TerrainTile::Forest => Some((ResourceType::Wood, 2)),
+        TerrainTile::Mountain => Some((ResourceType::Stone, 2)),
+        TerrainTile::Desert => Some((ResourceType::Gold, 1)),
+        TerrainTile::ResourceSpot(resource) => Some((resource.clone(), 2)),
+        _ => None,
+    }
+}
+
+/// Клетки территории, которые обрабатывают жители города: по одной на
+/// `POPULATION_PER_WORKED_TILE` жителей, сначала самые доходные и близкие к центру
+pub fn worked_tiles(world: &WorldMap, city_id: &str, population: u32) -> Vec<(i32, i32)> {
+    let Some(center) = world.city_position(city_id) else {
+        return Vec::new();
+    };
+    let mut tiles: Vec<((i32, i32), i32)> = world
+        .territory(city_id)
+        .into_iter()
+        .filter_map(|(x, y)| {
+            let tile = world.get_tile(x, y)?;
+            tile_yield(&tile).map(|(_, amount)| ((x, y), amount))
+        })
+        .collect();
+    tiles.sort_by_key(|((x, y), amount)| {
+        let distance = (x - center.0).pow(2) + (y - center.1).pow(2);
+        (-amount, distance, (*x, *y))
+    });
+    tiles
+        .into_iter()
+        .take((population / POPULATION_PER_WORKED_TILE) as usize)
+        .map(|(position, _)| position)
+        .collect()
+}
+
+/// Ресурсы с обрабатываемых клеток города за ход
+pub fn worked_tile_yields(world: &WorldMap, city_id: &str, population: u32) -> BTreeMap<ResourceType, i32> {
+    let mut yields = BTreeMap::new();
+    for (x, y) in worked_tiles(world, city_id, population) {
+        if let Some((resource, amount)) = world.get_tile(x, y).and_then(|tile| tile_yield(&tile)) {
+            *yields.entry(resource).or_insert(0) += amount;
+        }
+    }
+    yields
+}
@@ -0,0 +1,105 @@
+// cityrade-types/src/tests/building_registry_tests.rs
+use crate::building::BuildingType;
+use crate::building_registry::BuildingRegistry;
+use crate::city::{City, Terrain};
+use crate::clock::GameClock;
+use crate::events::EventSystem;
+use crate::production::ProductionModifiers;
+use crate::resources::ResourceType;
+use crate::rng;
+use crate::technology::TechnologyTree;
+use super::test_city;
+
+/// Реестр из встроенных описаний, в котором ферме заданы другие параметры
+fn registry_with_farm(farm: &str) -> String {
+    let mut json: serde_json::Value =
+        serde_json::from_str(&BuildingRegistry::builtin().to_json().unwrap()).unwrap();
+    let buildings = json["buildings"].as_array_mut().unwrap();
+    let farm_index = buildings.iter().position(|b| b["id"] == "Farm").unwrap();
+    buildings[farm_index] = serde_json::from_str(farm).unwrap();
+    json.to_string()
+}
+
+#[test]
+fn test_builtin_registry_covers_all_buildings() {
+    let registry = BuildingRegistry::builtin();
+
+    for building_type in BuildingType::ALL {
+        let definition = registry.get(&building_type).unwrap();
+        assert_eq!(definition.levels.len(), definition.max_level as usize);
+    }
+
+    let farm = registry.level(&BuildingType::Farm, 1).unwrap();
+    assert_eq!(farm.net_production(), vec![(ResourceType::Food, 13)]);
+}
+
+#[test]
+fn test_formats_round_trip() {
+    let registry = BuildingRegistry::builtin();
+
+    assert_eq!(BuildingRegistry::from_ron(&registry.to_ron().unwrap()).unwrap(), registry);
+    assert_eq!(BuildingRegistry::from_json(&registry.to_json().unwrap()).unwrap(), registry);
+    assert_eq!(BuildingRegistry::from_toml(&registry.to_toml().unwrap()).unwrap(), registry);
+}
+
+#[test]
+fn test_validation_errors() {
+    let data = registry_with_farm(
+        r#"{"id": "Farm", "display_name": "", "description": "", "max_level": 2,
+            "levels": [{"production": {"Food": 5}, "consumption": {"Food": 1}}]}"#,
+    );
+
+    let error = BuildingRegistry::from_json(&data).unwrap_err();
+    assert!(error.contains("не задано название"));
+    assert!(error.contains("описано уровней 1, а максимальный уровень 2"));
+    assert!(error.contains("одновременно производится и потребляется"));
+
+    let missing = r#"{"buildings": []}"#;
+    assert!(BuildingRegistry::from_json(missing).unwrap_err().contains("Нет описания здания Farm"));
+
+    assert!(BuildingRegistry::from_toml("buildings = 1").unwrap_err().starts_with("Ошибка разбора TOML"));
+}
+
+#[test]
+fn test_city_uses_registry() {
+    let data = registry_with_farm(
+        r#"{"id": "Farm", "display_name": "Ферма", "description": "", "max_level": 1,
+            "terrain": ["Plain"],
+            "levels": [{"cost": {"Gold": 7}, "production": {"Food": 40}, "stats": {"happiness": 3}}]}"#,
+    );
+    let registry = BuildingRegistry::from_json(&data).unwrap();
+    let clock = GameClock::new();
+    let mut rng = rng::seeded(1);
+
+    let mut city = test_city(Terrain::Plain);
+    let gold = city.resources.get(&ResourceType::Gold);
+    let farm_id = city
+        .add_building(BuildingType::Farm, "Ферма".to_string(), (1, 1), &registry, &TechnologyTree::new(), &mut rng)
+        .unwrap();
+    assert_eq!(city.resources.get(&ResourceType::Gold), gold - 7);
+    // build_time не задан — постройка завершается на следующем ходу
+    assert_eq!(city.update_construction(0, &EventSystem::new()), vec![farm_id.clone()]);
+
+    city.update_stats(&registry);
+    assert_eq!(city.stats.happiness, 53);
+
+    city.update_resource_production(&registry, &ProductionModifiers::default());
+    // 8 базовых + 40 от фермы, равнина дает +20% еды, жители — еще +50%
+    assert_eq!(city.resources.get_production_rate(&ResourceType::Food), 57 + 28);
+
+    // Максимальный уровень фермы — 1
+    assert!(city.upgrade_building(&farm_id, &registry, &TechnologyTree::new()).is_err());
+
+    // Ферма разрешена только на равнине
+    let mut mountain = City::new(
+        "mountain".to_string(),
+        "Горный".to_string(),
+        "player1".to_string(),
+        Terrain::Mountain,
+        (5, 5),
+        &clock,
+    );
+    assert!(mountain
+        .add_building(BuildingType::Farm, "Ферма".to_string(), (1, 1), &registry, &TechnologyTree::new(), &mut rng)
+        .is_err());
+}
@@ -0,0 +1,155 @@
+// cityrade-types/src/tests/building_tests.rs
+use std::str::FromStr;
+
+use crate::achievements::AchievementTrigger;
+use crate::building::{Building, BuildingError, BuildingType};
+use crate::building_registry::BuildingRegistry;
+use crate::city::{City, Terrain};
+use crate::constants::MAX_BUILDING_LEVEL;
+use crate::events::EventSystem;
+use crate::quest::QuestType;
+use crate::rng;
+use crate::technology::{TechnologyEffect, TechnologyTree, TechnologyType};
+use super::{test_city, with_resources};
+
+#[test]
+fn test_legacy_names_deserialize() {
+    let house: BuildingType = serde_json::from_str("\"House\"").unwrap();
+    let sawmill: BuildingType = serde_json::from_str("\"Sawmill\"").unwrap();
+
+    assert_eq!(house, BuildingType::Residential);
+    assert_eq!(sawmill, BuildingType::LumberMill);
+
+    // Сохраняется всегда новое название
+    assert_eq!(serde_json::to_string(&sawmill).unwrap(), "\"LumberMill\"");
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_legacy_path_and_variant_names() {
+    use crate::resources::BuildingType as LegacyBuildingType;
+
+    assert_eq!(LegacyBuildingType::House, BuildingType::Residential);
+    assert!(matches!(BuildingType::LumberMill, LegacyBuildingType::Sawmill));
+}
+
+#[test]
+fn test_legacy_saves_load() {
+    let quest: QuestType = serde_json::from_str(r#"{"BuildingConstruction":["House",3]}"#).unwrap();
+    assert!(matches!(quest, QuestType::BuildingConstruction(BuildingType::Residential, 3)));
+
+    let trigger: AchievementTrigger = serde_json::from_str(r#"{"BuildingCount":["Bank",2]}"#).unwrap();
+    assert!(matches!(trigger, AchievementTrigger::BuildingCount(BuildingType::Bank, 2)));
+
+    let effect: TechnologyEffect = serde_json::from_str(r#"{"UnlockBuilding":"Sawmill"}"#).unwrap();
+    assert!(matches!(effect, TechnologyEffect::UnlockBuilding(BuildingType::LumberMill)));
+}
+
+#[test]
+fn test_from_str() {
+    for building_type in BuildingType::ALL {
+        let name = format!("{:?}", building_type);
+        assert_eq!(BuildingType::from_str(&name), Ok(building_type));
+    }
+
+    assert_eq!(BuildingType::from_str("house"), Ok(BuildingType::Residential));
+    assert_eq!(BuildingType::from_str("Sawmill"), Ok(BuildingType::LumberMill));
+    assert!(BuildingType::from_str("castle").is_err());
+}
+
+fn finish_construction(city: &mut City) {
+    while !city.construction.is_empty() {
+        city.update_construction(0, &EventSystem::new());
+    }
+}
+
+#[test]
+fn test_upgrade_is_capped() {
+    let mut building = Building::new("b1".to_string(), "Ферма".to_string(), BuildingType::Farm, (0, 0));
+    building.level = MAX_BUILDING_LEVEL - 1;
+
+    assert!(building.upgrade());
+    assert!(!building.upgrade());
+    assert_eq!(building.level, MAX_BUILDING_LEVEL);
+
+    let registry = BuildingRegistry::builtin();
+    assert_eq!(building.upgrade_cost(&registry), None);
+}
+
+#[test]
+fn test_max_level_error() {
+    let registry = BuildingRegistry::builtin();
+    let technologies = TechnologyTree::new();
+    let mut city = with_resources(test_city(Terrain::Plain), 100_000);
+    let farm = city
+        .add_building(BuildingType::Farm, "Ферма".to_string(), (1, 1), &registry, &technologies, &mut rng::seeded(1))
+        .unwrap();
+    finish_construction(&mut city);
+    city.buildings.get_mut(&farm).unwrap().level = 5;
+
+    assert_eq!(
+        city.upgrade_building(&farm, &registry, &technologies),
+        Err(BuildingError::MaxLevelReached { building_type: BuildingType::Farm, max_level: 5 })
+    );
+}
+
+#[test]
+fn test_requirements_explain_what_is_missing() {
+    let registry = BuildingRegistry::builtin();
+    let mut technologies = TechnologyTree::new();
+    let mut city = with_resources(test_city(Terrain::Plain), 100_000);
+    let mut rng = rng::seeded(1);
+    // Рынок и банк должны стоять у дороги, ведущей к центру
+    for road in [(1, 0), (2, 0), (2, 1)] {
+        city.build_road(road).unwrap();
+    }
+
+    // Рынок требует технологию «Торговля»
+    let error = city
+        .add_building(BuildingType::Market, "Рынок".to_string(), (1, 1), &registry, &technologies, &mut rng)
+        .unwrap_err();
+    assert_eq!(error, BuildingError::MissingTechnology(TechnologyType::Trade));
+    assert_eq!(error.to_string(), "Требуется технология Trade");
+
+    technologies.completed_technologies.insert(TechnologyType::Trade);
+    technologies.completed_technologies.insert(TechnologyType::Banking);
+    let market = city
+        .add_building(BuildingType::Market, "Рынок".to_string(), (1, 1), &registry, &technologies, &mut rng)
+        .unwrap();
+    finish_construction(&mut city);
+
+    // Банк требует рынок второго уровня
+    assert_eq!(
+        city.add_building(BuildingType::Bank, "Банк".to_string(), (2, 2), &registry, &technologies, &mut rng),
+        Err(BuildingError::MissingBuilding { building_type: BuildingType::Market, level: 2 })
+    );
+    city.upgrade_building(&market, &registry, &technologies).unwrap();
+    finish_construction(&mut city);
+    assert!(city
+        .add_building(BuildingType::Bank, "Банк".to_string(), (2, 2), &registry, &technologies, &mut rng)
+        .is_ok());
+
+    // Четвертый уровень требует 40 жителей
+    city.upgrade_building(&market, &registry, &technologies).unwrap();
+    finish_construction(&mut city);
+    assert_eq!(
+        city.upgrade_building(&market, &registry, &technologies),
+        Err(BuildingError::NotEnoughPopulation { required: 40, actual: 10 })
+    );
+}
+
+#[test]
+fn test_registry_rejects_levels_above_cap() {
+    let mut json: serde_json::Value =
+        serde_json::from_str(&BuildingRegistry::builtin().to_json().unwrap()).unwrap();
+    let farm = &mut json["buildings"].as_array_mut().unwrap()[1];
+    assert_eq!(farm["id"], "Farm");
+    farm["max_level"] = 6.into();
+    let level = farm["levels"][0].clone();
+    farm["levels"].as_array_mut().unwrap().push(level);
+    farm["levels"][0]["requirements"] = serde_json::json!({"buildings": {"Wall": 9}});
+
+    let error = BuildingRegistry::from_json(&json.to_string()).unwrap_err();
+    assert!(error.contains("максимальный уровень должен быть от 1 до 5"));
+    assert!(error.contains("требуется Wall уровня 9"));
+}
@@ -0,0 +1,172 @@
+// cityrade-types/src/tests/city_grid_tests.rs
+use crate::building::{Building, BuildingError, BuildingType};
+use crate::building_registry::BuildingRegistry;
+use crate::city::{City, Terrain};
+use crate::city_grid::{grid_seed, CityGrid, CITY_CENTER, CITY_CORE_RADIUS, CITY_GRID_RADIUS, ROAD_COST};
+use crate::clock::GameClock;
+use crate::ledger::LedgerSource;
+use crate::production::ProductionModifiers;
+use crate::resources::ResourceType;
+use crate::rng;
+use crate::technology::TechnologyTree;
+use super::test_city;
+
+fn build(city: &mut City, building_type: BuildingType, position: (i32, i32)) -> Result<String, BuildingError> {
+    city.add_building(
+        building_type,
+        "Здание".to_string(),
+        position,
+        &BuildingRegistry::builtin(),
+        &TechnologyTree::new(),
+        &mut rng::seeded(1),
+    )
+}
+
+#[test]
+fn test_generated_grid_is_reproducible() {
+    let grid = CityGrid::generate(&Terrain::Forest, grid_seed((3, -7)));
+    assert_eq!(grid, CityGrid::generate(&Terrain::Forest, grid_seed((3, -7))));
+    assert_ne!(grid, CityGrid::generate(&Terrain::Forest, grid_seed((4, -7))));
+    assert_eq!(grid.positions().count(), ((2 * CITY_GRID_RADIUS + 1) * (2 * CITY_GRID_RADIUS + 1)) as usize);
+
+    // Ядро покрыто основной местностью, на окраинах есть вода
+    for x in -CITY_CORE_RADIUS..=CITY_CORE_RADIUS {
+        for y in -CITY_CORE_RADIUS..=CITY_CORE_RADIUS {
+            assert_eq!(grid.terrain((x, y)), Some(&Terrain::Forest));
+        }
+    }
+    assert!(grid.positions().any(|position| grid.terrain(position) == Some(&Terrain::Water)));
+    assert!(grid.has_road(CITY_CENTER));
+    assert_eq!(grid.terrain((CITY_GRID_RADIUS + 1, 0)), None);
+
+    // Сетка сохраняется вместе с городом
+    let city = test_city(Terrain::Plain);
+    let restored: City = serde_json::from_str(&serde_json::to_string(&city).unwrap()).unwrap();
+    assert_eq!(restored.grid, city.grid);
+}
+
+#[test]
+fn test_grid_save_is_validated() {
+    let saved = serde_json::to_value(CityGrid::new(&Terrain::Plain)).unwrap();
+    assert!(serde_json::from_value::<CityGrid>(saved.clone()).is_ok());
+
+    // Клеток меньше, чем нужно для радиуса
+    let mut short = saved.clone();
+    short["tiles"].as_array_mut().unwrap().pop();
+    assert!(serde_json::from_value::<CityGrid>(short).is_err());
+
+    let mut wider = saved.clone();
+    wider["radius"] = serde_json::json!(CITY_GRID_RADIUS + 1);
+    assert!(serde_json::from_value::<CityGrid>(wider).is_err());
+
+    let mut negative = saved;
+    negative["radius"] = serde_json::json!(-1);
+    negative["tiles"] = serde_json::json!([]);
+    assert!(serde_json::from_value::<CityGrid>(negative).is_err());
+}
+
+#[test]
+fn test_legacy_save_gets_generated_grid() {
+    let registry = BuildingRegistry::builtin();
+    let city = City::new(
+        "mountain".to_string(),
+        "Горный".to_string(),
+        "player1".to_string(),
+        Terrain::Mountain,
+        (4, 9),
+        &GameClock::new(),
+    );
+    let mut value = serde_json::to_value(&city).unwrap();
+    value.as_object_mut().unwrap().remove("grid");
+
+    // Сетка старого сохранения строится заново из местности и позиции города
+    let migrated: City = serde_json::from_value(value).unwrap();
+    assert_eq!(migrated.grid, CityGrid::generate(&Terrain::Mountain, grid_seed((4, 9))));
+    assert!(!migrated.valid_build_spots(&BuildingType::Mine, &registry).is_empty());
+}
+
+#[test]
+fn test_placement_follows_tile_terrain() {
+    let registry = BuildingRegistry::builtin();
+    let mut city = test_city(Terrain::Plain);
+    city.resources.set(ResourceType::Wood, 1000);
+    city.resources.set(ResourceType::Stone, 1000);
+    city.resources.set(ResourceType::Gold, 1000);
+    city.grid.set_terrain((2, 2), Terrain::Mountain);
+    city.grid.set_terrain((-2, 0), Terrain::Water);
+
+    // Шахта — только в горах
+    assert_eq!(
+        build(&mut city, BuildingType::Mine, (1, 1)),
+        Err(BuildingError::TileNotAllowed { building_type: BuildingType::Mine, terrain: Terrain::Plain })
+    );
+    assert!(city.valid_build_spots(&BuildingType::Mine, &registry).contains(&(2, 2)));
+    build(&mut city, BuildingType::Mine, (2, 2)).unwrap();
+    assert!(!city.valid_build_spots(&BuildingType::Mine, &registry).contains(&(2, 2)));
+
+    // Водяная мельница — только у воды, и ничего — на самой воде
+    assert_eq!(
+        build(&mut city, BuildingType::WaterMill, (1, 1)),
+        Err(BuildingError::AdjacentTerrainRequired {
+            building_type: BuildingType::WaterMill,
+            terrain: vec![Terrain::Water],
+        })
+    );
+    assert!(matches!(build(&mut city, BuildingType::Farm, (-2, 0)), Err(BuildingError::TileNotAllowed { .. })));
+    build(&mut city, BuildingType::WaterMill, (-1, 0)).unwrap();
+
+    // Площадь и клетки за пределами сетки заняты
+    assert_eq!(build(&mut city, BuildingType::Farm, CITY_CENTER), Err(BuildingError::PositionOccupied(CITY_CENTER)));
+    let outside = (CITY_GRID_RADIUS + 1, 0);
+    assert_eq!(build(&mut city, BuildingType::Farm, outside), Err(BuildingError::OutOfBounds(outside)));
+}
+
+#[test]
+fn test_roads_connect_to_the_center() {
+    let registry = BuildingRegistry::builtin();
+    let mut city = test_city(Terrain::Plain);
+    let stone = city.resources.get(&ResourceType::Stone);
+
+    // Дорога, не связанная с площадью, не дает доступа к центру
+    city.build_road((0, 3)).unwrap();
+    assert_eq!(city.resources.get(&ResourceType::Stone), stone - ROAD_COST);
+    assert!(!city.grid.is_connected((1, 3)));
+    assert_eq!(city.can_place(&BuildingType::Market, (1, 3), &registry), Err(BuildingError::RoadRequired));
+
+    city.build_road((0, 1)).unwrap();
+    city.build_road((0, 2)).unwrap();
+    assert_eq!(city.grid.connected_roads().len(), 4);
+    assert!(city.can_place(&BuildingType::Market, (1, 3), &registry).is_ok());
+    assert_eq!(city.build_road((0, 2)), Err(BuildingError::PositionOccupied((0, 2))));
+
+    // Без средней клетки дорога снова обрывается
+    assert!(city.remove_road((0, 2)));
+    assert!(!city.remove_road(CITY_CENTER));
+    assert!(!city.grid.is_connected((1, 3)));
+
+    city.grid.set_terrain((3, 3), Terrain::Water);
+    assert_eq!(city.build_road((3, 3)), Err(BuildingError::RoadNotAllowed(Terrain::Water)));
+}
+
+#[test]
+fn test_farm_next_to_water_mill_produces_more() {
+    let registry = BuildingRegistry::builtin();
+    let mut city = test_city(Terrain::Plain);
+    city.population.grow(20);
+    let farm = Building::new("farm".to_string(), "Ферма".to_string(), BuildingType::Farm, (1, 1));
+    city.buildings.insert(farm.id.clone(), farm);
+    city.update_resource_production(&registry, &ProductionModifiers::default());
+    let food = city.resources.get_production_rate(&ResourceType::Food);
+    assert_eq!(city.adjacency_bonus("farm", &registry), 0);
+
+    let mill = Building::new("mill".to_string(), "Мельница".to_string(), BuildingType::WaterMill, (1, 2));
+    city.buildings.insert(mill.id.clone(), mill);
+    assert_eq!(city.adjacency_bonus("farm", &registry), 25);
+
+    city.ledger.begin_turn(1);
+    city.update_resource_production(&registry, &ProductionModifiers::default());
+    assert!(city.resources.get_production_rate(&ResourceType::Food) > food);
+    assert!(city.ledger.current().unwrap().entries.iter().any(|entry| {
+        matches!(&entry.source, LedgerSource::Adjacency { building_id, .. } if building_id == "farm") && entry.amount > 0
+    }));
+}
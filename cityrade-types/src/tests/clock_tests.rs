@@ -0,0 +1,62 @@
+// cityrade-types/src/tests/clock_tests.rs
+use std::time::Duration;
+
+use chrono::{TimeZone, Utc};
+
+use crate::clock::{GameClock, TimeSource, CALENDAR_START_YEAR};
+use crate::quest::Achievement;
+
+#[test]
+fn test_calendar_follows_turns() {
+    let mut clock = GameClock::new();
+    let start = Utc.with_ymd_and_hms(CALENDAR_START_YEAR, 1, 1, 0, 0, 0).unwrap();
+
+    assert_eq!(clock.turn(), 0);
+    assert_eq!(clock.date(), start);
+
+    assert_eq!(clock.advance_turn(), 1);
+    assert_eq!(clock.date(), start + chrono::Duration::days(1));
+
+    let custom = GameClock::new().with_calendar(start, 6);
+    assert_eq!(custom.date_of_turn(4), start + chrono::Duration::days(1));
+}
+
+#[test]
+fn test_manual_source_fast_forward() {
+    let start = Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap();
+    let mut clock = GameClock::manual(start);
+
+    clock.advance_time(Duration::from_secs(90));
+
+    assert_eq!(clock.now(), start + chrono::Duration::seconds(90));
+    assert_eq!(clock.elapsed_since(start), Duration::from_secs(90));
+    assert_eq!(clock.turns_since(start, Duration::from_secs(30)), 3);
+    // Момент в будущем не дает пропущенных ходов
+    assert_eq!(clock.turns_since(clock.now() + chrono::Duration::hours(1), Duration::from_secs(30)), 0);
+}
+
+#[test]
+fn test_system_source_ignores_fast_forward() {
+    let mut clock = GameClock::new();
+    clock.advance_time(Duration::from_secs(3600));
+
+    assert_eq!(clock.source(), &TimeSource::System);
+    assert!(clock.now() <= Utc::now());
+}
+
+#[test]
+fn test_achievement_unlock_uses_game_clock() {
+    let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    let mut clock = GameClock::manual(start);
+    clock.advance_time(Duration::from_secs(3600));
+
+    let mut achievement = Achievement::new("a1", "Достижение", "Описание", 10).with_progress(0, 3);
+    assert!(!achievement.increment_progress(2, &clock));
+    assert!(achievement.update_progress(3, &clock));
+    assert_eq!(achievement.unlock_date, Some(start + chrono::Duration::hours(1)));
+
+    // Повторная разблокировка не меняет дату
+    clock.advance_time(Duration::from_secs(3600));
+    assert!(!achievement.unlock(&clock));
+    assert_eq!(achievement.unlock_date, Some(start + chrono::Duration::hours(1)));
+}
@@ -0,0 +1,143 @@
+// cityrade-types/src/tests/construction_tests.rs
+use crate::building::{BuildingError, BuildingType};
+use crate::building_registry::BuildingRegistry;
+use crate::city::Terrain;
+use crate::construction::{work_per_turn, CONSTRUCTION_REFUND_RATE};
+use crate::events::{BuildingConstructedEvent, EventPriority, EventResult, EventSystem};
+use crate::resources::ResourceType;
+use crate::rng;
+use crate::technology::TechnologyTree;
+use super::{test_city, with_resources};
+
+#[test]
+fn test_building_takes_build_time() {
+    let registry = BuildingRegistry::builtin();
+    let events = EventSystem::new();
+    let mut city = with_resources(test_city(Terrain::Plain), 10_000);
+
+    let farm_id = city
+        .add_building(BuildingType::Farm, "Ферма".to_string(), (1, 1), &registry, &TechnologyTree::new(), &mut rng::seeded(1))
+        .unwrap();
+    assert!(city.buildings.is_empty());
+    assert!(city.construction.get(&farm_id).is_some());
+
+    // Ферма строится 2 хода
+    assert!(city.update_construction(0, &events).is_empty());
+    assert_eq!(city.update_construction(0, &events), vec![farm_id.clone()]);
+    assert_eq!(city.buildings[&farm_id].level, 1);
+    assert!(city.construction.is_empty());
+
+    // Позиция строящегося здания занята
+    city.add_building(BuildingType::Wall, "Стена".to_string(), (2, 2), &registry, &TechnologyTree::new(), &mut rng::seeded(2))
+        .unwrap();
+    assert!(city
+        .add_building(BuildingType::Farm, "Ферма".to_string(), (2, 2), &registry, &TechnologyTree::new(), &mut rng::seeded(3))
+        .is_err());
+}
+
+#[test]
+fn test_parallel_slots() {
+    let registry = BuildingRegistry::builtin();
+    let events = EventSystem::new();
+    let mut city = with_resources(test_city(Terrain::Plain), 10_000);
+    let mut rng = rng::seeded(1);
+
+    let first = city
+        .add_building(BuildingType::Farm, "Ферма".to_string(), (1, 1), &registry, &TechnologyTree::new(), &mut rng)
+        .unwrap();
+    let second = city
+        .add_building(BuildingType::Farm, "Ферма".to_string(), (2, 1), &registry, &TechnologyTree::new(), &mut rng)
+        .unwrap();
+
+    // Одно место: вторая ферма ждет первую
+    city.update_construction(0, &events);
+    assert_eq!(city.update_construction(0, &events), vec![first]);
+    assert!(city.update_construction(0, &events).is_empty());
+    assert_eq!(city.update_construction(0, &events), vec![second]);
+
+    // Мастерская дает второе место
+    city.stats.construction_slots = 2;
+    let third = city
+        .add_building(BuildingType::Farm, "Ферма".to_string(), (3, 1), &registry, &TechnologyTree::new(), &mut rng)
+        .unwrap();
+    let fourth = city
+        .add_building(BuildingType::Farm, "Ферма".to_string(), (4, 1), &registry, &TechnologyTree::new(), &mut rng)
+        .unwrap();
+    city.update_construction(0, &events);
+    let mut done = city.update_construction(0, &events);
+    done.sort();
+    let mut expected = vec![third, fourth];
+    expected.sort();
+    assert_eq!(done, expected);
+}
+
+#[test]
+fn test_speed_bonus() {
+    assert_eq!(work_per_turn(0), 100);
+    assert_eq!(work_per_turn(25), 125);
+    assert_eq!(work_per_turn(-500), 10);
+
+    let registry = BuildingRegistry::builtin();
+    let events = EventSystem::new();
+    let mut city = with_resources(test_city(Terrain::Plain), 10_000);
+
+    // Стена строится 5 ходов; с бонусом +100% — 3 хода
+    let wall = city
+        .add_building(BuildingType::Wall, "Стена".to_string(), (1, 1), &registry, &TechnologyTree::new(), &mut rng::seeded(1))
+        .unwrap();
+    assert!(city.update_construction(100, &events).is_empty());
+    assert!(city.update_construction(100, &events).is_empty());
+    assert_eq!(city.update_construction(100, &events), vec![wall]);
+}
+
+#[test]
+fn test_cancel_refunds_remaining_work() {
+    let registry = BuildingRegistry::builtin();
+    let events = EventSystem::new();
+    let mut city = with_resources(test_city(Terrain::Plain), 10_000);
+    let stone = city.resources.get(&ResourceType::Stone);
+
+    // Стена: 300 камня, 5 ходов
+    let wall = city
+        .add_building(BuildingType::Wall, "Стена".to_string(), (1, 1), &registry, &TechnologyTree::new(), &mut rng::seeded(1))
+        .unwrap();
+    city.update_construction(0, &events);
+    city.update_construction(0, &events);
+
+    let refund = city.cancel_construction(&wall).unwrap();
+    let expected = (300.0 * 0.6 * CONSTRUCTION_REFUND_RATE) as u32;
+    assert!(refund.contains(&(ResourceType::Stone, expected)));
+    assert_eq!(city.resources.get(&ResourceType::Stone), stone - 300 + expected);
+    assert!(city.construction.is_empty());
+    assert_eq!(city.cancel_construction(&wall), Err(BuildingError::ConstructionNotFound));
+}
+
+#[test]
+fn test_constructed_event_can_cancel_job() {
+    let registry = BuildingRegistry::builtin();
+    let mut city = with_resources(test_city(Terrain::Plain), 10_000);
+    let before = city.resources.clone();
+    let farm = city
+        .add_building(BuildingType::Farm, "Ферма".to_string(), (1, 1), &registry, &TechnologyTree::new(), &mut rng::seeded(1))
+        .unwrap();
+    let cost = city.construction.get(&farm).unwrap().cost.clone();
+    assert!(!cost.is_empty());
+
+    let mut events = EventSystem::new();
+    events.register_handler::<BuildingConstructedEvent>("deny".to_string(), EventPriority::Normal, false, |event| {
+        assert_eq!((event.building_type.clone(), event.level), (BuildingType::Farm, 1));
+        EventResult::Cancel
+    });
+
+    city.update_construction(0, &events);
+    assert!(city.update_construction(0, &events).is_empty());
+    assert!(!city.buildings.contains_key(&farm));
+    assert!(city.construction.is_empty());
+
+    // Работы завершены, но отмена событием возвращает долю всей стоимости
+    for (resource, amount) in cost {
+        let refund = (amount as f32 * CONSTRUCTION_REFUND_RATE) as u32;
+        assert!(refund > 0);
+        assert_eq!(city.resources.get(&resource), before.get(&resource) - amount + refund);
+    }
+}
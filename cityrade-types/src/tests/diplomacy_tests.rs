@@ -1,4 +1,7 @@
-use crate::diplomacy::{DiplomacyManager, DiplomaticAction, DiplomaticRelation, RelationType};
+use crate::events::EventSystem;
+use crate::diplomacy::{
+    BORDER_TENSION_FLOOR, DiplomacyManager, DiplomaticAction, DiplomaticRelation, RelationType, border_tension,
+};
 
 #[test]
 fn test_diplomatic_relation_creation() {
@@ -108,7 +111,7 @@ fn test_diplomacy_manager() {
     assert!(manager.get_relation("faction1", "faction2").is_none());
     
     // Изменяем репутацию между фракциями
-    manager.change_reputation("faction1", "faction2", 50);
+    manager.change_reputation("faction1", "faction2", 50, &EventSystem::new());
     
     // Проверяем, что отношения созданы и обновлены
     let relation = manager.get_relation("faction1", "faction2");
@@ -149,4 +152,28 @@ fn test_set_relation() {
     // Проверяем коммутативность операции установки отношений
     let stored_relation_reversed = manager.get_relation("faction2", "faction1").unwrap();
     assert_eq!(stored_relation_reversed.relation_type, RelationType::Friendly);
-} 
\ No newline at end of file
+} 
+
+#[test]
+fn test_border_tension() {
+    assert_eq!(border_tension(0), 0);
+    assert_eq!(border_tension(4), 1);
+    assert_eq!(border_tension(5), 2);
+    assert_eq!(border_tension(100), 3);
+
+    let events = EventSystem::new();
+    let mut manager = DiplomacyManager::new();
+    assert_eq!(manager.apply_border_tension("faction1", "faction2", 12, &events), 3);
+    assert_eq!(manager.get_relation("faction1", "faction2").unwrap().reputation, -3);
+
+    // Репутация не опускается ниже порога
+    manager.change_reputation("faction1", "faction2", BORDER_TENSION_FLOOR + 4, &events);
+    assert_eq!(manager.apply_border_tension("faction2", "faction1", 12, &events), 1);
+    assert_eq!(manager.get_relation("faction1", "faction2").unwrap().reputation, BORDER_TENSION_FLOOR);
+    assert_eq!(manager.apply_border_tension("faction1", "faction2", 12, &events), 0);
+
+    // Союзники границу не замечают
+    manager.change_reputation("faction1", "faction3", 80, &events);
+    assert_eq!(manager.apply_border_tension("faction1", "faction3", 12, &events), 0);
+    assert_eq!(manager.get_relation("faction1", "faction3").unwrap().reputation, 80);
+}
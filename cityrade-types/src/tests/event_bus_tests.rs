@@ -0,0 +1,137 @@
+// cityrade-types/src/tests/event_bus_tests.rs
+use std::sync::{Arc, Mutex};
+
+use futures::executor::block_on;
+
+use crate::event_bus::{AsyncEventBus, HandlerOptions, OverflowPolicy};
+use crate::events::{EventPriority, EventResult, EventSystem, PlayerJoinEvent, PlayerLeaveEvent};
+
+fn join_event(player_id: &str) -> PlayerJoinEvent {
+    PlayerJoinEvent::new(player_id.to_string(), "world1".to_string())
+}
+
+fn recording_bus(options: HandlerOptions) -> (AsyncEventBus, Arc<Mutex<Vec<String>>>) {
+    let mut bus = AsyncEventBus::new();
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let log = received.clone();
+    bus.register_handler::<PlayerJoinEvent, _, _>("join".to_string(), options, move |event| {
+        let log = log.clone();
+        async move {
+            log.lock().unwrap().push(event.player_id.clone());
+        }
+    });
+    (bus, received)
+}
+
+#[test]
+fn test_events_are_delivered_at_end_of_tick() {
+    let (mut bus, received) = recording_bus(HandlerOptions::default());
+    bus.publish(join_event("a"));
+    bus.publish(join_event("b"));
+    // Событие без обработчиков просто отбрасывается
+    bus.publish(PlayerLeaveEvent::new("a".to_string(), "world1".to_string()));
+
+    let report = block_on(bus.flush());
+    assert_eq!(report.delivered, 0);
+    assert_eq!(bus.pending_count(), 3);
+
+    bus.end_tick(0);
+    let report = block_on(bus.flush());
+
+    assert_eq!(report.delivered, 2);
+    assert_eq!(*received.lock().unwrap(), vec!["a", "b"]);
+    assert_eq!(bus.pending_count(), 0);
+}
+
+#[test]
+fn test_delayed_event_fires_after_turns() {
+    let (mut bus, received) = recording_bus(HandlerOptions::default());
+    bus.publish_delayed(join_event("late"), 3);
+    assert_eq!(bus.delayed_count(), 1);
+
+    for turn in 0..3 {
+        bus.end_tick(turn);
+        block_on(bus.flush());
+        assert!(received.lock().unwrap().is_empty(), "ход {}", turn);
+    }
+
+    bus.end_tick(3);
+    block_on(bus.flush());
+
+    assert_eq!(*received.lock().unwrap(), vec!["late"]);
+    assert_eq!(bus.delayed_count(), 0);
+}
+
+#[test]
+fn test_backpressure_limits_delivery_per_flush() {
+    let options = HandlerOptions::default().with_capacity(4).with_max_per_flush(2);
+    let (mut bus, received) = recording_bus(options);
+    for id in ["1", "2", "3", "4", "5", "6"] {
+        bus.publish(join_event(id));
+    }
+    bus.end_tick(0);
+
+    // Очередь вмещает 4 события: два самых старых потеряны
+    assert_eq!(bus.queue_len("join"), Some(4));
+    assert_eq!(bus.dropped_count("join"), Some(2));
+
+    let report = block_on(bus.flush());
+    assert_eq!(report.delivered, 2);
+    assert_eq!(report.backlog, 2);
+
+    block_on(bus.flush());
+    assert_eq!(*received.lock().unwrap(), vec!["3", "4", "5", "6"]);
+}
+
+#[test]
+fn test_drop_newest_keeps_oldest_events() {
+    let options = HandlerOptions::default()
+        .with_capacity(2)
+        .with_overflow(OverflowPolicy::DropNewest);
+    let (mut bus, received) = recording_bus(options);
+    for id in ["1", "2", "3"] {
+        bus.publish(join_event(id));
+    }
+    bus.end_tick(0);
+    block_on(bus.flush());
+
+    assert_eq!(*received.lock().unwrap(), vec!["1", "2"]);
+    assert_eq!(bus.dropped_count("join"), Some(1));
+}
+
+#[test]
+fn test_unregister_drops_handler_queue() {
+    let (mut bus, received) = recording_bus(HandlerOptions::default());
+    bus.publish(join_event("a"));
+    bus.end_tick(0);
+
+    bus.unregister_handler("join");
+    block_on(bus.flush());
+
+    assert!(received.lock().unwrap().is_empty());
+    assert_eq!(bus.queue_len("join"), None);
+}
+
+#[test]
+fn test_forwarded_events_skip_cancelled() {
+    let (mut bus, received) = recording_bus(HandlerOptions::default());
+    let mut events = EventSystem::new();
+    bus.forward_all(&mut events);
+    events.register_handler::<PlayerJoinEvent>("ban".to_string(), EventPriority::Normal, false, |event| {
+        if event.player_id == "banned" {
+            EventResult::Cancel
+        } else {
+            EventResult::Continue
+        }
+    });
+
+    assert!(events.call_event(&mut join_event("a")));
+    assert!(!events.call_event(&mut join_event("banned")));
+    bus.publisher().publish(join_event("b"));
+    assert_eq!(bus.pending_count(), 2);
+
+    bus.end_tick(0);
+    block_on(bus.flush());
+
+    assert_eq!(*received.lock().unwrap(), vec!["a", "b"]);
+}
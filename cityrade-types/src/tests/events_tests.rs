@@ -0,0 +1,321 @@
+// cityrade-types/src/tests/events_tests.rs
+use std::sync::{Arc, Mutex};
+
+use crate::building::BuildingType;
+use crate::building_registry::BuildingRegistry;
+use crate::city::Terrain;
+use crate::clock::GameClock;
+use crate::diplomacy::{DiplomacyManager, RelationType};
+use crate::events::{
+    BuildingConstructedEvent, BuildingUpgradedEvent, Event, EventPriority, EventResult, EventSystem,
+    MarketTradeEvent, PlayerJoinEvent, PlayerLeaveEvent, QuestCompletedEvent, RelationChangedEvent,
+    ResearchCompletedEvent,
+};
+use crate::market::{TradeDirection, TradeManager};
+use crate::quest::{Quest, QuestManager, QuestObjective, QuestStatus};
+use crate::resources::ResourceType;
+use crate::technology::{TechnologyTree, TechnologyType};
+use super::test_city;
+
+fn join_event() -> PlayerJoinEvent {
+    PlayerJoinEvent::new("player1".to_string(), "world1".to_string())
+}
+
+fn recorder() -> Arc<Mutex<Vec<&'static str>>> {
+    Arc::new(Mutex::new(Vec::new()))
+}
+
+#[test]
+fn test_handler_receives_concrete_event() {
+    let mut system = EventSystem::new();
+    let calls = recorder();
+    let log = calls.clone();
+    system.register_handler::<PlayerJoinEvent>(
+        "join".to_string(),
+        EventPriority::Normal,
+        false,
+        move |event| {
+            assert_eq!(event.player_id, "player1");
+            log.lock().unwrap().push("join");
+            EventResult::Continue
+        },
+    );
+
+    assert!(system.call_event(&mut join_event()));
+    // Обработчик другого типа события не вызывается
+    assert!(system.call_event(&mut PlayerLeaveEvent::new("player1".to_string(), "world1".to_string())));
+
+    assert_eq!(*calls.lock().unwrap(), vec!["join"]);
+}
+
+#[test]
+fn test_handlers_run_in_priority_order() {
+    let mut system = EventSystem::new();
+    let calls = recorder();
+    for (id, priority) in [
+        ("monitor", EventPriority::Monitor),
+        ("high", EventPriority::High),
+        ("lowest", EventPriority::Lowest),
+        ("normal", EventPriority::Normal),
+        ("highest", EventPriority::Highest),
+        ("low", EventPriority::Low),
+    ] {
+        let log = calls.clone();
+        system.register_handler::<PlayerJoinEvent>(id.to_string(), priority, false, move |_| {
+            log.lock().unwrap().push(id);
+            EventResult::Continue
+        });
+    }
+
+    system.call_event(&mut join_event());
+
+    assert_eq!(
+        *calls.lock().unwrap(),
+        vec!["lowest", "low", "normal", "high", "highest", "monitor"]
+    );
+}
+
+#[test]
+fn test_cancel_is_written_back_to_event() {
+    let mut system = EventSystem::new();
+    system.register_handler::<PlayerJoinEvent>("by_result".to_string(), EventPriority::Normal, false, |_| {
+        EventResult::Cancel
+    });
+
+    let mut event = join_event();
+    assert!(!system.call_event(&mut event));
+    assert!(event.is_cancelled());
+
+    let mut system = EventSystem::new();
+    system.register_handler::<BuildingConstructedEvent>(
+        "by_method".to_string(),
+        EventPriority::Normal,
+        false,
+        |event| {
+            event.cancel();
+            EventResult::Continue
+        },
+    );
+
+    let mut event = BuildingConstructedEvent::new(
+        "city1".to_string(),
+        "building1".to_string(),
+        BuildingType::Farm,
+        1,
+        "player1".to_string(),
+        (0, 0),
+    );
+    assert!(!system.call_event(&mut event));
+    assert!(event.is_cancelled());
+}
+
+#[test]
+fn test_ignore_cancelled_skips_handler() {
+    let mut system = EventSystem::new();
+    let calls = recorder();
+    system.register_handler::<PlayerJoinEvent>("cancel".to_string(), EventPriority::Low, false, |_| {
+        EventResult::Cancel
+    });
+    let log = calls.clone();
+    system.register_handler::<PlayerJoinEvent>("skipped".to_string(), EventPriority::Normal, true, move |_| {
+        log.lock().unwrap().push("skipped");
+        EventResult::Continue
+    });
+    let log = calls.clone();
+    system.register_handler::<PlayerJoinEvent>("uncancel".to_string(), EventPriority::High, false, move |event| {
+        log.lock().unwrap().push("uncancel");
+        event.set_cancelled(false);
+        EventResult::Continue
+    });
+
+    assert!(system.call_event(&mut join_event()));
+    assert_eq!(*calls.lock().unwrap(), vec!["uncancel"]);
+}
+
+#[test]
+fn test_monitor_cannot_change_outcome() {
+    let mut system = EventSystem::new();
+    system.register_handler::<PlayerJoinEvent>("monitor_cancel".to_string(), EventPriority::Monitor, false, |event| {
+        event.cancel();
+        EventResult::Cancel
+    });
+
+    let mut event = join_event();
+    assert!(system.call_event(&mut event));
+    assert!(!event.is_cancelled());
+
+    system.register_handler::<PlayerJoinEvent>("cancel".to_string(), EventPriority::Normal, false, |_| {
+        EventResult::Cancel
+    });
+    system.register_handler::<PlayerJoinEvent>("monitor_uncancel".to_string(), EventPriority::Monitor, false, |event| {
+        event.set_cancelled(false);
+        EventResult::Continue
+    });
+
+    let mut event = join_event();
+    assert!(!system.call_event(&mut event));
+    assert!(event.is_cancelled());
+}
+
+#[test]
+fn test_non_cancellable_event_ignores_cancel() {
+    let mut system = EventSystem::new();
+    system.register_handler::<PlayerLeaveEvent>("cancel".to_string(), EventPriority::Normal, false, |_| {
+        EventResult::Cancel
+    });
+
+    let mut event = PlayerLeaveEvent::new("player1".to_string(), "world1".to_string());
+    assert!(system.call_event(&mut event));
+    assert!(!event.is_cancelled());
+}
+
+#[test]
+fn test_unregister_handler() {
+    let mut system = EventSystem::new();
+    system.register_handler::<PlayerJoinEvent>("cancel".to_string(), EventPriority::Normal, false, |_| {
+        EventResult::Cancel
+    });
+    assert!(system.has_handlers::<PlayerJoinEvent>());
+
+    system.unregister_handler("cancel");
+
+    assert!(!system.has_handlers::<PlayerJoinEvent>());
+    assert!(system.call_event(&mut join_event()));
+}
+
+fn cancel_all<E: Event + 'static>(system: &mut EventSystem) {
+    system.register_handler::<E>("cancel_all".to_string(), EventPriority::Normal, false, |_| {
+        EventResult::Cancel
+    });
+}
+
+#[test]
+fn test_building_upgrade_fires_on_completion() {
+    let mut city = test_city(Terrain::Plain);
+    city.add_resources(&ResourceType::Gold, 1000);
+    city.add_resources(&ResourceType::Wood, 1000);
+    city.add_resources(&ResourceType::Stone, 1000);
+    let registry = BuildingRegistry::builtin();
+    let building_id = city
+        .add_building(BuildingType::Farm, "Ферма".to_string(), (1, 1), &registry, &TechnologyTree::new(), &mut crate::rng::seeded(1))
+        .unwrap();
+    while city.update_construction(0, &EventSystem::new()).is_empty() {}
+
+    let mut system = EventSystem::new();
+    let seen = recorder();
+    let log = seen.clone();
+    system.register_handler::<BuildingUpgradedEvent>("check".to_string(), EventPriority::Low, false, move |event| {
+        assert_eq!((event.old_level, event.new_level), (1, 2));
+        log.lock().unwrap().push("upgrade");
+        EventResult::Continue
+    });
+    cancel_all::<BuildingUpgradedEvent>(&mut system);
+
+    // Постановка в очередь события не вызывает
+    city.upgrade_building(&building_id, &registry, &TechnologyTree::new()).unwrap();
+    let gold_paid = city.resources.get(&ResourceType::Gold);
+    assert!(seen.lock().unwrap().is_empty());
+
+    // Отмененное улучшение не меняет уровень и возвращает часть стоимости
+    while city.construction.get(&building_id).is_some() {
+        assert!(city.update_construction(0, &system).is_empty());
+    }
+    assert_eq!(*seen.lock().unwrap(), vec!["upgrade"]);
+    assert_eq!(city.buildings[&building_id].level, 1);
+    assert!(city.resources.get(&ResourceType::Gold) > gold_paid);
+
+    city.upgrade_building(&building_id, &registry, &TechnologyTree::new()).unwrap();
+    while city.update_construction(0, &EventSystem::new()).is_empty() {}
+    assert_eq!(city.buildings[&building_id].level, 2);
+}
+
+#[test]
+fn test_market_trade_event() {
+    let mut trade = TradeManager::new();
+    trade.create_city_market("city1");
+    let quantity_before = trade.get_city_market("city1").unwrap().get_market_item(&ResourceType::Wood).unwrap().quantity;
+
+    let mut system = EventSystem::new();
+    cancel_all::<MarketTradeEvent>(&mut system);
+    assert!(trade.buy("city1", &ResourceType::Wood, 10, &system).is_none());
+    assert_eq!(
+        trade.get_city_market("city1").unwrap().get_market_item(&ResourceType::Wood).unwrap().quantity,
+        quantity_before
+    );
+
+    let mut system = EventSystem::new();
+    let trades = Arc::new(Mutex::new(Vec::new()));
+    let log = trades.clone();
+    system.register_handler::<MarketTradeEvent>("log".to_string(), EventPriority::Monitor, false, move |event| {
+        log.lock().unwrap().push((event.city_id.clone(), event.direction, event.amount));
+        EventResult::Continue
+    });
+    let price = trade.sell("city1", &ResourceType::Wood, 5, &system).unwrap();
+
+    assert!(price > 0.0);
+    assert_eq!(*trades.lock().unwrap(), vec![("city1".to_string(), TradeDirection::Sell, 5)]);
+}
+
+#[test]
+fn test_relation_change_can_be_cancelled() {
+    let mut manager = DiplomacyManager::new();
+    let mut system = EventSystem::new();
+    cancel_all::<RelationChangedEvent>(&mut system);
+
+    assert!(!manager.change_reputation("a", "b", 50, &system));
+    assert!(manager.get_relation("a", "b").is_none());
+
+    let mut system = EventSystem::new();
+    system.register_handler::<RelationChangedEvent>("check".to_string(), EventPriority::Normal, false, |event| {
+        assert_eq!(event.old_relation, RelationType::Neutral);
+        assert_eq!(event.new_relation, RelationType::Friendly);
+        EventResult::Continue
+    });
+    assert!(manager.change_reputation("b", "a", 50, &system));
+    assert_eq!(manager.get_relation("a", "b").unwrap().reputation, 50);
+}
+
+#[test]
+fn test_quest_completion_can_be_cancelled() {
+    let clock = GameClock::new();
+    let mut manager = QuestManager::new().with_owner("player1".to_string());
+    let quest = Quest::new("q1", "Квест", "Описание", &clock)
+        .with_objective(QuestObjective::new("o1".to_string(), "Цель".to_string(), 1));
+    manager.add_quest(quest);
+    manager.start_quest("q1", &clock).unwrap();
+    manager.update_objective("q1", "o1", 1, &clock).unwrap();
+
+    let mut system = EventSystem::new();
+    cancel_all::<QuestCompletedEvent>(&mut system);
+    assert!(manager.complete_quest("q1", &clock, &system).is_err());
+    assert_eq!(manager.get_quest("q1").unwrap().status, QuestStatus::InProgress);
+
+    let mut system = EventSystem::new();
+    system.register_handler::<QuestCompletedEvent>("check".to_string(), EventPriority::Normal, false, |event| {
+        assert_eq!(event.player_id, "player1");
+        EventResult::Continue
+    });
+    assert!(manager.complete_quest("q1", &clock, &system).is_ok());
+    assert_eq!(manager.get_quest("q1").unwrap().status, QuestStatus::Completed);
+}
+
+#[test]
+fn test_research_completed_event() {
+    let mut tree = TechnologyTree::new().with_owner("player1".to_string());
+    tree.start_research(TechnologyType::Agriculture).unwrap();
+    tree.set_research_rate(1000);
+
+    let mut system = EventSystem::new();
+    let completed = Arc::new(Mutex::new(Vec::new()));
+    let log = completed.clone();
+    system.register_handler::<ResearchCompletedEvent>("log".to_string(), EventPriority::Normal, false, move |event| {
+        log.lock().unwrap().push((event.player_id.clone(), event.technology.clone()));
+        EventResult::Continue
+    });
+    tree.update_research(&system);
+
+    assert_eq!(
+        *completed.lock().unwrap(),
+        vec![("player1".to_string(), TechnologyType::Agriculture)]
+    );
+}
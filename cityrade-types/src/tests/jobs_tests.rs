@@ -0,0 +1,89 @@
+// cityrade-types/src/tests/jobs_tests.rs
+use std::collections::BTreeMap;
+
+use crate::building::{Building, BuildingType};
+use crate::building_registry::BuildingRegistry;
+use crate::city::{City, Terrain};
+use crate::jobs::{assign_jobs, unemployed, JobOverrides};
+use crate::population::{Population, PopulationClass};
+use crate::production::ProductionModifiers;
+use crate::resources::ResourceType;
+use super::test_city;
+
+fn slots(class: PopulationClass, count: u32) -> BTreeMap<PopulationClass, u32> {
+    BTreeMap::from([(class, count)])
+}
+
+#[test]
+fn test_assign_jobs_fills_in_order() {
+    // 5 крестьян, 3 рабочих, 1 торговец, 1 солдат
+    let population = Population::with_total(10);
+    let buildings = vec![
+        ("farm".to_string(), BuildingType::Farm, slots(PopulationClass::Peasant, 4)),
+        ("mill".to_string(), BuildingType::LumberMill, slots(PopulationClass::Peasant, 4)),
+        ("lab".to_string(), BuildingType::Laboratory, slots(PopulationClass::Scholar, 1)),
+    ];
+
+    let assignments = assign_jobs(&buildings, &population, &JobOverrides::default());
+
+    assert_eq!(assignments[0].staffing(), 1.0);
+    assert_eq!(assignments[1].staffing(), 0.25);
+    assert_eq!(assignments[1].vacancies(), slots(PopulationClass::Peasant, 3));
+    assert_eq!(assignments[2].staffing(), 0.0);
+
+    let idle = unemployed(&population, &assignments);
+    assert_eq!(idle.get(&PopulationClass::Peasant), None);
+    assert_eq!(idle.get(&PopulationClass::Worker), Some(&3));
+}
+
+#[test]
+fn test_overrides_are_served_first() {
+    let population = Population::with_total(10);
+    let buildings = vec![
+        ("farm".to_string(), BuildingType::Farm, slots(PopulationClass::Peasant, 4)),
+        ("mill".to_string(), BuildingType::LumberMill, slots(PopulationClass::Peasant, 4)),
+    ];
+    let mut overrides = JobOverrides::default();
+    // Лесопилке — все места, ферме — что останется
+    overrides.set("mill", PopulationClass::Peasant, 10);
+
+    let assignments = assign_jobs(&buildings, &population, &overrides);
+    assert_eq!(assignments[1].assigned, slots(PopulationClass::Peasant, 4));
+    assert_eq!(assignments[0].assigned, slots(PopulationClass::Peasant, 1));
+
+    // Нулевая настройка снимает жителей со здания
+    overrides.set("mill", PopulationClass::Peasant, 0);
+    let assignments = assign_jobs(&buildings, &population, &overrides);
+    assert_eq!(assignments[0].staffing(), 1.0);
+    assert_eq!(assignments[1].staffing(), 0.0);
+    assert_eq!(unemployed(&population, &assignments).get(&PopulationClass::Peasant), Some(&1));
+}
+
+#[test]
+fn test_production_scales_with_staffing() {
+    let registry = BuildingRegistry::builtin();
+    let mut city = test_city(Terrain::Forest);
+    let mut mine = Building::new("mine".to_string(), "Шахта".to_string(), BuildingType::Mine, (1, 1));
+    mine.level = 2;
+    city.buildings.insert(mine.id.clone(), mine);
+    let iron = |city: &City| city.resources.get_production_rate(&ResourceType::Iron);
+
+    // Шахте 2 уровня нужно 4 рабочих, в городе их 3
+    city.update_resource_production(&registry, &ProductionModifiers::default());
+    assert_eq!(city.staffing("mine"), 0.75);
+    let partial = iron(&city);
+
+    city.population.grow(20);
+    city.update_resource_production(&registry, &ProductionModifiers::default());
+    assert_eq!(city.staffing("mine"), 1.0);
+    assert!(iron(&city) > partial);
+
+    city.set_job_override("mine", PopulationClass::Worker, 0).unwrap();
+    city.update_resource_production(&registry, &ProductionModifiers::default());
+    assert_eq!(iron(&city), 0);
+    assert!(city.unemployment().contains_key(&PopulationClass::Worker));
+    assert!(city.set_job_override("missing", PopulationClass::Worker, 1).is_err());
+
+    city.remove_building("mine").unwrap();
+    assert!(city.job_overrides.buildings.is_empty());
+}
@@ -0,0 +1,140 @@
+// cityrade-types/src/tests/ledger_tests.rs
+use std::collections::BTreeMap;
+
+use crate::building::{Building, BuildingType};
+use crate::building_registry::BuildingRegistry;
+use crate::city::Terrain;
+use crate::clock::GameClock;
+use crate::events::EventSystem;
+use crate::ledger::{EconomicLedger, LedgerSource, TurnLedger};
+use crate::production::ProductionModifiers;
+use crate::resources::ResourceType;
+use crate::rng;
+use crate::technology::TechnologyTree;
+use super::test_city;
+
+#[test]
+fn test_ledger_keeps_last_turns() {
+    let mut ledger = EconomicLedger::new(3);
+    for turn in 0..5 {
+        ledger.begin_turn(turn);
+        ledger.record(LedgerSource::BaseProduction, ResourceType::Gold, 5);
+        ledger.record(LedgerSource::Trade, ResourceType::Gold, -2);
+        // Нулевые изменения не записываются
+        ledger.record(LedgerSource::Terrain, ResourceType::Gold, 0);
+    }
+    // Повторное начало того же хода не создает новую запись
+    ledger.begin_turn(4);
+
+    let turns: Vec<u64> = ledger.last(10).iter().map(|turn| turn.turn).collect();
+    assert_eq!(turns, vec![2, 3, 4]);
+    assert_eq!(ledger.last(2).len(), 2);
+    assert!(ledger.turn(1).is_none());
+
+    let current = ledger.current().unwrap();
+    assert_eq!(current.entries.len(), 2);
+    assert_eq!(current.income(&ResourceType::Gold), 5);
+    assert_eq!(current.expenses(&ResourceType::Gold), 2);
+    assert_eq!(ledger.net(&ResourceType::Gold, 2), 6);
+
+    let statement = ledger.income_statement(3);
+    assert_eq!(statement["base_production"][&ResourceType::Gold], 15);
+    assert_eq!(statement["trade"][&ResourceType::Gold], -6);
+}
+
+#[test]
+fn test_ledger_exports_json_and_csv() {
+    let mut ledger = EconomicLedger::default();
+    ledger.begin_turn(7);
+    ledger.record(
+        LedgerSource::Upkeep {
+            building_id: "barracks".to_string(),
+            building_type: BuildingType::Barracks,
+        },
+        ResourceType::Gold,
+        -12,
+    );
+    ledger.record(LedgerSource::Event("flood, north".to_string()), ResourceType::Food, -30);
+
+    assert_eq!(
+        ledger.to_csv(1),
+        "turn,category,detail,resource,amount\n\
+         7,upkeep,barracks,Gold,-12\n\
+         7,event,\"flood, north\",Food,-30\n"
+    );
+
+    let turns: Vec<TurnLedger> = serde_json::from_str(&ledger.to_json(1).unwrap()).unwrap();
+    assert_eq!(turns.len(), 1);
+    assert_eq!(turns[0].turn, 7);
+    assert_eq!(turns[0].net(&ResourceType::Gold), -12);
+}
+
+#[test]
+fn test_city_ledger_explains_stock_change() {
+    let registry = BuildingRegistry::builtin();
+    let clock = GameClock::new();
+    let mut city = test_city(Terrain::Plain);
+    for (index, building_type) in [BuildingType::Farm, BuildingType::Barracks].into_iter().enumerate() {
+        let building = Building::new(format!("b{}", index), "Здание".to_string(), building_type, (index as i32, 0));
+        city.buildings.insert(building.id.clone(), building);
+    }
+    let modifiers = ProductionModifiers {
+        faction: BTreeMap::from([(ResourceType::Food, 25)]),
+        technology: BTreeMap::from([(ResourceType::Food, 20)]),
+        ..ProductionModifiers::default()
+    };
+
+    let food_before = city.resources.get(&ResourceType::Food) as i64;
+    let gold_before = city.resources.get(&ResourceType::Gold) as i64;
+    city.update(&registry, &modifiers, &clock, &mut rng::seeded(1), &EventSystem::new());
+
+    let turn = city.ledger.current().unwrap();
+    assert_eq!(turn.turn, clock.turn());
+    assert_eq!(turn.net(&ResourceType::Food), city.resources.get(&ResourceType::Food) as i64 - food_before);
+    assert_eq!(turn.net(&ResourceType::Gold), city.resources.get(&ResourceType::Gold) as i64 - gold_before);
+
+    let categories: Vec<&str> = turn
+        .entries
+        .iter()
+        .filter(|entry| entry.resource == ResourceType::Food)
+        .map(|entry| entry.source.category())
+        .collect();
+    for category in ["upkeep", "base_production", "building", "terrain", "faction_bonus", "technology_bonus", "spoilage"] {
+        assert!(categories.contains(&category), "нет записи {}", category);
+    }
+}
+
+#[test]
+fn test_construction_is_recorded() {
+    let registry = BuildingRegistry::builtin();
+    let mut city = test_city(Terrain::Plain);
+
+    let farm_id = city
+        .add_building(
+            BuildingType::Farm,
+            "Ферма".to_string(),
+            (1, 1),
+            &registry,
+            &TechnologyTree::new(),
+            &mut rng::seeded(1),
+        )
+        .unwrap();
+    let refund = city.cancel_construction(&farm_id).unwrap();
+
+    let turn = city.ledger.current().unwrap();
+    let cost: i64 = registry
+        .get(&BuildingType::Farm)
+        .unwrap()
+        .build_cost()
+        .iter()
+        .filter(|(resource, _)| *resource == ResourceType::Wood)
+        .map(|(_, amount)| *amount as i64)
+        .sum();
+    let refunded: i64 = refund
+        .iter()
+        .filter(|(resource, _)| *resource == ResourceType::Wood)
+        .map(|(_, amount)| *amount as i64)
+        .sum();
+    assert_eq!(turn.expenses(&ResourceType::Wood), cost);
+    assert_eq!(turn.income(&ResourceType::Wood), refunded);
+}
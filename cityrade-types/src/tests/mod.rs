@@ -1,5 +1,39 @@
+mod building_registry_tests;
+mod building_tests;
+mod city_grid_tests;
+mod clock_tests;
+mod construction_tests;
 mod diplomacy_tests;
+mod event_bus_tests;
+mod events_tests;
 mod faction_tests;
+mod jobs_tests;
+mod ledger_tests;
+mod policies_tests;
+mod population_tests;
+mod production_tests;
+mod storage_tests;
+mod taxation_tests;
+mod territory_tests;
+mod upkeep_tests;
+mod world_tests;
 
 // Другие существующие модули тестов
 // ...
+
+use crate::city::{City, Terrain};
+use crate::clock::GameClock;
+use crate::resources::ResourceType;
+
+/// Город игрока player1 в (0, 0) для тестов
+pub(crate) fn test_city(terrain: Terrain) -> City {
+    City::new("city1".to_string(), "Город".to_string(), "player1".to_string(), terrain, (0, 0), &GameClock::new())
+}
+
+/// Добавляет городу `amount` каждого строительного ресурса: золота, дерева, камня и железа
+pub(crate) fn with_resources(mut city: City, amount: u32) -> City {
+    for resource in [ResourceType::Gold, ResourceType::Wood, ResourceType::Stone, ResourceType::Iron] {
+        city.add_resources(&resource, amount);
+    }
+    city
+}
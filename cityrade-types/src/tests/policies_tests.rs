@@ -0,0 +1,165 @@
+// cityrade-types/src/tests/policies_tests.rs
+use std::sync::{Arc, Mutex};
+
+use crate::building::{Building, BuildingType};
+use crate::building_registry::BuildingRegistry;
+use crate::city::Terrain;
+use crate::clock::GameClock;
+use crate::events::{EventPriority, EventResult, EventSystem, PolicyLapsedEvent};
+use crate::ledger::LedgerSource;
+use crate::policies::{CityPolicies, PolicyError, PolicyType, POLICY_SWITCH_COOLDOWN};
+use crate::production::ProductionModifiers;
+use crate::resources::ResourceType;
+use crate::rng;
+use crate::technology::{TechnologyTree, TechnologyType};
+use super::test_city;
+
+fn governed() -> TechnologyTree {
+    let mut technologies = TechnologyTree::new();
+    technologies.completed_technologies.insert(TechnologyType::Administration);
+    technologies.completed_technologies.insert(TechnologyType::Governance);
+    technologies
+}
+
+#[test]
+fn test_enact_requires_technology_and_respects_conflicts() {
+    let mut policies = CityPolicies::new();
+    let mut technologies = TechnologyTree::new();
+
+    assert_eq!(
+        policies.enact(PolicyType::Festival, &technologies, 0),
+        Err(PolicyError::MissingTechnology { policy: PolicyType::Festival, technology: TechnologyType::Governance })
+    );
+    technologies.completed_technologies.insert(TechnologyType::Governance);
+    policies.enact(PolicyType::Festival, &technologies, 0).unwrap();
+    assert_eq!(
+        policies.enact(PolicyType::Festival, &technologies, 0),
+        Err(PolicyError::AlreadyActive(PolicyType::Festival))
+    );
+
+    // Военное положение требует «Администрирования» и несовместимо с празднествами
+    assert!(matches!(
+        policies.enact(PolicyType::MartialLaw, &technologies, 0),
+        Err(PolicyError::MissingTechnology { .. })
+    ));
+    technologies.completed_technologies.insert(TechnologyType::Administration);
+    assert_eq!(
+        policies.enact(PolicyType::MartialLaw, &technologies, 0),
+        Err(PolicyError::Conflict { policy: PolicyType::MartialLaw, active: PolicyType::Festival })
+    );
+    policies.enact(PolicyType::FreeTrade, &technologies, 0).unwrap();
+
+    let active: Vec<_> = policies.active().copied().collect();
+    assert_eq!(active, vec![PolicyType::Festival, PolicyType::FreeTrade]);
+}
+
+#[test]
+fn test_switching_has_cooldown() {
+    let mut policies = CityPolicies::new();
+    let technologies = governed();
+
+    policies.enact(PolicyType::MartialLaw, &technologies, 10).unwrap();
+    assert_eq!(policies.cooldown_remaining(&PolicyType::MartialLaw, 12), 3);
+    assert_eq!(
+        policies.revoke(PolicyType::MartialLaw, 12),
+        Err(PolicyError::Cooldown { policy: PolicyType::MartialLaw, remaining: 3 })
+    );
+    policies.revoke(PolicyType::MartialLaw, 10 + POLICY_SWITCH_COOLDOWN).unwrap();
+    assert!(!policies.is_active(&PolicyType::MartialLaw));
+
+    // Вернуть политику сразу после отмены тоже нельзя
+    assert!(policies.enact(PolicyType::MartialLaw, &technologies, 16).is_err());
+    policies.enact(PolicyType::MartialLaw, &technologies, 20).unwrap();
+    assert_eq!(policies.revoke(PolicyType::Festival, 20), Err(PolicyError::NotActive(PolicyType::Festival)));
+}
+
+#[test]
+fn test_policies_modify_stats_and_production() {
+    let registry = BuildingRegistry::builtin();
+    let mut city = test_city(Terrain::Forest);
+    let mut mill = Building::new("mill".to_string(), "Лесопилка".to_string(), BuildingType::LumberMill, (1, 1));
+    mill.level = 3;
+    city.buildings.insert(mill.id.clone(), mill);
+    city.population.grow(20);
+    city.update_resource_production(&registry, &ProductionModifiers::default());
+    city.update_stats(&registry);
+    let wood = city.resources.get_production_rate(&ResourceType::Wood);
+    let stats = city.stats.clone();
+
+    // Празднества отвлекают от работы: -10% дерева
+    city.policies.enact(PolicyType::Festival, &governed(), 0).unwrap();
+    city.ledger.begin_turn(1);
+    city.update_resource_production(&registry, &ProductionModifiers::default());
+    city.update_stats(&registry);
+    assert!(city.resources.get_production_rate(&ResourceType::Wood) < wood);
+    assert!(city
+        .ledger
+        .current()
+        .unwrap()
+        .entries
+        .iter()
+        .any(|entry| entry.source == LedgerSource::PolicyBonus && entry.amount < 0));
+    assert_eq!(city.stats.culture, stats.culture + 5);
+    assert!(city.stats.happiness > stats.happiness);
+
+    city.policies.lapse(&PolicyType::Festival);
+    city.policies.enact(PolicyType::MartialLaw, &governed(), 0).unwrap();
+    city.policies.enact(PolicyType::FreeTrade, &governed(), 0).unwrap();
+    city.update_stats(&registry);
+    assert_eq!(city.stats.defense, stats.defense + 10);
+    assert_eq!(city.stats.growth, stats.growth + 5);
+}
+
+#[test]
+fn test_rationing_saves_food() {
+    let mut city = test_city(Terrain::Forest);
+    let mut rng = rng::seeded(3);
+    city.resources.set(ResourceType::Food, 100);
+    city.policies.enact(PolicyType::Rationing, &governed(), 0).unwrap();
+
+    // Жители получают 75% обычных 6 единиц еды и не голодают
+    let total = city.population.total();
+    city.update_population(&mut rng);
+    assert_eq!(city.resources.get(&ResourceType::Food), 96);
+    assert!(city.population.total() >= total);
+}
+
+#[test]
+fn test_unpaid_policy_lapses() {
+    let registry = BuildingRegistry::builtin();
+    let mut city = test_city(Terrain::Forest);
+    let mut rng = rng::seeded(1);
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let mut events = EventSystem::new();
+    let handler_log = log.clone();
+    events.register_handler::<PolicyLapsedEvent>(
+        "policy_lapsed".to_string(),
+        EventPriority::Normal,
+        false,
+        move |event| {
+            handler_log.lock().unwrap().push(event.policy);
+            EventResult::Continue
+        },
+    );
+
+    city.policies.enact(PolicyType::MartialLaw, &governed(), 0).unwrap();
+    city.resources.set(ResourceType::Gold, 20);
+    city.update(&registry, &ProductionModifiers::default(), &GameClock::new(), &mut rng, &events);
+    assert!(city.policies.is_active(&PolicyType::MartialLaw));
+    let upkeep: i64 = city
+        .ledger
+        .current()
+        .unwrap()
+        .entries
+        .iter()
+        .filter(|entry| entry.source == LedgerSource::Policy(PolicyType::MartialLaw))
+        .map(|entry| entry.amount)
+        .sum();
+    assert_eq!(upkeep, -15);
+
+    // Казне не хватает на содержание — политика отменяется
+    city.resources.set(ResourceType::Gold, 10);
+    city.update(&registry, &ProductionModifiers::default(), &GameClock::new(), &mut rng, &events);
+    assert!(!city.policies.is_active(&PolicyType::MartialLaw));
+    assert_eq!(*log.lock().unwrap(), vec![PolicyType::MartialLaw]);
+}
@@ -0,0 +1,222 @@
+// cityrade-types/src/tests/population_tests.rs
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use crate::building::{Building, BuildingType};
+use crate::building_registry::BuildingRegistry;
+use crate::city::{City, Terrain};
+use crate::construction::{ConstructionJob, ConstructionKind};
+use crate::events::{EventPriority, EventResult, EventSystem, UnrestEvent};
+use crate::ledger::LedgerSource;
+use crate::population::{Population, PopulationClass, Unrest, STRIKE_HAPPINESS};
+use crate::production::ProductionModifiers;
+use crate::resources::ResourceType;
+use crate::rng;
+use super::test_city;
+
+#[test]
+fn test_with_total_follows_class_shares() {
+    let population = Population::with_total(10);
+
+    assert_eq!(population.total(), 10);
+    assert_eq!(population.count(&PopulationClass::Peasant), 5);
+    assert_eq!(population.count(&PopulationClass::Worker), 3);
+    assert_eq!(population.count(&PopulationClass::Merchant), 1);
+    assert_eq!(population.count(&PopulationClass::Soldier), 1);
+    assert_eq!(population.count(&PopulationClass::Noble), 0);
+    // 5 + 3 + 1 + 1.5 (солдат) порций по 0.5 еды, с округлением вверх
+    assert_eq!(population.daily_food_consumption(), 6);
+
+    let empty = Population::with_total(0);
+    assert_eq!(empty.get_production_bonus(&ResourceType::Crystal), 0.0);
+    assert_eq!(empty.get_production_bonus(&ResourceType::Food), 0.0);
+}
+
+#[test]
+fn test_grow_and_shrink_are_deterministic() {
+    let mut population = Population::with_total(10);
+    population.grow(30);
+    assert_eq!(population.total(), 40);
+    assert_eq!(population.count(&PopulationClass::Peasant), 20);
+    assert_eq!(population.count(&PopulationClass::Soldier), 5);
+
+    // Уходят жители самых многочисленных классов
+    population.shrink(2);
+    assert_eq!(population.count(&PopulationClass::Peasant), 18);
+    population.shrink(100);
+    assert_eq!(population.total(), 0);
+}
+
+#[test]
+fn test_legacy_population_is_migrated() {
+    let city = test_city(Terrain::Plain);
+    let mut value = serde_json::to_value(&city).unwrap();
+    value["population"] = serde_json::json!(25);
+
+    let migrated: City = serde_json::from_value(value).unwrap();
+    assert_eq!(migrated.population.total(), 25);
+    assert_eq!(migrated.population.classes, Population::with_total(25).classes);
+
+    let restored: City = serde_json::from_str(&serde_json::to_string(&migrated).unwrap()).unwrap();
+    assert_eq!(restored.population.classes, migrated.population.classes);
+}
+
+#[test]
+fn test_city_population_eats_and_starves() {
+    let mut city = test_city(Terrain::Plain);
+    let mut rng = rng::seeded(3);
+    city.resources.set(ResourceType::Food, 100);
+
+    city.ledger.begin_turn(1);
+    city.update_population(&mut rng);
+    assert_eq!(city.resources.get(&ResourceType::Food), 94);
+    assert_eq!(city.ledger.net(&ResourceType::Food, 1), -6);
+    assert!(city
+        .ledger
+        .current()
+        .unwrap()
+        .entries
+        .iter()
+        .any(|entry| entry.source == LedgerSource::Consumption));
+
+    // Еды не хватает — город теряет жителя и счастье
+    city.resources.set(ResourceType::Food, 2);
+    let total = city.population.total();
+    let happiness = city.stats.happiness;
+    city.update_population(&mut rng);
+    assert_eq!(city.resources.get(&ResourceType::Food), 0);
+    assert_eq!(city.population.total(), total - 1);
+    assert_eq!(city.resources.get(&ResourceType::Population), total - 1);
+    assert_eq!(city.stats.happiness, happiness - 5);
+}
+
+#[test]
+fn test_population_bonus_applies_to_production() {
+    let registry = BuildingRegistry::builtin();
+    let mut city = test_city(Terrain::Plain);
+
+    city.update_resource_production(&registry, &ProductionModifiers::default());
+    // 8 базовых, равнина дает +20% еды, жители — еще +50%
+    assert_eq!(city.resources.get_production_rate(&ResourceType::Food), 9 + 4);
+
+    // Без жителей бонуса нет
+    city.population = Population::with_total(0);
+    city.update_resource_production(&registry, &ProductionModifiers::default());
+    assert_eq!(city.resources.get_production_rate(&ResourceType::Food), 9);
+}
+
+#[test]
+fn test_class_needs_drive_class_happiness() {
+    let mut population = Population::with_total(40);
+    assert_eq!(population.count(&PopulationClass::Noble), 1);
+    assert_eq!(population.demand().get(&ResourceType::LuxuryGoods), Some(&1));
+
+    // Еды вдоволь, роскоши нет: знать довольна лишь на 70%
+    let food = population.daily_food_consumption();
+    for _ in 0..50 {
+        population.apply_supply(&BTreeMap::from([(ResourceType::Food, food)]));
+    }
+    assert!(population.class_happiness(&PopulationClass::Peasant) > 0.95);
+    let noble = population.class_happiness(&PopulationClass::Noble);
+    assert!((noble - 0.7).abs() < 0.05);
+    assert_eq!(population.unrest(&PopulationClass::Noble), Unrest::Calm);
+
+    // Долгий голод доводит до забастовок, а затем до бунта
+    for _ in 0..12 {
+        population.apply_supply(&BTreeMap::new());
+    }
+    assert!(population.class_happiness(&PopulationClass::Peasant) < STRIKE_HAPPINESS);
+    assert_eq!(population.unrest(&PopulationClass::Peasant), Unrest::Strike);
+    for _ in 0..8 {
+        population.apply_supply(&BTreeMap::new());
+    }
+    assert_eq!(population.unrest(&PopulationClass::Peasant), Unrest::Riot);
+    assert_eq!(population.striking_classes(), PopulationClass::ALL.to_vec());
+}
+
+#[test]
+fn test_schools_and_markets_move_idle_residents() {
+    let mut population = Population::with_total(10);
+    let buildings = BTreeMap::from([(BuildingType::School, 2), (BuildingType::Market, 1)]);
+    let idle = BTreeMap::from([(PopulationClass::Peasant, 2), (PopulationClass::Worker, 3)]);
+
+    // Школа 2 уровня переводит одного крестьянина за ход,
+    // рынок 1 уровня — одного рабочего за четыре хода
+    let moved = population.apply_mobility(&buildings, &idle);
+    assert_eq!(moved, vec![(PopulationClass::Peasant, PopulationClass::Worker, 1)]);
+    for _ in 0..3 {
+        population.apply_mobility(&buildings, &idle);
+    }
+    assert_eq!(population.count(&PopulationClass::Peasant), 1);
+    assert_eq!(population.count(&PopulationClass::Merchant), 2);
+    assert_eq!(population.total(), 10);
+
+    // Переходят только безработные
+    let moved = population.apply_mobility(&buildings, &BTreeMap::new());
+    assert!(moved.is_empty());
+}
+
+#[test]
+fn test_strikes_halt_production_and_riots_damage_buildings() {
+    let registry = BuildingRegistry::builtin();
+    let mut city = test_city(Terrain::Plain);
+    let mut farm = Building::new("farm".to_string(), "Ферма".to_string(), BuildingType::Farm, (1, 1));
+    farm.level = 3;
+    city.buildings.insert(farm.id.clone(), farm);
+
+    city.update_resource_production(&registry, &ProductionModifiers::default());
+    let working = city.resources.get_production_rate(&ResourceType::Food);
+
+    city.population.class_happiness.insert(PopulationClass::Peasant, 0.2);
+    city.update_resource_production(&registry, &ProductionModifiers::default());
+    assert!(city.resources.get_production_rate(&ResourceType::Food) < working);
+    assert_eq!(city.staffing("farm"), 5.0 / 6.0);
+
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let mut events = EventSystem::new();
+    let handler_log = log.clone();
+    events.register_handler::<UnrestEvent>("unrest".to_string(), EventPriority::Normal, false, move |event| {
+        handler_log.lock().unwrap().push((event.class.clone(), event.unrest, event.damaged_building.clone()));
+        EventResult::Continue
+    });
+
+    city.population.class_happiness.insert(PopulationClass::Peasant, 0.0);
+    let mut rng = rng::seeded(7);
+    for _ in 0..20 {
+        city.resolve_unrest(&mut rng, &events);
+    }
+    let log = log.lock().unwrap();
+    assert_eq!(log.len(), 20);
+    assert!(log.iter().all(|(class, unrest, _)| *class == PopulationClass::Peasant && *unrest == Unrest::Riot));
+    // Ферма теряет уровни до первого, дальше бунт ее не трогает
+    let damage = log.iter().filter(|(_, _, building)| building.is_some()).count();
+    assert_eq!(damage, 2);
+    assert_eq!(city.buildings["farm"].level, 1);
+}
+
+#[test]
+fn test_riots_spare_buildings_being_upgraded() {
+    let mut city = test_city(Terrain::Plain);
+    let mut farm = Building::new("farm".to_string(), "Ферма".to_string(), BuildingType::Farm, (1, 1));
+    farm.level = 2;
+    city.buildings.insert(farm.id.clone(), farm);
+    city.construction.push(ConstructionJob::new(
+        "farm".to_string(),
+        BuildingType::Farm,
+        ConstructionKind::Upgrade,
+        3,
+        Vec::new(),
+        1,
+    ));
+
+    city.population.class_happiness.insert(PopulationClass::Peasant, 0.0);
+    let mut rng = rng::seeded(7);
+    for _ in 0..20 {
+        city.resolve_unrest(&mut rng, &EventSystem::new());
+    }
+    assert_eq!(city.buildings["farm"].level, 2);
+
+    // Улучшение поднимает здание ровно на один уровень
+    city.update_construction(0, &EventSystem::new());
+    assert_eq!(city.buildings["farm"].level, 3);
+}
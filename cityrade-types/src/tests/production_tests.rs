@@ -0,0 +1,103 @@
+// cityrade-types/src/tests/production_tests.rs
+use std::collections::BTreeMap;
+
+use crate::building::{Building, BuildingType};
+use crate::building_registry::{BuildingRegistry, Recipe};
+use crate::city::{City, Terrain};
+use crate::production::{run_recipe, run_recipes, ProductionModifiers};
+use crate::resources::{Resource, ResourceType};
+use super::test_city;
+
+fn tools_recipe() -> Recipe {
+    Recipe {
+        inputs: BTreeMap::from([(ResourceType::Iron, 4), (ResourceType::Coal, 2)]),
+        outputs: BTreeMap::from([(ResourceType::Tools, 2)]),
+    }
+}
+
+fn city_with_workshop() -> City {
+    let mut city = test_city(Terrain::Plain);
+    let workshop = Building::new("workshop".to_string(), "Мастерская".to_string(), BuildingType::Workshop, (1, 1));
+    city.buildings.insert(workshop.id.clone(), workshop);
+    city
+}
+
+#[test]
+fn test_recipe_converts_inputs() {
+    let mut resources = Resource::new();
+    resources.set(ResourceType::Iron, 10);
+    resources.set(ResourceType::Coal, 10);
+
+    let run = run_recipe(&tools_recipe(), &mut resources);
+
+    assert_eq!(run.efficiency, 1.0);
+    assert!(run.missing.is_empty());
+    assert_eq!(resources.get(&ResourceType::Iron), 6);
+    assert_eq!(resources.get(&ResourceType::Coal), 8);
+    assert_eq!(resources.get(&ResourceType::Tools), 2);
+}
+
+#[test]
+fn test_recipe_throttles_and_stalls() {
+    let mut resources = Resource::new();
+    resources.set(ResourceType::Iron, 2);
+    resources.set(ResourceType::Coal, 10);
+
+    // Половина железа — половина выпуска
+    let run = run_recipe(&tools_recipe(), &mut resources);
+    assert_eq!(run.efficiency, 0.5);
+    assert_eq!(run.missing, vec![(ResourceType::Iron, 2)]);
+    assert_eq!(resources.get(&ResourceType::Iron), 0);
+    assert_eq!(resources.get(&ResourceType::Coal), 9);
+    assert_eq!(resources.get(&ResourceType::Tools), 1);
+
+    // Без железа здание простаивает и ничего не расходует
+    let status = run_recipes("workshop", &BuildingType::Workshop, &[tools_recipe()], &mut resources, 1.0);
+    assert!(status.is_stalled());
+    assert_eq!(status.missing, vec![(ResourceType::Iron, 4)]);
+    assert_eq!(resources.get(&ResourceType::Coal), 9);
+    assert_eq!(resources.get(&ResourceType::Tools), 1);
+}
+
+#[test]
+fn test_workshop_reports_bottlenecks() {
+    let registry = BuildingRegistry::builtin();
+    let mut city = city_with_workshop();
+    city.resources.set(ResourceType::Iron, 100);
+    city.resources.set(ResourceType::Coal, 100);
+    city.resources.set(ResourceType::Crystal, 0);
+
+    city.update_resource_production(&registry, &ProductionModifiers::default());
+
+    let status = &city.production_status[0];
+    assert_eq!(status.building_id, "workshop");
+    // Инструменты производятся, роскошные товары — нет
+    assert_eq!(status.efficiency, 0.5);
+    assert_eq!(status.missing, vec![(ResourceType::Crystal, 1)]);
+    assert_eq!(city.production_bottlenecks().len(), 1);
+    assert_eq!(city.resources.get(&ResourceType::Tools), 2);
+    assert_eq!(city.resources.get(&ResourceType::LuxuryGoods), 0);
+
+    city.resources.set(ResourceType::Crystal, 5);
+    city.update_resource_production(&registry, &ProductionModifiers::default());
+    assert_eq!(city.production_status[0].efficiency, 1.0);
+    assert!(city.production_bottlenecks().is_empty());
+    assert_eq!(city.resources.get(&ResourceType::LuxuryGoods), 1);
+}
+
+#[test]
+fn test_registry_rejects_invalid_recipe() {
+    let registry = BuildingRegistry::builtin();
+    let mut definitions: Vec<_> = registry.definitions().cloned().collect();
+    let workshop = definitions
+        .iter_mut()
+        .find(|definition| definition.id == BuildingType::Workshop)
+        .unwrap();
+    workshop.levels[0].recipes.push(Recipe {
+        inputs: BTreeMap::from([(ResourceType::Tools, 1)]),
+        outputs: BTreeMap::from([(ResourceType::Tools, 2)]),
+    });
+
+    let error = BuildingRegistry::new(definitions).unwrap_err();
+    assert!(error.contains("одновременно вход и выход"));
+}
@@ -0,0 +1,90 @@
+// cityrade-types/src/tests/storage_tests.rs
+use crate::building::{Building, BuildingType};
+use crate::building_registry::BuildingRegistry;
+use crate::city::Terrain;
+use crate::resources::{Resource, ResourceType};
+use crate::storage::{self, OverflowPolicy, StoragePolicy};
+use super::test_city;
+
+#[test]
+fn test_add_respects_capacity() {
+    let mut resources = Resource::new();
+    resources.set(ResourceType::Wood, 0);
+    resources.set_capacity(ResourceType::Wood, Some(100));
+
+    assert_eq!(resources.add(&ResourceType::Wood, 80), 0);
+    assert_eq!(resources.add(&ResourceType::Wood, 50), 30);
+    assert_eq!(resources.get(&ResourceType::Wood), 100);
+    assert!(resources.is_full(&ResourceType::Wood));
+    assert_eq!(resources.pending_overflow(&ResourceType::Wood), 30);
+
+    // try_store не копит излишки
+    assert_eq!(resources.try_store(&ResourceType::Wood, 10), 10);
+    assert_eq!(resources.pending_overflow(&ResourceType::Wood), 30);
+
+    assert_eq!(resources.take_overflow(), vec![(ResourceType::Wood, 30)]);
+    assert!(resources.take_overflow().is_empty());
+}
+
+#[test]
+fn test_add_saturates_instead_of_wrapping() {
+    let mut resources = Resource::new();
+    resources.set(ResourceType::Energy, u32::MAX - 5);
+
+    assert_eq!(resources.capacity(&ResourceType::Energy), None);
+    assert_eq!(resources.add(&ResourceType::Energy, 10), 5);
+    assert_eq!(resources.get(&ResourceType::Energy), u32::MAX);
+    assert_eq!(resources.pending_overflow(&ResourceType::Energy), 5);
+}
+
+#[test]
+fn test_shrinking_capacity_moves_excess_to_overflow() {
+    let mut resources = Resource::new();
+    resources.set(ResourceType::Stone, 500);
+
+    resources.set_capacity(ResourceType::Stone, Some(300));
+    assert_eq!(resources.get(&ResourceType::Stone), 300);
+    assert_eq!(resources.pending_overflow(&ResourceType::Stone), 200);
+
+    resources.set_capacity(ResourceType::Stone, None);
+    assert_eq!(resources.free_space(&ResourceType::Stone), u32::MAX - 300);
+}
+
+#[test]
+fn test_food_spoils() {
+    let mut resources = Resource::new();
+    resources.set(ResourceType::Food, 1000);
+    resources.set(ResourceType::Wood, 1000);
+
+    let spoiled = resources.apply_spoilage();
+
+    assert_eq!(spoiled, vec![(ResourceType::Food, 20)]);
+    assert_eq!(resources.get(&ResourceType::Food), 980);
+    assert_eq!(resources.get(&ResourceType::Wood), 1000);
+}
+
+#[test]
+fn test_storage_buildings_raise_capacity() {
+    let registry = BuildingRegistry::builtin();
+    let mut city = test_city(Terrain::Plain);
+
+    assert_eq!(city.resources.capacity(&ResourceType::Food), storage::base_capacity(&ResourceType::Food));
+    assert_eq!(city.resources.capacity(&ResourceType::Population), None);
+
+    let mut granary = Building::new("granary".to_string(), "Амбар".to_string(), BuildingType::Granary, (1, 1));
+    granary.upgrade();
+    city.buildings.insert(granary.id.clone(), granary);
+    city.update_storage_capacity(&registry);
+
+    assert_eq!(city.resources.capacity(&ResourceType::Food), Some(1000 + 1600));
+    assert_eq!(city.resources.capacity(&ResourceType::Wood), Some(1000));
+}
+
+#[test]
+fn test_storage_policy_overrides() {
+    let mut policy = StoragePolicy::default();
+    policy.set(ResourceType::Food, OverflowPolicy::Spill("city2".to_string()));
+
+    assert_eq!(policy.policy_for(&ResourceType::Wood), &OverflowPolicy::Waste);
+    assert_eq!(policy.policy_for(&ResourceType::Food), &OverflowPolicy::Spill("city2".to_string()));
+}
@@ -0,0 +1,147 @@
+// cityrade-types/src/tests/taxation_tests.rs
+use std::sync::{Arc, Mutex};
+
+use crate::building::{Building, BuildingError, BuildingType};
+use crate::building_registry::BuildingRegistry;
+use crate::city::Terrain;
+use crate::events::{BankruptcyEvent, EventPriority, EventResult, EventSystem};
+use crate::ledger::LedgerSource;
+use crate::policies::PolicyType;
+use crate::population::{Population, PopulationClass};
+use crate::resources::ResourceType;
+use crate::rng;
+use crate::taxation::{self, FiscalStatus, TaxError, TaxPolicy, BANKRUPTCY_DEBT};
+use crate::technology::{TechnologyTree, TechnologyType};
+use super::test_city;
+
+#[test]
+fn test_tax_policy_trades_gold_for_happiness() {
+    // 5 крестьян, 3 рабочих, 1 торговец, 1 солдат
+    let population = Population::with_total(10);
+    let mut policy = TaxPolicy::default();
+
+    // Доход классов 5, 6, 5 и 1 золота; налог 10% с каждого округляется вниз
+    assert!(policy.income_tax_revenue(&population).is_empty());
+    assert_eq!(policy.happiness_modifier(&population), 0);
+
+    for class in PopulationClass::ALL {
+        policy.set_income_tax(class, 50).unwrap();
+    }
+    let revenue = policy.income_tax_revenue(&population);
+    assert_eq!(revenue.get(&PopulationClass::Peasant), Some(&2));
+    assert_eq!(revenue.get(&PopulationClass::Worker), Some(&3));
+    assert_eq!(policy.happiness_modifier(&population), -20);
+
+    policy.set_income_tax(PopulationClass::Peasant, 0).unwrap();
+    assert_eq!(policy.set_income_tax(PopulationClass::Peasant, 101), Err(TaxError::IncomeTaxTooHigh(101)));
+
+    // Пошлины дают золото с торговых маршрутов, но отпугивают переселенцев
+    policy.set_tariff(20).unwrap();
+    assert_eq!(policy.tariff_on(200), 40);
+    assert_eq!(policy.tariff_on(4), 0);
+    assert_eq!(policy.growth_modifier(), -10);
+    assert_eq!(policy.set_tariff(150), Err(TaxError::TariffTooHigh(150)));
+}
+
+#[test]
+fn test_taxes_are_collected_and_recorded() {
+    let registry = BuildingRegistry::builtin();
+    let mut city = test_city(Terrain::Plain);
+    let mut market = Building::new("market".to_string(), "Рынок".to_string(), BuildingType::Market, (1, 1));
+    market.level = 2;
+    city.buildings.insert(market.id.clone(), market);
+    city.tax_policy.set_income_tax(PopulationClass::Merchant, 40).unwrap();
+    city.tax_policy.set_tariff(10).unwrap();
+    let gold = city.resources.get(&ResourceType::Gold);
+
+    city.ledger.begin_turn(1);
+    city.collect_taxes(&EventSystem::new());
+
+    // Налог с торговца 2, содержание рынка 2 уровня — 2; без сделок пошлин нет
+    assert_eq!(city.fiscal_report.income_tax.get(&PopulationClass::Merchant), Some(&2));
+    assert_eq!(city.fiscal_report.tariffs, 0);
+    assert_eq!(city.fiscal_report.maintenance, 2);
+    assert_eq!(city.resources.get(&ResourceType::Gold), gold);
+
+    // Пошлина с оборота маршрута поступает в казну
+    assert_eq!(city.levy_tariff(300), 30);
+    assert_eq!(city.fiscal_report.tariffs, 30);
+    assert_eq!(city.resources.get(&ResourceType::Gold), gold + 30);
+    assert_eq!(city.ledger.net(&ResourceType::Gold, 1), 30);
+    let statement = city.ledger.income_statement(1);
+    assert_eq!(statement["tariff"][&ResourceType::Gold], 30);
+    assert_eq!(statement["maintenance"][&ResourceType::Gold], -2);
+    assert!(city
+        .ledger
+        .current()
+        .unwrap()
+        .entries
+        .iter()
+        .any(|entry| entry.source == LedgerSource::IncomeTax(PopulationClass::Merchant)));
+
+    city.update_stats(&registry);
+    assert_eq!(city.stats.growth, -5);
+
+    // При свободной торговле пошлины не взимаются
+    let mut technologies = TechnologyTree::new();
+    technologies.completed_technologies.insert(TechnologyType::Governance);
+    city.policies.enact(PolicyType::FreeTrade, &technologies, 0).unwrap();
+    assert_eq!(city.levy_tariff(300), 0);
+    assert_eq!(city.fiscal_report.tariffs, 30);
+}
+
+#[test]
+fn test_debt_and_bankruptcy() {
+    let registry = BuildingRegistry::builtin();
+    let mut city = test_city(Terrain::Plain);
+    let bankruptcies = Arc::new(Mutex::new(Vec::new()));
+    let mut events = EventSystem::new();
+    let log = bankruptcies.clone();
+    events.register_handler::<BankruptcyEvent>("bankruptcy".to_string(), EventPriority::Normal, false, move |event| {
+        log.lock().unwrap().push(event.debt);
+        EventResult::Continue
+    });
+
+    // Содержание не по карману казне: недостача становится долгом
+    let farm = Building::new("farm".to_string(), "Ферма".to_string(), BuildingType::Farm, (1, 1));
+    city.buildings.insert(farm.id.clone(), farm);
+    city.tax_policy.maintenance = 300;
+    city.resources.set(ResourceType::Gold, 100);
+    city.collect_taxes(&events);
+    assert_eq!(city.treasury_debt, 200);
+    assert_eq!(city.resources.get(&ResourceType::Gold), 0);
+    assert_eq!(city.fiscal_status(), FiscalStatus::InDebt);
+
+    let happiness = city.stats.happiness;
+    city.update_stats(&registry);
+    assert_eq!(city.stats.happiness, happiness - taxation::debt_happiness_penalty(200));
+
+    // Проценты и новая недостача доводят до банкротства
+    city.collect_taxes(&events);
+    assert_eq!(city.fiscal_report.interest, 10);
+    assert_eq!(city.treasury_debt, 510);
+    assert!(city.treasury_debt >= BANKRUPTCY_DEBT);
+    assert!(city.is_bankrupt());
+    assert_eq!(*bankruptcies.lock().unwrap(), vec![510]);
+
+    let err = city
+        .add_building(
+            BuildingType::Farm,
+            "Ферма".to_string(),
+            (2, 2),
+            &registry,
+            &TechnologyTree::new(),
+            &mut rng::seeded(1),
+        )
+        .unwrap_err();
+    assert_eq!(err, BuildingError::Bankrupt { debt: 510 });
+
+    // Доходы сначала гасят долг
+    city.tax_policy.maintenance = 0;
+    city.resources.set(ResourceType::Gold, 1000);
+    city.collect_taxes(&events);
+    assert_eq!(city.treasury_debt, 0);
+    assert_eq!(city.fiscal_report.repaid, 510 + 26);
+    assert_eq!(city.resources.get(&ResourceType::Gold), 1000 - 536);
+    assert!(!city.is_bankrupt());
+}
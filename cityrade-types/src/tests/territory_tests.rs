@@ -0,0 +1,113 @@
+// cityrade-types/src/tests/territory_tests.rs
+use std::collections::BTreeMap;
+
+use crate::resources::ResourceType;
+use crate::territory::{BorderClaim, border_tile_cost, expand_borders, worked_tile_yields, worked_tiles};
+use crate::world::{TerrainTile, WorldMap};
+
+fn claim(city_id: &str, culture: u32) -> BorderClaim {
+    BorderClaim { city_id: city_id.to_string(), culture }
+}
+
+fn cultures(values: &[(&str, u32)]) -> BTreeMap<String, u32> {
+    values.iter().map(|(id, culture)| (id.to_string(), *culture)).collect()
+}
+
+/// Два города на узкой карте: "a" в (2, 2), "b" в (8, 2), между ними свободный столбец x = 5
+fn two_cities(width: u64) -> WorldMap {
+    let mut world = WorldMap::new(width, 5);
+    world.add_city(2, 2, "a", "Альфа".to_string()).unwrap();
+    world.add_city(8, 2, "b", "Бета".to_string()).unwrap();
+    world
+}
+
+#[test]
+fn test_border_tile_cost_grows_with_territory() {
+    assert_eq!(border_tile_cost(0), 20);
+    assert_eq!(border_tile_cost(25), 70);
+}
+
+#[test]
+fn test_expand_borders_takes_nearest_free_tile() {
+    let mut world = WorldMap::new(30, 30);
+    world.add_city(10, 10, "a", "Альфа".to_string()).unwrap();
+    let size = world.territory("a").len();
+
+    let claimed = expand_borders(&mut world, &[claim("a", 1)], &cultures(&[("a", 1)]));
+    assert_eq!(claimed, vec![("a".to_string(), (7, 10))]);
+    assert_eq!(world.owner(7, 10), Some("a"));
+    assert_eq!(world.territory("a").len(), size + 1);
+
+    // Город без позиции на карте ничего не получает
+    assert!(expand_borders(&mut world, &[claim("ghost", 10)], &BTreeMap::new()).is_empty());
+}
+
+#[test]
+fn test_contested_tile_goes_to_more_cultured_city() {
+    let mut world = two_cities(20);
+    let claims = [claim("b", 5), claim("a", 10)];
+
+    let claimed = expand_borders(&mut world, &claims, &cultures(&[("a", 10), ("b", 5)]));
+    assert_eq!(claimed, vec![("a".to_string(), (5, 2)), ("b".to_string(), (11, 2))]);
+    assert_eq!(world.owner(5, 2), Some("a"));
+}
+
+#[test]
+fn test_dominant_culture_takes_foreign_tiles() {
+    let mut world = two_cities(20);
+    for y in 0..5 {
+        world.claim(5, y, "b");
+    }
+
+    // Культуры не хватает вдвое — чужие клетки не отходят
+    let claimed = expand_borders(&mut world, &[claim("a", 19)], &cultures(&[("a", 19), ("b", 10)]));
+    assert!(claimed.is_empty());
+    assert_eq!(world.owner(5, 2), Some("b"));
+
+    let claimed = expand_borders(&mut world, &[claim("a", 20)], &cultures(&[("a", 20), ("b", 10)]));
+    assert_eq!(claimed, vec![("a".to_string(), (5, 2))]);
+    assert_eq!(world.owner(5, 2), Some("a"));
+    assert!(world.territory("a").contains(&(5, 2)));
+    assert!(!world.territory("b").contains(&(5, 2)));
+}
+
+#[test]
+fn test_core_tiles_are_never_taken() {
+    // Справа от "b" карта кончается, на границе только ядро "a"
+    let mut world = two_cities(11);
+    for y in 0..5 {
+        world.claim(5, y, "b");
+    }
+
+    let claimed = expand_borders(&mut world, &[claim("b", 100)], &cultures(&[("a", 1), ("b", 100)]));
+    assert!(claimed.is_empty());
+    assert_eq!(world.owner(4, 2), Some("a"));
+}
+
+#[test]
+fn test_frontier_and_border_between() {
+    let mut world = two_cities(20);
+    assert!(world.border_between("a", "b").is_empty());
+
+    world.claim(5, 2, "a");
+    assert_eq!(world.border_between("a", "b"), vec![(5, 2), (6, 2)]);
+    assert_eq!(world.frontier("a"), vec![(5, 0), (5, 1), (5, 3), (5, 4), (6, 2)]);
+}
+
+#[test]
+fn test_worked_tiles_prefer_rich_tiles_near_center() {
+    let mut world = WorldMap::new(30, 30);
+    world.add_city(10, 10, "a", "Альфа".to_string()).unwrap();
+    world.set_tile(9, 10, TerrainTile::Mountain);
+    world.set_tile(10, 11, TerrainTile::Water);
+
+    // Жителей меньше, чем нужно на одну клетку
+    assert!(worked_tiles(&world, "a", 4).is_empty());
+    assert_eq!(worked_tiles(&world, "a", 10), vec![(9, 10), (10, 9)]);
+    assert!(!worked_tiles(&world, "a", 100).contains(&(10, 10)));
+
+    let yields = worked_tile_yields(&world, "a", 10);
+    assert_eq!(yields.get(&ResourceType::Stone), Some(&2));
+    assert_eq!(yields.get(&ResourceType::Food), Some(&2));
+    assert!(worked_tile_yields(&world, "ghost", 10).is_empty());
+}
@@ -0,0 +1,117 @@
+// cityrade-types/src/tests/upkeep_tests.rs
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use crate::building::{Building, BuildingType};
+use crate::building_registry::BuildingRegistry;
+use crate::city::{City, CityStats, Terrain};
+use crate::clock::GameClock;
+use crate::events::{EventPriority, EventResult, EventSystem, ResourceShortageEvent};
+use crate::production::ProductionModifiers;
+use crate::resources::{Resource, ResourceType};
+use crate::rng;
+use crate::upkeep::{pay_upkeep, UpkeepPriority, SHORTAGE_HAPPINESS_PENALTY};
+use super::test_city;
+
+fn city_with(buildings: &[(&str, BuildingType)]) -> City {
+    let mut city = test_city(Terrain::Plain);
+    for (index, (id, building_type)) in buildings.iter().enumerate() {
+        let building = Building::new(id.to_string(), id.to_string(), building_type.clone(), (index as i32, 0));
+        city.buildings.insert(building.id.clone(), building);
+    }
+    city
+}
+
+#[test]
+fn test_pay_upkeep_throttles_by_scarcest_resource() {
+    let mut resources = Resource::new();
+    resources.set(ResourceType::Gold, 100);
+    resources.set(ResourceType::Energy, 5);
+    let consumption = BTreeMap::from([(ResourceType::Gold, 20), (ResourceType::Energy, 10)]);
+
+    let entry = pay_upkeep("lab", &BuildingType::Laboratory, &consumption, &mut resources);
+
+    assert_eq!(entry.efficiency, 0.5);
+    assert!(entry.is_starved());
+    assert!(!entry.is_disabled());
+    assert_eq!(entry.missing(), vec![(ResourceType::Gold, 10), (ResourceType::Energy, 5)]);
+    assert_eq!(resources.get(&ResourceType::Gold), 90);
+    assert_eq!(resources.get(&ResourceType::Energy), 0);
+}
+
+#[test]
+fn test_upkeep_follows_priority_order() {
+    let registry = BuildingRegistry::builtin();
+    let mut city = city_with(&[("barracks", BuildingType::Barracks), ("temple", BuildingType::Temple)]);
+    city.resources.set(ResourceType::Gold, 20);
+    city.resources.set(ResourceType::Food, 100);
+
+    // По умолчанию казармы обеспечиваются раньше храма
+    city.update_resource_production(&registry, &ProductionModifiers::default());
+    let starved: Vec<&str> = city.starved_buildings().iter().map(|e| e.building_id.as_str()).collect();
+    assert_eq!(starved, vec!["temple"]);
+
+    city.upkeep_priority = UpkeepPriority::new(vec![BuildingType::Temple, BuildingType::Barracks]);
+    city.resources.set(ResourceType::Gold, 20);
+    city.update_resource_production(&registry, &ProductionModifiers::default());
+    let starved: Vec<&str> = city.starved_buildings().iter().map(|e| e.building_id.as_str()).collect();
+    assert_eq!(starved, vec!["barracks"]);
+}
+
+#[test]
+fn test_disabled_building_loses_stats_and_hurts_happiness() {
+    let registry = BuildingRegistry::builtin();
+    let mut city = city_with(&[("lab", BuildingType::Laboratory)]);
+    city.resources.set(ResourceType::Gold, 1000);
+    city.resources.set(ResourceType::Energy, 0);
+
+    city.update_resource_production(&registry, &ProductionModifiers::default());
+    city.update_stats(&registry);
+
+    let entry = &city.upkeep_ledger[0];
+    assert!(entry.is_disabled());
+    assert_eq!(entry.supplied, vec![(ResourceType::Gold, 0), (ResourceType::Energy, 0)]);
+    assert_eq!(city.resources.get(&ResourceType::Gold), 1000 + 5);
+    assert_eq!(city.stats.culture, CityStats::default().culture);
+    assert_eq!(city.stats.happiness, CityStats::default().happiness - SHORTAGE_HAPPINESS_PENALTY);
+}
+
+#[test]
+fn test_shortage_event_emitted() {
+    let registry = BuildingRegistry::builtin();
+    let mut city = city_with(&[("lab", BuildingType::Laboratory)]);
+    city.resources.set(ResourceType::Gold, 1000);
+    city.resources.set(ResourceType::Energy, 0);
+
+    let mut events = EventSystem::new();
+    let shortages = Arc::new(Mutex::new(Vec::new()));
+    let log = shortages.clone();
+    events.register_handler::<ResourceShortageEvent>(
+        "shortage".to_string(),
+        EventPriority::Normal,
+        false,
+        move |event| {
+            log.lock().unwrap().push((event.building_id.clone(), event.missing.clone()));
+            EventResult::Continue
+        },
+    );
+
+    city.update(&registry, &ProductionModifiers::default(), &GameClock::new(), &mut rng::seeded(1), &events);
+
+    assert_eq!(
+        *shortages.lock().unwrap(),
+        vec![("lab".to_string(), vec![(ResourceType::Gold, 25), (ResourceType::Energy, 7)])]
+    );
+}
+
+#[test]
+fn test_update_production_reports_shortfall() {
+    let mut resources = Resource::new();
+    resources.set(ResourceType::Energy, 3);
+    resources.set_production_rate(ResourceType::Energy, -10);
+
+    let shortfall = resources.update_production();
+
+    assert_eq!(shortfall, vec![(ResourceType::Energy, 7)]);
+    assert_eq!(resources.get(&ResourceType::Energy), 0);
+}
@@ -0,0 +1,305 @@
+// cityrade-types/src/tests/world_tests.rs
+use crate::city::Terrain;
+use crate::resources::ResourceType;
+use crate::world::{
+    deposit_resources, ResourceDeposit, TerrainTile, CITY_MIN_DISTANCE, CITY_TERRITORY_RADIUS, WorldGenConfig, WorldGenerator, WorldMap, CHUNK_SIZE,
+};
+
+#[test]
+fn test_get_and_set_tile_keep_semantics() {
+    let mut world = WorldMap::new(100, 50);
+    assert_eq!(world.chunk_count(), 4 * 2);
+    assert_eq!(world.allocated_chunks(), 0);
+    assert_eq!(world.get_tile(99, 49), Some(TerrainTile::Land));
+    assert_eq!(world.get_tile(100, 0), None);
+    assert_eq!(world.get_tile(-1, 0), None);
+
+    world.set_tile(40, 10, TerrainTile::Water);
+    world.set_tile(41, 10, TerrainTile::ResourceSpot(ResourceType::Crystal));
+    world.add_building(42, 10, "Шахта".to_string());
+    world.add_city(43, 10, "capital", "Столица".to_string()).unwrap();
+    // За пределами карты клетки не меняются
+    world.set_tile(500, 10, TerrainTile::Water);
+
+    assert_eq!(world.get_tile(40, 10), Some(TerrainTile::Water));
+    assert_eq!(world.get_tile(41, 10), Some(TerrainTile::ResourceSpot(ResourceType::Crystal)));
+    assert_eq!(world.get_tile(42, 10), Some(TerrainTile::Building("Шахта".to_string())));
+    assert_eq!(world.get_tile(43, 10), Some(TerrainTile::City("Столица".to_string())));
+    assert_eq!(world.get_tile(500, 10), None);
+    // Все изменения попали в один чанк
+    assert_eq!(world.allocated_chunks(), 1);
+}
+
+#[test]
+fn test_expand_map_marks_new_tiles_unknown() {
+    let mut world = WorldMap::new(40, 40);
+    world.set_tile(39, 39, TerrainTile::Forest);
+    world.expand_map(70, 45);
+
+    assert_eq!(world.get_width(), 70);
+    assert_eq!(world.get_tile(39, 39), Some(TerrainTile::Forest));
+    assert_eq!(world.get_tile(20, 20), Some(TerrainTile::Land));
+    // Новые клетки — и в старых чанках, и в новых
+    assert_eq!(world.get_tile(45, 10), Some(TerrainTile::Unknown));
+    assert_eq!(world.get_tile(10, 42), Some(TerrainTile::Unknown));
+    assert_eq!(world.get_tile(69, 44), Some(TerrainTile::Unknown));
+    assert_eq!(world.tiles().count(), 70 * 45);
+}
+
+#[test]
+fn test_region_and_search() {
+    let mut world = WorldMap::new(CHUNK_SIZE as u64 * 3, CHUNK_SIZE as u64 * 3);
+    world.add_city(30, 33, "first", "Город".to_string()).unwrap();
+    world.add_city(70, 70, "second", "Город".to_string()).unwrap();
+    world.set_tile(31, 33, TerrainTile::Mountain);
+
+    let region: Vec<_> = world.region((29, 32), (31, 33)).collect();
+    assert_eq!(region.len(), 6);
+    assert_eq!(region[4], ((30, 33), TerrainTile::City("Город".to_string())));
+    assert_eq!(region[5], ((31, 33), TerrainTile::Mountain));
+    // Прямоугольник обрезается по краям карты
+    assert_eq!(world.region((-5, -5), (1, 1)).count(), 4);
+
+    let cities: Vec<_> = world
+        .find_tiles(|tile| matches!(tile, TerrainTile::City(_)))
+        .map(|(position, _)| position)
+        .collect();
+    assert_eq!(cities, vec![(30, 33), (70, 70)]);
+}
+
+#[test]
+fn test_world_map_roundtrip() {
+    let mut world = WorldGenerator::new(Some(42)).generate(80, 60);
+    let (x, y) = world.find_tiles(|tile| *tile == TerrainTile::Land).next().unwrap().0;
+    world.add_city(x, y, "first", "Город".to_string()).unwrap();
+
+    let restored: WorldMap = serde_json::from_str(&serde_json::to_string(&world).unwrap()).unwrap();
+    assert!(restored.tiles().eq(world.tiles()));
+    assert!(world.deposits().next().is_some());
+    assert!(restored.deposits().eq(world.deposits()));
+    assert_eq!(restored.territory("first"), world.territory("first"));
+
+    // Таблица имен восстановлена: то же имя не дублируется
+    let mut restored = restored;
+    restored.add_building(x, y + 1, "Город".to_string());
+    assert_eq!(restored.get_tile(x, y + 1), Some(TerrainTile::Building("Город".to_string())));
+}
+
+#[test]
+fn test_corrupted_world_map_is_rejected() {
+    let mut world = WorldMap::new(40, 40);
+    world.add_building(1, 1, "Склад".to_string());
+    world.set_tile(2, 2, TerrainTile::ResourceSpot(ResourceType::Iron));
+    let saved = serde_json::to_value(&world).unwrap();
+    assert!(serde_json::from_value::<WorldMap>(saved.clone()).is_ok());
+
+    // Чанков меньше, чем нужно для размеров карты
+    let mut truncated = saved.clone();
+    truncated["chunks"].as_array_mut().unwrap().pop();
+    assert!(serde_json::from_value::<WorldMap>(truncated).is_err());
+
+    // Размеры, не помещающиеся в координаты, и переполнение числа чанков
+    for (width, height) in [(i32::MAX as u64 + 1, 40), (40, u64::MAX), (u64::MAX, u64::MAX)] {
+        let mut oversized = saved.clone();
+        oversized["width"] = serde_json::json!(width);
+        oversized["height"] = serde_json::json!(height);
+        assert!(serde_json::from_value::<WorldMap>(oversized).is_err());
+    }
+
+    // Плотный чанк без части клеток
+    let mut short = saved.clone();
+    short["chunks"][0]["Dense"].as_array_mut().unwrap().pop();
+    assert!(serde_json::from_value::<WorldMap>(short).is_err());
+
+    // Клетка постройки ссылается на имя, которого нет в таблице
+    let mut unnamed = saved.clone();
+    unnamed["names"] = serde_json::json!([]);
+    assert!(serde_json::from_value::<WorldMap>(unnamed).is_err());
+
+    // Клетка месторождения с несуществующим ресурсом
+    let offset = 2 * CHUNK_SIZE as usize + 2;
+    let mut unknown_resource = saved.clone();
+    unknown_resource["chunks"][0]["Dense"][offset] = serde_json::json!((6u32 << 24) | 0xFFFF);
+    assert!(serde_json::from_value::<WorldMap>(unknown_resource).is_err());
+
+    // Клетка неизвестного вида
+    let mut unknown_kind = saved.clone();
+    unknown_kind["chunks"][0]["Dense"][offset] = serde_json::json!(9u32 << 24);
+    assert!(serde_json::from_value::<WorldMap>(unknown_kind).is_err());
+
+    // Месторождение, территория и город за пределами карты
+    let mut world = WorldMap::new(40, 40);
+    world.add_deposit(ResourceDeposit::new((3, 3), ResourceType::Iron, 100));
+    world.add_city(10, 10, "city", "Город".to_string()).unwrap();
+    let saved = serde_json::to_value(&world).unwrap();
+    assert!(serde_json::from_value::<WorldMap>(saved.clone()).is_ok());
+
+    let mut far_deposit = saved.clone();
+    far_deposit["deposits"][0]["position"] = serde_json::json!([40, 3]);
+    assert!(serde_json::from_value::<WorldMap>(far_deposit).is_err());
+
+    let mut far_territory = saved.clone();
+    far_territory["territory"][0][0] = serde_json::json!([-1, 10]);
+    assert!(serde_json::from_value::<WorldMap>(far_territory).is_err());
+
+    let mut far_city = saved;
+    far_city["cities"]["city"] = serde_json::json!([10, 400]);
+    assert!(serde_json::from_value::<WorldMap>(far_city).is_err());
+}
+
+#[test]
+fn test_generation_is_reproducible_for_seed() {
+    let world = WorldGenerator::new(Some(7)).generate(96, 64);
+    assert!(world.tiles().eq(WorldGenerator::new(Some(7)).generate(96, 64).tiles()));
+    assert!(!world.tiles().eq(WorldGenerator::new(Some(8)).generate(96, 64).tiles()));
+    assert_eq!(WorldGenerator::new(Some(7)).seed(), 7);
+}
+
+#[test]
+fn test_generated_biomes_are_coherent() {
+    let world = WorldGenerator::new(Some(42)).generate(128, 128);
+    for biome in [
+        TerrainTile::Water,
+        TerrainTile::Land,
+        TerrainTile::Forest,
+        TerrainTile::Desert,
+        TerrainTile::Mountain,
+    ] {
+        assert!(world.find_tiles(|tile| *tile == biome).next().is_some(), "нет биома {:?}", biome);
+    }
+    // Края карты — океан
+    assert_eq!(world.get_tile(0, 0), Some(TerrainTile::Water));
+    assert_eq!(world.get_tile(127, 64), Some(TerrainTile::Water));
+
+    // Биомы лежат пятнами: соседние клетки чаще всего одного типа
+    let same = world
+        .tiles()
+        .filter(|((x, y), tile)| world.get_tile(x + 1, *y).as_ref() == Some(tile))
+        .count();
+    assert!(same * 10 > world.tiles().count() * 8);
+}
+
+#[test]
+fn test_generation_follows_config() {
+    let config = WorldGenConfig {
+        sea_level: 1.0,
+        ..WorldGenConfig::default()
+    };
+    let world = WorldGenerator::new(Some(3)).with_config(config).generate(40, 40);
+    assert!(world.tiles().all(|(_, tile)| tile == TerrainTile::Water));
+
+    // Без воды, гор, пустынь, лесов, рек и ресурсов остается только суша
+    let config = WorldGenConfig {
+        edge_falloff: 0.0,
+        sea_level: 0.0,
+        mountain_level: 1.0,
+        desert_temperature: 1.0,
+        forest_moisture: 1.0,
+        river_density: 0.0,
+        ..WorldGenConfig::default()
+    };
+    let world = WorldGenerator::new(Some(3)).with_config(config).generate(40, 40);
+    assert!(world.tiles().all(|(_, tile)| tile == TerrainTile::Land));
+}
+
+#[test]
+fn test_deposits_follow_biomes_in_clusters() {
+    let world = WorldGenerator::new(Some(42)).generate(128, 128);
+    let deposits: Vec<&ResourceDeposit> = world.deposits().collect();
+    for resource in [ResourceType::Iron, ResourceType::Crystal, ResourceType::Wood] {
+        assert!(deposits.iter().any(|deposit| deposit.resource == resource), "нет месторождений {:?}", resource);
+    }
+    for deposit in &deposits {
+        let tile = world.get_tile(deposit.position.0, deposit.position.1).unwrap();
+        assert!(deposit_resources(&tile).iter().any(|(resource, _)| *resource == deposit.resource));
+        assert!(deposit.richness > 0 && !deposit.is_depleted());
+    }
+    // Большинство месторождений лежит рядом с другими
+    let clustered = deposits
+        .iter()
+        .filter(|deposit| world.deposits_near(deposit.position, 2).count() > 1)
+        .count();
+    assert!(clustered * 10 > deposits.len() * 7);
+
+    let config = WorldGenConfig {
+        resource_density: 0.0,
+        ..WorldGenConfig::default()
+    };
+    assert_eq!(WorldGenerator::new(Some(42)).with_config(config).generate(128, 128).deposits().count(), 0);
+}
+
+#[test]
+fn test_extract_drains_richest_deposit_first() {
+    let mut world = WorldMap::new(10, 10);
+    world.add_deposit(ResourceDeposit::new((2, 2), ResourceType::Iron, 10));
+    world.add_deposit(ResourceDeposit::new((3, 2), ResourceType::Iron, 20));
+    world.add_deposit(ResourceDeposit::new((2, 3), ResourceType::Crystal, 50));
+    world.add_deposit(ResourceDeposit::new((20, 2), ResourceType::Iron, 10));
+    assert_eq!(world.deposits().count(), 3);
+    assert_eq!(world.deposit_richness((0, 0), 2).get(&ResourceType::Iron), Some(&10));
+    assert_eq!(world.deposit_richness((0, 0), 3).get(&ResourceType::Iron), Some(&20));
+
+    let depleted = world.extract((0, 0), 3, &ResourceType::Iron, 450);
+    assert_eq!(depleted.len(), 1);
+    assert_eq!(depleted[0].position, (3, 2));
+    assert_eq!(world.deposit(2, 2).unwrap().remaining, 200 - 50);
+    assert_eq!(world.deposit(2, 3).unwrap().remaining, 1000);
+    assert!(world.extract((0, 0), 3, &ResourceType::Iron, 1000)[0].is_depleted());
+    assert_eq!(world.deposits().count(), 1);
+}
+
+#[test]
+fn test_city_site_follows_surrounding_tiles() {
+    let mut world = WorldMap::new(40, 40);
+    for x in 8..=12 {
+        for y in 8..=12 {
+            world.set_tile(x, y, TerrainTile::Mountain);
+        }
+    }
+    world.set_tile(10, 10, TerrainTile::Land);
+
+    // Горы вокруг: город горный, модификаторы — среднее по клеткам
+    let site = world.survey_site(10, 10).unwrap();
+    assert_eq!(site.terrain, Terrain::Mountain);
+    let side = (2 * CITY_TERRITORY_RADIUS + 1) as usize;
+    assert_eq!(site.territory.len(), side * side);
+    let food = (24.0 * 0.6 + 1.2) / 25.0;
+    assert!((site.modifiers[&ResourceType::Food] - food).abs() < 1e-5);
+    assert!((site.modifiers[&ResourceType::Iron] - (24.0 * 1.3 + 1.0) / 25.0).abs() < 1e-5);
+
+    // Много воды рядом — прибрежный город
+    for y in 28..=32 {
+        world.set_tile(28, y, TerrainTile::Water);
+        world.set_tile(29, y, TerrainTile::Water);
+    }
+    assert_eq!(world.survey_site(30, 30).unwrap().terrain, Terrain::Water);
+    assert_eq!(world.survey_site(20, 20).unwrap().terrain, Terrain::Plain);
+}
+
+#[test]
+fn test_add_city_validates_site_and_claims_territory() {
+    let mut world = WorldMap::new(40, 40);
+    world.set_tile(5, 5, TerrainTile::Water);
+    assert!(world.add_city(5, 5, "sea", "Город".to_string()).is_err());
+    assert!(world.add_city(40, 5, "outside", "Город".to_string()).is_err());
+
+    let site = world.add_city(10, 10, "first", "Первый".to_string()).unwrap();
+    assert_eq!(world.get_tile(10, 10), Some(TerrainTile::City("Первый".to_string())));
+    assert_eq!(world.territory("first"), site.territory);
+    assert_eq!(world.owner(12, 12), Some("first"));
+    assert_eq!(world.owner(13, 12), None);
+
+    // Слишком близко к другому городу
+    let near = 10 + CITY_MIN_DISTANCE - 1;
+    assert_eq!(
+        world.add_city(near, 10, "second", "Второй".to_string()),
+        Err("Слишком близко к городу Первый".to_string())
+    );
+
+    // Новый город не забирает чужие клетки
+    let far = 10 + CITY_MIN_DISTANCE;
+    let site = world.add_city(far, 10, "second", "Второй".to_string()).unwrap();
+    assert!(!site.territory.contains(&(12, 10)));
+    assert_eq!(world.owner(12, 10), Some("first"));
+    assert_eq!(world.owner(far - 1, 10), Some("second"));
+}
@@ -0,0 +1,148 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::building::BuildingType;
+use crate::resources::{Resource, ResourceType};
+
+/// Снижение счастья за каждое здание, недополучившее содержание за ход
+pub const SHORTAGE_HAPPINESS_PENALTY: u32 = 3;
+/// Наибольшее снижение счастья из-за нехватки содержания
+pub const MAX_SHORTAGE_HAPPINESS_PENALTY: u32 = 30;
+
+/// Порядок, в котором здания города получают содержание.
+///
+/// При нехватке ресурсов первыми обеспечиваются здания, стоящие в начале
+/// списка; здания, которых нет в списке, обслуживаются последними.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpkeepPriority {
+    pub order: Vec<BuildingType>,
+}
+
+impl Default for UpkeepPriority {
+    fn default() -> Self {
+        UpkeepPriority {
+            order: vec![
+                BuildingType::Farm,
+                BuildingType::Residential,
+                BuildingType::WaterMill,
+                BuildingType::PowerPlant,
+                BuildingType::Granary,
+                BuildingType::Warehouse,
+                BuildingType::Vault,
+                BuildingType::Wall,
+                BuildingType::Barracks,
+                BuildingType::Temple,
+                BuildingType::School,
+                BuildingType::Market,
+                BuildingType::Bank,
+                BuildingType::Mine,
+                BuildingType::LumberMill,
+                BuildingType::Workshop,
+                BuildingType::Laboratory,
+                BuildingType::CrystalMine,
+            ],
+        }
+    }
+}
+
+impl UpkeepPriority {
+    pub fn new(order: Vec<BuildingType>) -> Self {
+        UpkeepPriority { order }
+    }
+
+    /// Место типа здания в очереди на содержание (меньше — раньше)
+    pub fn rank(&self, building_type: &BuildingType) -> usize {
+        self.order
+            .iter()
+            .position(|t| t == building_type)
+            .unwrap_or(self.order.len())
+    }
+}
+
+/// Запись журнала содержания: сколько здание должно было получить за ход
+/// и сколько получило
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UpkeepEntry {
+    pub building_id: String,
+    pub building_type: BuildingType,
+    pub required: Vec<(ResourceType, u32)>,
+    pub supplied: Vec<(ResourceType, u32)>,
+    /// Доля обеспечения (от 0 до 1); с ней же здание работает в этом ходу
+    pub efficiency: f32,
+}
+
+impl UpkeepEntry {
+    /// Здание получило не все, что требовалось
+    pub fn is_starved(&self) -> bool {
+        self.efficiency < 1.0
+    }
+
+    /// Здание не получило содержания и не работает
+    pub fn is_disabled(&self) -> bool {
+        self.efficiency <= 0.0
+    }
+
+    /// Недополученные ресурсы
+    pub fn missing(&self) -> Vec<(ResourceType, u32)> {
+        self.required
+            .iter()
+            .map(|(resource, required)| {
+                let supplied = self
+                    .supplied
+                    .iter()
+                    .find(|(r, _)| r == resource)
+                    .map_or(0, |(_, amount)| *amount);
+                (resource.clone(), required.saturating_sub(supplied))
+            })
+            .filter(|(_, amount)| *amount > 0)
+            .collect()
+    }
+}
+
+/// Списывает содержание здания с запасов.
+///
+/// Доля обеспечения равна доле самого дефицитного ресурса: здание, которому
+/// хватает половины энергии, работает наполовину и берет половину каждого
+/// ресурса. Без одного из ресурсов здание отключается и ничего не списывается.
+pub fn pay_upkeep(
+    building_id: &str,
+    building_type: &BuildingType,
+    consumption: &BTreeMap<ResourceType, u32>,
+    resources: &mut Resource,
+) -> UpkeepEntry {
+    let mut efficiency: f32 = 1.0;
+    for (resource, required) in consumption {
+        if *required > 0 {
+            efficiency = efficiency.min(resources.get(resource) as f32 / *required as f32);
+        }
+    }
+
+    let mut required = Vec::new();
+    let mut supplied = Vec::new();
+    for (resource, amount) in consumption {
+        let paid = if efficiency >= 1.0 {
+            *amount
+        } else {
+            ((*amount as f32 * efficiency) as u32).min(resources.get(resource))
+        };
+        resources.subtract(resource, paid);
+        required.push((resource.clone(), *amount));
+        supplied.push((resource.clone(), paid));
+    }
+
+    UpkeepEntry {
+        building_id: building_id.to_string(),
+        building_type: building_type.clone(),
+        required,
+        supplied,
+        efficiency,
+    }
+}
+
+/// Снижение счастья из-за нехватки содержания по журналу за ход
+pub fn happiness_penalty(ledger: &[UpkeepEntry]) -> u32 {
+    let starved = ledger.iter().filter(|entry| entry.is_starved()).count() as u32;
+    starved
+        .saturating_mul(SHORTAGE_HAPPINESS_PENALTY)
+        .min(MAX_SHORTAGE_HAPPINESS_PENALTY)
+}
@@ -1,92 +1,533 @@
+use crate::city::Terrain;
+use crate::noise::PerlinNoise;
 use crate::resources::ResourceType;
 use rand::{Rng, SeedableRng, rngs::StdRng};
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Параметры генерации мира.
+///
+/// Высота, влажность и температура каждой клетки берутся из шума Перлина
+/// (значения от 0 до 1), а биом выбирается по порогам ниже.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorldGenConfig {
+    /// Размер крупных форм рельефа (материков, хребтов) в клетках
+    pub scale: f64,
+    /// Число слоев шума: чем больше, тем изрезаннее берега
+    pub octaves: u32,
+    /// Во сколько раз слабее каждый следующий слой шума
+    pub persistence: f64,
+    /// Во сколько раз мельче каждый следующий слой шума
+    pub lacunarity: f64,
+    /// Насколько высота опускается к краям карты: края становятся океаном
+    pub edge_falloff: f64,
+    /// Клетки ниже этой высоты — вода
+    pub sea_level: f64,
+    /// Клетки выше этой высоты — горы
+    pub mountain_level: f64,
+    /// Клетки жарче этой температуры и суше `desert_moisture` — пустыня
+    pub desert_temperature: f64,
+    pub desert_moisture: f64,
+    /// Клетки влажнее этого значения — лес
+    pub forest_moisture: f64,
+    /// Число рек на клетку карты
+    pub river_density: f64,
+    /// Доля гор и лесов, занятых месторождениями
+    pub resource_density: f64,
+    /// Радиус скопления месторождений вокруг его центра
+    pub deposit_cluster_radius: i32,
+}
+
+impl Default for WorldGenConfig {
+    fn default() -> Self {
+        WorldGenConfig {
+            scale: 48.0,
+            octaves: 5,
+            persistence: 0.5,
+            lacunarity: 2.0,
+            edge_falloff: 0.25,
+            sea_level: 0.4,
+            mountain_level: 0.58,
+            desert_temperature: 0.6,
+            desert_moisture: 0.45,
+            forest_moisture: 0.55,
+            river_density: 0.0004,
+            resource_density: 0.08,
+            deposit_cluster_radius: 2,
+        }
+    }
+}
+
+impl WorldGenConfig {
+    /// Значение шума в точке с октавами из параметров
+    fn sample(&self, noise: &PerlinNoise, x: f64, y: f64) -> f64 {
+        noise.fractal(x, y, self.octaves, self.persistence, self.lacunarity)
+    }
+}
+
+/// Поля шума для одной карты: высота, влажность, температура по клеткам
+struct Climate {
+    width: usize,
+    height: Vec<f64>,
+    moisture: Vec<f64>,
+    temperature: Vec<f64>,
+}
+
+impl Climate {
+    fn index(&self, x: i32, y: i32) -> usize {
+        y as usize * self.width + x as usize
+    }
+}
 
 pub struct WorldGenerator {
-    _seed: u64,
+    seed: u64,
+    config: WorldGenConfig,
     rng: StdRng,
 }
 
 impl WorldGenerator {
     pub fn new(seed: Option<u64>) -> Self {
-        let seed = seed.unwrap_or_else(|| rand::random());
+        let seed = seed.unwrap_or_else(rand::random);
         let rng = StdRng::seed_from_u64(seed);
 
-        WorldGenerator { _seed: seed, rng }
+        WorldGenerator {
+            seed,
+            config: WorldGenConfig::default(),
+            rng,
+        }
+    }
+
+    /// Задает параметры генерации
+    pub fn with_config(mut self, config: WorldGenConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn config(&self) -> &WorldGenConfig {
+        &self.config
     }
 
     pub fn generate(&mut self, width: u64, height: u64) -> WorldMap {
         let mut world = WorldMap::new(width, height);
 
-        // Генерация водоемов
-        self.generate_water_bodies(&mut world);
+        // Рельеф и биомы
+        let climate = self.generate_climate(width as usize, height as usize);
+        self.generate_biomes(&mut world, &climate);
 
-        // Генерация ресурсов
-        self.generate_resources(&mut world);
+        // Реки стекают с гор к морю
+        self.generate_rivers(&mut world, &climate);
+
+        // Месторождения скоплениями в горах и лесах
+        self.generate_deposits(&mut world);
 
         world
     }
 
-    fn generate_water_bodies(&mut self, world: &mut WorldMap) {
-        let water_bodies_count = (world.get_width() * world.get_height()) as f64 * 0.05;
+    fn generate_climate(&self, width: usize, height: usize) -> Climate {
+        let config = &self.config;
+        // Каждое поле — свой шум из зерна мира
+        let elevation = PerlinNoise::new(self.seed);
+        let moisture_noise = PerlinNoise::new(self.seed.wrapping_add(1));
+        let temperature_noise = PerlinNoise::new(self.seed.wrapping_add(2));
+        let scale = config.scale.max(1.0);
+
+        let mut climate = Climate {
+            width,
+            height: Vec::with_capacity(width * height),
+            moisture: Vec::with_capacity(width * height),
+            temperature: Vec::with_capacity(width * height),
+        };
+        for y in 0..height {
+            for x in 0..width {
+                let (nx, ny) = (x as f64 / scale, y as f64 / scale);
+
+                // Расстояние до края: 0 в центре, 1 на краю
+                let dx = 2.0 * (x as f64 + 0.5) / width as f64 - 1.0;
+                let dy = 2.0 * (y as f64 + 0.5) / height as f64 - 1.0;
+                let edge = dx.abs().max(dy.abs());
+                let base = config.sample(&elevation, nx, ny);
+                let elevation = (base - config.edge_falloff * edge.powi(6)).clamp(0.0, 1.0);
+
+                let moisture = config.sample(&moisture_noise, nx, ny);
+
+                // Жарко у экватора (середина карты по высоте), холодно у полюсов и в горах
+                let latitude = 1.0 - dy.abs();
+                let variation = config.sample(&temperature_noise, nx, ny);
+                let altitude = (elevation - config.sea_level).max(0.0);
+                let temperature =
+                    (0.6 * latitude + 0.4 * variation - 0.3 * altitude).clamp(0.0, 1.0);
+
+                climate.height.push(elevation);
+                climate.moisture.push(moisture);
+                climate.temperature.push(temperature);
+            }
+        }
+        climate
+    }
+
+    fn generate_biomes(&self, world: &mut WorldMap, climate: &Climate) {
+        let config = &self.config;
+        for y in 0..world.get_height() as i32 {
+            for x in 0..world.get_width() as i32 {
+                let index = climate.index(x, y);
+                let elevation = climate.height[index];
+                let moisture = climate.moisture[index];
+                let temperature = climate.temperature[index];
 
-        for _ in 0..water_bodies_count as u64 {
-            let x = self.rng.random_range(0..world.get_width()) as i32;
-            let y = self.rng.random_range(0..world.get_height()) as i32;
+                let tile = if elevation < config.sea_level {
+                    TerrainTile::Water
+                } else if elevation > config.mountain_level {
+                    TerrainTile::Mountain
+                } else if temperature > config.desert_temperature
+                    && moisture < config.desert_moisture
+                {
+                    TerrainTile::Desert
+                } else if moisture > config.forest_moisture {
+                    TerrainTile::Forest
+                } else {
+                    TerrainTile::Land
+                };
+                if tile != TerrainTile::Land {
+                    world.set_tile(x, y, tile);
+                }
+            }
+        }
+    }
 
-            // Создаем водоем разного размера
-            let size = self.rng.random_range(3..10);
-            self.create_water_body(world, x, y, size);
+    /// Реки начинаются в горах и текут вниз по склону, пока не дойдут до
+    /// воды или низины, из которой некуда стекать
+    fn generate_rivers(&mut self, world: &mut WorldMap, climate: &Climate) {
+        let (width, height) = (world.get_width() as i32, world.get_height() as i32);
+        if width == 0 || height == 0 {
+            return;
+        }
+        let rivers = ((width as f64 * height as f64) * self.config.river_density).round() as u64;
+        let max_length = (width + height) as usize;
+
+        for _ in 0..rivers {
+            // Ищем исток в горах
+            let Some(source) = (0..100).find_map(|_| {
+                let position = (
+                    self.rng.random_range(0..width),
+                    self.rng.random_range(0..height),
+                );
+                (world.get_tile(position.0, position.1) == Some(TerrainTile::Mountain))
+                    .then_some(position)
+            }) else {
+                continue;
+            };
+
+            let (mut x, mut y) = source;
+            for _ in 0..max_length {
+                world.set_tile(x, y, TerrainTile::Water);
+                let lowest = [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)]
+                    .into_iter()
+                    .filter(|(nx, ny)| world.in_bounds(*nx, *ny))
+                    .min_by(|a, b| {
+                        climate.height[climate.index(a.0, a.1)]
+                            .total_cmp(&climate.height[climate.index(b.0, b.1)])
+                    });
+                let Some((nx, ny)) = lowest else {
+                    break;
+                };
+                if climate.height[climate.index(nx, ny)] >= climate.height[climate.index(x, y)] {
+                    break;
+                }
+                if world.get_tile(nx, ny) == Some(TerrainTile::Water) {
+                    break;
+                }
+                (x, y) = (nx, ny);
+            }
         }
     }
 
-    fn create_water_body(&mut self, world: &mut WorldMap, x: i32, y: i32, size: u32) {
-        for dx in -(size as i32)..=(size as i32) {
-            for dy in -(size as i32)..=(size as i32) {
-                let distance = ((dx * dx + dy * dy) as f64).sqrt();
-                if distance <= size as f64 {
-                    let noise = self.rng.random_range(-1.5..1.5);
-                    if distance + noise <= size as f64 {
-                        let nx = x + dx;
-                        let ny = y + dy;
+    /// Месторождения лежат скоплениями: в центре скопления богаче, к краям
+    /// беднее. Вид ресурса зависит от биома центра скопления
+    /// (`deposit_resources`), а месторождения ставятся только на клетки того же биома.
+    fn generate_deposits(&mut self, world: &mut WorldMap) {
+        let suitable: Vec<((i32, i32), TerrainTile)> = world
+            .find_tiles(|tile| !deposit_resources(tile).is_empty())
+            .collect();
+        let target = (suitable.len() as f64 * self.config.resource_density) as usize;
+        let radius = self.config.deposit_cluster_radius.max(0);
+
+        let mut placed = 0;
+        let mut clusters = 0;
+        while placed < target && clusters < target {
+            clusters += 1;
+            let (center, biome) = &suitable[self.rng.random_range(0..suitable.len())];
+            let resources = deposit_resources(biome);
+            let total: u32 = resources.iter().map(|(_, weight)| weight).sum();
+            let mut roll = self.rng.random_range(0..total);
+            let Some((resource, _)) = resources.iter().find(|(_, weight)| {
+                let found = roll < *weight;
+                roll = roll.saturating_sub(*weight);
+                found
+            }) else {
+                continue;
+            };
+            let peak: u32 = self.rng.random_range(DEPOSIT_RICHNESS.clone());
 
-                        if nx >= 0
-                            && nx < world.get_width() as i32
-                            && ny >= 0
-                            && ny < world.get_height() as i32
-                        {
-                            world.set_tile(nx, ny, TerrainTile::Water);
-                        }
+            for dx in -radius..=radius {
+                for dy in -radius..=radius {
+                    let distance = dx.abs() + dy.abs();
+                    let (x, y) = (center.0 + dx, center.1 + dy);
+                    if distance > radius
+                        || world.get_tile(x, y).as_ref() != Some(biome)
+                        || world.deposit(x, y).is_some()
+                    {
+                        continue;
                     }
+                    // Чем дальше от центра, тем реже и беднее месторождения
+                    let share = (radius + 1 - distance) as u32;
+                    if self.rng.random_range(0..=radius as u32) >= share {
+                        continue;
+                    }
+                    let richness =
+                        (peak * share / (radius as u32 + 1)).max(*DEPOSIT_RICHNESS.start() / 2);
+                    world.add_deposit(ResourceDeposit::new((x, y), resource.clone(), richness));
+                    placed += 1;
                 }
             }
         }
     }
+}
 
-    fn generate_resources(&mut self, world: &mut WorldMap) {
-        // Генерация областей с ресурсами (можно привязать к структуре из resources.rs)
-        let resource_spots = (world.get_width() * world.get_height()) as f64 * 0.03;
+/// Ресурсы месторождений на клетке биома и их относительные веса:
+/// железо и кристаллы в горах, дерево в лесах
+pub fn deposit_resources(tile: &TerrainTile) -> &'static [(ResourceType, u32)] {
+    match tile {
+        TerrainTile::Mountain => &[(ResourceType::Iron, 3), (ResourceType::Crystal, 1)],
+        TerrainTile::Forest => &[(ResourceType::Wood, 1)],
+        _ => &[],
+    }
+}
 
-        for _ in 0..resource_spots as u64 {
-            let x = self.rng.random_range(0..world.get_width()) as i32;
-            let y = self.rng.random_range(0..world.get_height()) as i32;
+/// Богатство месторождения в центре скопления, в процентах бонуса к добыче
+pub const DEPOSIT_RICHNESS: std::ops::RangeInclusive<u32> = 50..=150;
+/// Запас месторождения на каждый процент богатства
+pub const DEPOSIT_UNITS_PER_RICHNESS: u32 = 20;
+/// На каком расстоянии от города (по каждой оси) его здания добывают из месторождений
+pub const DEPOSIT_REACH: i32 = 3;
 
-            // Проверяем, что это не вода
-            if let Some(TerrainTile::Land) = world.get_tile(x, y) {
-                // Здесь можно расширить: добавить enum с типами ресурсов в TerrainTile
-                // и генерировать разные ресурсы (например, дерево, камень, золото)
-                world.set_tile(x, y, TerrainTile::ResourceSpot(ResourceType::default()));
-            }
+/// Месторождение ресурса на карте мира
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceDeposit {
+    pub position: (i32, i32),
+    pub resource: ResourceType,
+    /// Бонус к добыче зданий города в процентах
+    pub richness: u32,
+    /// Сколько ресурса осталось
+    pub remaining: u32,
+}
+
+impl ResourceDeposit {
+    /// Месторождение с запасом, пропорциональным богатству
+    pub fn new(position: (i32, i32), resource: ResourceType, richness: u32) -> Self {
+        ResourceDeposit {
+            position,
+            resource,
+            richness,
+            remaining: richness * DEPOSIT_UNITS_PER_RICHNESS,
         }
     }
+
+    pub fn is_depleted(&self) -> bool {
+        self.remaining == 0
+    }
 }
 
+/// Радиус территории, которую город получает при основании
+pub const CITY_TERRITORY_RADIUS: i32 = 2;
+/// Ближе этого расстояния (по каждой оси) к другому городу основать город нельзя
+pub const CITY_MIN_DISTANCE: i32 = 4;
+/// Сколько клеток воды в окрестностях делают город прибрежным (`Terrain::Water`)
+pub const COASTAL_WATER_TILES: usize = 6;
+
+/// Место для города на карте мира: местность и модификаторы ресурсов,
+/// выведенные из клеток в радиусе `CITY_TERRITORY_RADIUS`
+#[derive(Debug, Clone, PartialEq)]
+pub struct CitySite {
+    pub position: (i32, i32),
+    /// Преобладающая местность суши или `Terrain::Water` для прибрежного города
+    pub terrain: Terrain,
+    /// Средние модификаторы ресурсов местности окрестных клеток
+    pub modifiers: BTreeMap<ResourceType, f32>,
+    /// Клетки, которые город получит, по возрастанию позиции; клетки других городов не входят
+    pub territory: Vec<(i32, i32)>,
+}
+
+/// Сторона квадратного чанка карты в клетках
+pub const CHUNK_SIZE: i32 = 32;
+/// Число клеток в чанке
+const CHUNK_AREA: usize = (CHUNK_SIZE * CHUNK_SIZE) as usize;
+
+/// Карта мира.
+///
+/// Клетки хранятся чанками `CHUNK_SIZE`×`CHUNK_SIZE`. Чанк, все клетки
+/// которого одинаковы (например, неисследованная область), хранит одну
+/// клетку и не выделяет память под остальные; плотный массив появляется
+/// при первом изменении клетки. Каждая клетка занимает 4 байта: имена
+/// зданий и городов хранятся один раз в таблице имен карты.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "WorldMapData")]
 pub struct WorldMap {
     width: u64,
     height: u64,
-    terrain: HashMap<(i32, i32), TerrainTile>,
+    /// Чанки по строкам
+    chunks: Vec<Chunk>,
+    /// Имена зданий и городов; клетка хранит индекс имени
+    names: Vec<String>,
+    #[serde(skip_serializing)]
+    name_ids: HashMap<String, u32>,
+    /// Месторождения по позициям
+    #[serde(serialize_with = "serialize_deposits")]
+    deposits: BTreeMap<(i32, i32), ResourceDeposit>,
+    /// Владельцы клеток: позиция -> ID города
+    #[serde(serialize_with = "serialize_territory")]
+    territory: BTreeMap<(i32, i32), String>,
+    /// Клетки каждого города; строится по `territory` и не сохраняется
+    #[serde(skip_serializing)]
+    city_tiles: BTreeMap<String, BTreeSet<(i32, i32)>>,
+    /// Позиции городов по ID
+    cities: BTreeMap<String, (i32, i32)>,
+}
+
+/// Формат сохранения карты
+#[derive(Deserialize)]
+struct WorldMapData {
+    width: u64,
+    height: u64,
+    chunks: Vec<Chunk>,
+    names: Vec<String>,
+    #[serde(default)]
+    deposits: Vec<ResourceDeposit>,
+    #[serde(default)]
+    territory: Vec<((i32, i32), String)>,
+    #[serde(default)]
+    cities: BTreeMap<String, (i32, i32)>,
+}
+
+/// Месторождения сохраняются списком: позиция хранится в самом месторождении
+fn serialize_deposits<S: Serializer>(
+    deposits: &BTreeMap<(i32, i32), ResourceDeposit>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(deposits.values())
+}
+
+/// Владельцы клеток сохраняются списком пар (позиция, ID города)
+fn serialize_territory<S: Serializer>(
+    territory: &BTreeMap<(i32, i32), String>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(territory.iter())
+}
+
+/// Последняя координата `i32` вдоль стороны карты длиной `length`
+/// (-1 для пустой стороны)
+fn last_coordinate(length: u64) -> i32 {
+    length.min(i32::MAX as u64) as i32 - 1
+}
+
+/// Входит ли клетка в карту размером `width` x `height`
+fn within(width: u64, height: u64, x: i32, y: i32) -> bool {
+    x >= 0 && y >= 0 && (x as u64) < width && (y as u64) < height
+}
+
+impl TryFrom<WorldMapData> for WorldMap {
+    type Error = String;
+
+    /// Проверяет сохранение: размеры карты укладываются в координаты `i32`,
+    /// число чанков соответствует размерам, плотные чанки заполнены целиком,
+    /// виды клеток известны, индексы имен и ресурсов существуют, а
+    /// месторождения, территория и города лежат на карте
+    fn try_from(data: WorldMapData) -> Result<Self, Self::Error> {
+        if data.width > i32::MAX as u64 || data.height > i32::MAX as u64 {
+            return Err(format!("Размеры карты {}x{} превышают допустимые", data.width, data.height));
+        }
+        let expected = chunk_count(data.width)
+            .checked_mul(chunk_count(data.height))
+            .ok_or_else(|| format!("Карта {}x{} содержит слишком много чанков", data.width, data.height))?;
+        if data.chunks.len() != expected {
+            return Err(format!(
+                "Карта {}x{} должна содержать {} чанков, а не {}",
+                data.width,
+                data.height,
+                expected,
+                data.chunks.len()
+            ));
+        }
+        for (index, chunk) in data.chunks.iter().enumerate() {
+            let cells = match chunk {
+                Chunk::Uniform(cell) => std::slice::from_ref(cell),
+                Chunk::Dense(cells) if cells.len() != CHUNK_AREA => {
+                    return Err(format!(
+                        "Чанк {} содержит {} клеток вместо {}",
+                        index,
+                        cells.len(),
+                        CHUNK_AREA
+                    ));
+                }
+                Chunk::Dense(cells) => cells.as_slice(),
+            };
+            for cell in cells {
+                let limit = match cell.kind() {
+                    Cell::BUILDING | Cell::CITY => data.names.len(),
+                    Cell::RESOURCE_SPOT => ResourceType::ALL.len(),
+                    Cell::LAND..=Cell::DESERT | Cell::UNKNOWN => continue,
+                    kind => return Err(format!("Чанк {} содержит клетку неизвестного вида {}", index, kind)),
+                };
+                if cell.index() as usize >= limit {
+                    return Err(format!("Чанк {} ссылается на несуществующий индекс {}", index, cell.index()));
+                }
+            }
+        }
+
+        let positions = data
+            .deposits
+            .iter()
+            .map(|deposit| deposit.position)
+            .chain(data.territory.iter().map(|(position, _)| *position))
+            .chain(data.cities.values().copied());
+        for (x, y) in positions {
+            if !within(data.width, data.height, x, y) {
+                return Err(format!("Позиция ({}, {}) лежит за пределами карты", x, y));
+            }
+        }
+
+        let name_ids = data
+            .names
+            .iter()
+            .enumerate()
+            .map(|(id, name)| (name.clone(), id as u32))
+            .collect();
+        let territory: BTreeMap<(i32, i32), String> = data.territory.into_iter().collect();
+        let mut city_tiles: BTreeMap<String, BTreeSet<(i32, i32)>> = BTreeMap::new();
+        for (position, city_id) in &territory {
+            city_tiles.entry(city_id.clone()).or_default().insert(*position);
+        }
+        Ok(WorldMap {
+            width: data.width,
+            height: data.height,
+            chunks: data.chunks,
+            names: data.names,
+            name_ids,
+            deposits: data
+                .deposits
+                .into_iter()
+                .map(|deposit| (deposit.position, deposit))
+                .collect(),
+            territory,
+            city_tiles,
+            cities: data.cities,
+        })
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -102,21 +543,98 @@ pub enum TerrainTile {
     Unknown,
 }
 
-impl WorldMap {
-    pub fn new(width: u64, height: u64) -> WorldMap {
-        let mut terrain = HashMap::new();
+impl TerrainTile {
+    /// Местность города, соответствующая клетке; у построек и
+    /// неисследованных клеток местности нет
+    pub fn terrain(&self) -> Option<Terrain> {
+        match self {
+            TerrainTile::Land => Some(Terrain::Plain),
+            TerrainTile::Water => Some(Terrain::Water),
+            TerrainTile::Mountain => Some(Terrain::Mountain),
+            TerrainTile::Forest => Some(Terrain::Forest),
+            TerrainTile::Desert => Some(Terrain::Desert),
+            _ => None,
+        }
+    }
+}
 
-        // Заполнение карты начальными значениями
-        for x in 0..width as i32 {
-            for y in 0..height as i32 {
-                terrain.insert((x, y), TerrainTile::Land);
+/// Клетка в плотном хранилище: вид клетки в старшем байте,
+/// индекс имени или ресурса — в младших трех
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct Cell(u32);
+
+impl Cell {
+    const LAND: u8 = 0;
+    const WATER: u8 = 1;
+    const MOUNTAIN: u8 = 2;
+    const FOREST: u8 = 3;
+    const DESERT: u8 = 4;
+    const BUILDING: u8 = 5;
+    const RESOURCE_SPOT: u8 = 6;
+    const CITY: u8 = 7;
+    const UNKNOWN: u8 = 8;
+
+    /// Наибольший индекс, который помещается в клетку
+    const MAX_INDEX: u32 = 0x00FF_FFFF;
+
+    fn new(kind: u8, index: u32) -> Self {
+        assert!(index <= Cell::MAX_INDEX, "Индекс {} не помещается в клетку", index);
+        Cell(((kind as u32) << 24) | index)
+    }
+
+    fn kind(self) -> u8 {
+        (self.0 >> 24) as u8
+    }
+
+    fn index(self) -> u32 {
+        self.0 & Cell::MAX_INDEX
+    }
+}
+
+/// Чанк карты
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+enum Chunk {
+    /// Все клетки чанка одинаковы
+    Uniform(Cell),
+    /// Клетки чанка по строкам
+    Dense(Vec<Cell>),
+}
+
+impl Chunk {
+    fn get(&self, offset: usize) -> Cell {
+        match self {
+            Chunk::Uniform(cell) => *cell,
+            Chunk::Dense(cells) => cells[offset],
+        }
+    }
+
+    fn set(&mut self, offset: usize, cell: Cell) {
+        match self {
+            Chunk::Uniform(fill) if *fill == cell => {}
+            Chunk::Uniform(fill) => {
+                let mut cells = vec![*fill; CHUNK_AREA];
+                cells[offset] = cell;
+                *self = Chunk::Dense(cells);
             }
+            Chunk::Dense(cells) => cells[offset] = cell,
         }
+    }
+}
 
+impl WorldMap {
+    pub fn new(width: u64, height: u64) -> WorldMap {
+        // Заполнение карты начальными значениями
+        let count = chunk_count(width) * chunk_count(height);
         WorldMap {
             width,
             height,
-            terrain,
+            chunks: vec![Chunk::Uniform(Cell::new(Cell::LAND, 0)); count],
+            names: Vec::new(),
+            name_ids: HashMap::new(),
+            deposits: BTreeMap::new(),
+            territory: BTreeMap::new(),
+            city_tiles: BTreeMap::new(),
+            cities: BTreeMap::new(),
         }
     }
 
@@ -129,44 +647,387 @@ impl WorldMap {
     }
 
     pub fn expand_map(&mut self, new_width: u64, new_height: u64) {
-        for x in self.width..new_width {
-            for y in 0..self.height {
-                self.terrain
-                    .insert((x as i32, y as i32), TerrainTile::Unknown);
-            }
-        }
+        let (old_width, old_height) = (self.width, self.height);
+        let new_width = new_width.max(old_width);
+        let new_height = new_height.max(old_height);
+        let old_columns = chunk_count(old_width);
+        let columns = chunk_count(new_width);
+        let rows = chunk_count(new_height);
 
-        for y in self.height..new_height {
-            for x in 0..new_width {
-                self.terrain
-                    .insert((x as i32, y as i32), TerrainTile::Unknown);
-            }
+        let unknown = Cell::new(Cell::UNKNOWN, 0);
+        let mut chunks = vec![Chunk::Uniform(unknown); columns * rows];
+        for (index, chunk) in std::mem::take(&mut self.chunks).into_iter().enumerate() {
+            chunks[(index / old_columns) * columns + index % old_columns] = chunk;
         }
-
+        self.chunks = chunks;
         self.width = new_width;
         self.height = new_height;
+
+        // Новые клетки в чанках, которые уже частично входили в карту
+        let covered_x = (old_columns as i32 * CHUNK_SIZE).min(new_width as i32);
+        let covered_y = (chunk_count(old_height) as i32 * CHUNK_SIZE).min(new_height as i32);
+        for y in 0..covered_y {
+            for x in 0..covered_x {
+                if x as u64 >= old_width || y as u64 >= old_height {
+                    self.set_cell(x, y, unknown);
+                }
+            }
+        }
     }
 
     pub fn set_tile(&mut self, x: i32, y: i32, tile: TerrainTile) {
-        if x >= 0 && x < self.width as i32 && y >= 0 && y < self.height as i32 {
-            self.terrain.insert((x, y), tile);
+        if self.in_bounds(x, y) {
+            let cell = self.encode(tile);
+            self.set_cell(x, y, cell);
         }
     }
 
-    pub fn get_tile(&self, x: i32, y: i32) -> Option<&TerrainTile> {
-        self.terrain.get(&(x, y))
+    pub fn get_tile(&self, x: i32, y: i32) -> Option<TerrainTile> {
+        self.cell(x, y).map(|cell| self.decode(cell))
     }
 
     pub fn add_building(&mut self, x: i32, y: i32, building_name: String) {
-        if let Some(_) = self.terrain.get(&(x, y)) {
-            self.terrain
-                .insert((x, y), TerrainTile::Building(building_name));
+        self.set_tile(x, y, TerrainTile::Building(building_name));
+    }
+
+    /// Основывает город `city_id` в клетке: проверяет место (`survey_site`),
+    /// ставит на клетку город и отдает ему территорию места
+    pub fn add_city(&mut self, x: i32, y: i32, city_id: &str, city_name: String) -> Result<CitySite, String> {
+        let site = self.survey_site(x, y)?;
+        self.set_tile(x, y, TerrainTile::City(city_name));
+        self.cities.insert(city_id.to_string(), (x, y));
+        for (x, y) in &site.territory {
+            self.claim(*x, *y, city_id);
+        }
+        Ok(site)
+    }
+
+    /// Проверяет место для города и выводит его местность из окрестностей.
+    ///
+    /// Город нельзя основать на воде, за пределами карты, на клетке с
+    /// постройкой, на чужой территории и ближе `CITY_MIN_DISTANCE` к другому городу.
+    pub fn survey_site(&self, x: i32, y: i32) -> Result<CitySite, String> {
+        let tile = self.get_tile(x, y).ok_or("Позиция за пределами карты")?;
+        match tile {
+            TerrainTile::Water => return Err("Нельзя основать город на воде".to_string()),
+            TerrainTile::Land | TerrainTile::Mountain | TerrainTile::Forest | TerrainTile::Desert => {}
+            _ => return Err("Клетка занята или не исследована".to_string()),
+        }
+        if self.owner(x, y).is_some() {
+            return Err("Клетка принадлежит другому городу".to_string());
+        }
+        let reach = CITY_MIN_DISTANCE - 1;
+        if let Some((_, TerrainTile::City(name))) = self
+            .region((x - reach, y - reach), (x + reach, y + reach))
+            .find(|(_, tile)| matches!(tile, TerrainTile::City(_)))
+        {
+            return Err(format!("Слишком близко к городу {}", name));
+        }
+
+        let radius = CITY_TERRITORY_RADIUS;
+        let surroundings: Vec<((i32, i32), Terrain)> = self
+            .region((x - radius, y - radius), (x + radius, y + radius))
+            .filter_map(|(position, tile)| tile.terrain().map(|terrain| (position, terrain)))
+            .collect();
+
+        // Преобладающая местность суши; при равенстве — в порядке перечисления
+        let land = [Terrain::Plain, Terrain::Forest, Terrain::Mountain, Terrain::Desert];
+        let count = |terrain: &Terrain| surroundings.iter().filter(|(_, other)| other == terrain).count();
+        let mut terrain = land
+            .iter()
+            .rev()
+            .max_by_key(|terrain| count(terrain))
+            .cloned()
+            .unwrap_or(Terrain::Plain);
+        if count(&Terrain::Water) >= COASTAL_WATER_TILES {
+            terrain = Terrain::Water;
+        }
+
+        // Модификаторы — среднее по окрестным клеткам, отсутствующий модификатор равен 1
+        let mut modifiers = BTreeMap::new();
+        for (_, tile_terrain) in &surroundings {
+            for resource in tile_terrain.resource_modifier().into_keys() {
+                modifiers.insert(resource, 0.0);
+            }
+        }
+        for (resource, total) in modifiers.iter_mut() {
+            let sum: f32 = surroundings
+                .iter()
+                .map(|(_, tile_terrain)| tile_terrain.resource_modifier().get(resource).copied().unwrap_or(1.0))
+                .sum();
+            *total = sum / surroundings.len() as f32;
+        }
+
+        let mut territory: Vec<(i32, i32)> = self
+            .region((x - radius, y - radius), (x + radius, y + radius))
+            .map(|(position, _)| position)
+            .filter(|position| self.owner(position.0, position.1).is_none())
+            .collect();
+        territory.sort();
+
+        Ok(CitySite {
+            position: (x, y),
+            terrain,
+            modifiers,
+            territory,
+        })
+    }
+
+    /// ID города, которому принадлежит клетка
+    pub fn owner(&self, x: i32, y: i32) -> Option<&str> {
+        self.territory.get(&(x, y)).map(String::as_str)
+    }
+
+    /// Клетки города по возрастанию позиции
+    pub fn territory(&self, city_id: &str) -> Vec<(i32, i32)> {
+        self.city_tiles
+            .get(city_id)
+            .map(|tiles| tiles.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Позиция города, основанного через `add_city`
+    pub fn city_position(&self, city_id: &str) -> Option<(i32, i32)> {
+        self.cities.get(city_id).copied()
+    }
+
+    /// ID городов на карте по возрастанию
+    pub fn city_ids(&self) -> impl Iterator<Item = &str> + '_ {
+        self.cities.keys().map(String::as_str)
+    }
+
+    /// Передает клетку городу; возвращает прежнего владельца.
+    /// Позиции за пределами карты игнорируются.
+    pub fn claim(&mut self, x: i32, y: i32, city_id: &str) -> Option<String> {
+        if !self.in_bounds(x, y) {
+            return None;
+        }
+        let previous = self.territory.insert((x, y), city_id.to_string());
+        if let Some(previous) = &previous
+            && let Some(tiles) = self.city_tiles.get_mut(previous)
+        {
+            tiles.remove(&(x, y));
+        }
+        self.city_tiles.entry(city_id.to_string()).or_default().insert((x, y));
+        previous
+    }
+
+    /// Клетки, граничащие (по сторонам) с территорией города, но не его:
+    /// свободные и чужие. Неисследованные клетки не входят.
+    pub fn frontier(&self, city_id: &str) -> Vec<(i32, i32)> {
+        let mut frontier: Vec<(i32, i32)> = self
+            .territory(city_id)
+            .into_iter()
+            .flat_map(|(x, y)| [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)])
+            .filter(|(x, y)| self.owner(*x, *y) != Some(city_id))
+            .filter(|(x, y)| self.get_tile(*x, *y).is_some_and(|tile| tile != TerrainTile::Unknown))
+            .collect();
+        frontier.sort();
+        frontier.dedup();
+        frontier
+    }
+
+    /// Граница между территориями городов `first` и `second`: клетки каждого
+    /// из них, граничащие (по сторонам) с клетками другого
+    pub fn border_between(&self, first: &str, second: &str) -> Vec<(i32, i32)> {
+        let tiles = |city_id: &str| self.city_tiles.get(city_id).into_iter().flatten().copied();
+        let touches = |(x, y): (i32, i32), other: &str| {
+            [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)]
+                .iter()
+                .any(|(nx, ny)| self.owner(*nx, *ny) == Some(other))
+        };
+        let mut border: Vec<(i32, i32)> = tiles(first)
+            .filter(|position| touches(*position, second))
+            .chain(tiles(second).filter(|position| touches(*position, first)))
+            .collect();
+        border.sort();
+        border
+    }
+
+    /// Входит ли клетка в карту
+    pub fn in_bounds(&self, x: i32, y: i32) -> bool {
+        within(self.width, self.height, x, y)
+    }
+
+    /// Все клетки карты по строкам
+    pub fn tiles(&self) -> impl Iterator<Item = ((i32, i32), TerrainTile)> + '_ {
+        self.region((0, 0), (last_coordinate(self.width), last_coordinate(self.height)))
+    }
+
+    /// Клетки прямоугольника от `min` до `max` включительно (по строкам);
+    /// клетки за пределами карты пропускаются
+    pub fn region(&self, min: (i32, i32), max: (i32, i32)) -> impl Iterator<Item = ((i32, i32), TerrainTile)> + '_ {
+        let min_x = min.0.max(0);
+        let min_y = min.1.max(0);
+        let max_x = max.0.min(last_coordinate(self.width));
+        let max_y = max.1.min(last_coordinate(self.height));
+        (min_y..=max_y).flat_map(move |y| {
+            (min_x..=max_x).filter_map(move |x| self.get_tile(x, y).map(|tile| ((x, y), tile)))
+        })
+    }
+
+    /// Клетки, подходящие под условие, по строкам
+    pub fn find_tiles<'a>(
+        &'a self,
+        predicate: impl Fn(&TerrainTile) -> bool + 'a,
+    ) -> impl Iterator<Item = ((i32, i32), TerrainTile)> + 'a {
+        self.tiles().filter(move |(_, tile)| predicate(tile))
+    }
+
+    /// Месторождение в клетке
+    pub fn deposit(&self, x: i32, y: i32) -> Option<&ResourceDeposit> {
+        self.deposits.get(&(x, y))
+    }
+
+    /// Добавляет или заменяет месторождение; позиции за пределами карты игнорируются
+    pub fn add_deposit(&mut self, deposit: ResourceDeposit) {
+        if self.in_bounds(deposit.position.0, deposit.position.1) {
+            self.deposits.insert(deposit.position, deposit);
+        }
+    }
+
+    /// Все месторождения по возрастанию позиции
+    pub fn deposits(&self) -> impl Iterator<Item = &ResourceDeposit> + '_ {
+        self.deposits.values()
+    }
+
+    /// Месторождения в квадрате со стороной `2 * radius + 1` вокруг `center`
+    pub fn deposits_near(&self, center: (i32, i32), radius: i32) -> impl Iterator<Item = &ResourceDeposit> + '_ {
+        let (x, y) = center;
+        self.deposits
+            .range((x - radius, i32::MIN)..=(x + radius, i32::MAX))
+            .map(|(_, deposit)| deposit)
+            .filter(move |deposit| (deposit.position.1 - y).abs() <= radius)
+    }
+
+    /// Богатство самого богатого месторождения каждого ресурса рядом с `center`
+    pub fn deposit_richness(&self, center: (i32, i32), radius: i32) -> BTreeMap<ResourceType, i32> {
+        let mut richness = BTreeMap::new();
+        for deposit in self.deposits_near(center, radius) {
+            let best = richness.entry(deposit.resource.clone()).or_insert(0);
+            *best = (*best).max(deposit.richness as i32);
+        }
+        richness
+    }
+
+    /// Добывает `amount` ресурса из месторождений рядом с `center`, начиная
+    /// с самого богатого. Исчерпанные месторождения убираются с карты и
+    /// возвращаются.
+    pub fn extract(
+        &mut self,
+        center: (i32, i32),
+        radius: i32,
+        resource: &ResourceType,
+        mut amount: u32,
+    ) -> Vec<ResourceDeposit> {
+        let mut positions: Vec<(u32, (i32, i32))> = self
+            .deposits_near(center, radius)
+            .filter(|deposit| deposit.resource == *resource)
+            .map(|deposit| (deposit.richness, deposit.position))
+            .collect();
+        positions.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+
+        let mut depleted = Vec::new();
+        for (_, position) in positions {
+            if amount == 0 {
+                break;
+            }
+            let Some(deposit) = self.deposits.get_mut(&position) else {
+                continue;
+            };
+            let taken = amount.min(deposit.remaining);
+            deposit.remaining -= taken;
+            amount -= taken;
+            if deposit.is_depleted() {
+                depleted.extend(self.deposits.remove(&position));
+            }
         }
+        depleted
+    }
+
+    /// Число чанков карты
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Число чанков с выделенным плотным массивом клеток
+    pub fn allocated_chunks(&self) -> usize {
+        self.chunks.iter().filter(|chunk| matches!(chunk, Chunk::Dense(_))).count()
     }
 
-    pub fn add_city(&mut self, x: i32, y: i32, city_name: String) {
-        if let Some(_) = self.terrain.get(&(x, y)) {
-            self.terrain.insert((x, y), TerrainTile::City(city_name));
+    /// Примерный объем памяти под клетки и имена в байтах
+    pub fn memory_usage(&self) -> usize {
+        let chunks = self.chunks.len() * std::mem::size_of::<Chunk>()
+            + self.allocated_chunks() * CHUNK_AREA * std::mem::size_of::<Cell>();
+        let names: usize = self.names.iter().map(|name| name.capacity() + std::mem::size_of::<String>()).sum();
+        chunks + names
+    }
+
+    fn chunk_position(&self, x: i32, y: i32) -> (usize, usize) {
+        let columns = chunk_count(self.width);
+        let chunk = (y / CHUNK_SIZE) as usize * columns + (x / CHUNK_SIZE) as usize;
+        let offset = (y % CHUNK_SIZE * CHUNK_SIZE + x % CHUNK_SIZE) as usize;
+        (chunk, offset)
+    }
+
+    fn cell(&self, x: i32, y: i32) -> Option<Cell> {
+        if !self.in_bounds(x, y) {
+            return None;
         }
+        let (chunk, offset) = self.chunk_position(x, y);
+        Some(self.chunks[chunk].get(offset))
+    }
+
+    fn set_cell(&mut self, x: i32, y: i32, cell: Cell) {
+        let (chunk, offset) = self.chunk_position(x, y);
+        self.chunks[chunk].set(offset, cell);
     }
+
+    fn intern(&mut self, name: String) -> u32 {
+        if let Some(id) = self.name_ids.get(&name) {
+            return *id;
+        }
+        let id = self.names.len() as u32;
+        assert!(id <= Cell::MAX_INDEX, "Таблица имен карты переполнена");
+        self.name_ids.insert(name.clone(), id);
+        self.names.push(name);
+        id
+    }
+
+    fn encode(&mut self, tile: TerrainTile) -> Cell {
+        match tile {
+            TerrainTile::Land => Cell::new(Cell::LAND, 0),
+            TerrainTile::Water => Cell::new(Cell::WATER, 0),
+            TerrainTile::Mountain => Cell::new(Cell::MOUNTAIN, 0),
+            TerrainTile::Forest => Cell::new(Cell::FOREST, 0),
+            TerrainTile::Desert => Cell::new(Cell::DESERT, 0),
+            TerrainTile::Building(name) => Cell::new(Cell::BUILDING, self.intern(name)),
+            TerrainTile::ResourceSpot(resource) => {
+                let index = ResourceType::ALL.iter().position(|r| *r == resource).unwrap_or(0);
+                Cell::new(Cell::RESOURCE_SPOT, index as u32)
+            }
+            TerrainTile::City(name) => Cell::new(Cell::CITY, self.intern(name)),
+            TerrainTile::Unknown => Cell::new(Cell::UNKNOWN, 0),
+        }
+    }
+
+    fn decode(&self, cell: Cell) -> TerrainTile {
+        let name = || self.names[cell.index() as usize].clone();
+        match cell.kind() {
+            Cell::LAND => TerrainTile::Land,
+            Cell::WATER => TerrainTile::Water,
+            Cell::MOUNTAIN => TerrainTile::Mountain,
+            Cell::FOREST => TerrainTile::Forest,
+            Cell::DESERT => TerrainTile::Desert,
+            Cell::BUILDING => TerrainTile::Building(name()),
+            Cell::RESOURCE_SPOT => TerrainTile::ResourceSpot(ResourceType::ALL[cell.index() as usize].clone()),
+            Cell::CITY => TerrainTile::City(name()),
+            _ => TerrainTile::Unknown,
+        }
+    }
+}
+
+/// Сколько чанков нужно, чтобы покрыть `length` клеток
+fn chunk_count(length: u64) -> usize {
+    length.div_ceil(CHUNK_SIZE as u64) as usize
 }
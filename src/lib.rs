@@ -1,6 +1,9 @@
-extern crate cityrade_types;
-#[macro_use]
-extern crate cityrade_macros;
+pub use cityrade_types::*;
+pub use cityrade_macros::*;
 
-use cityrade_types::*;
-use cityrade_macros::*;
\ No newline at end of file
+pub mod simulation;
+
+pub use simulation::{GameState, Simulation, TickPhase, TickReport};
+
+#[cfg(test)]
+mod tests;
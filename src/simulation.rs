@@ -0,0 +1,763 @@
+//! Авторитетный игровой цикл.
+//!
+//! `GameState` владеет всеми подсистемами мира и продвигает их одним вызовом
+//! [`GameState::tick`]. Порядок фаз внутри тика фиксирован (см. [`TickPhase`]),
+//! поэтому два сервера, применяющие одни и те же команды, получают одинаковый
+//! результат. Все коллекции, по которым идёт обход во время тика, упорядочены
+//! по ключу (`BTreeMap`), чтобы порядок обработки не зависел от хеширования.
+//!
+//! Вся случайность берется из одного генератора, созданного из зерна мира:
+//! одинаковое зерно и одинаковая последовательность команд воспроизводят
+//! одно и то же состояние.
+//!
+//! Время берется из [`GameClock`]: номер хода, игровая дата и реальное время,
+//! которым помечаются города, игроки и квесты. С ручным источником времени
+//! тесты могут перематывать часы.
+//!
+//! `Simulation` — обёртка над `GameState`, которая переводит реальное время в
+//! тики с периодом `constants::TICK_RATE_MS` и догоняет ходы, пропущенные
+//! за время простоя.
+
+use std::collections::BTreeMap;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use rand::Rng;
+
+use cityrade_types::{
+    building::{BuildingError, BuildingType},
+    building_registry::BuildingRegistry,
+    city::City,
+    clock::GameClock,
+    constants::TICK_RATE_MS,
+    diplomacy::DiplomacyManager,
+    event_bus::AsyncEventBus,
+    events::EventSystem,
+    faction::FactionManager,
+    ledger::LedgerSource,
+    market::TradeManager,
+    player::{Player, PlayerManager},
+    policies::{PolicyError, PolicyType},
+    production::ProductionModifiers,
+    quest::QuestManager,
+    random_events::EventManager,
+    resources::ResourceType,
+    rng::{self, GameRng},
+    storage::{OverflowPolicy, OverflowResolution},
+    technology::{TechnologyTree, TechnologyType},
+    territory::{self, BorderClaim},
+    world::{DEPOSIT_REACH, ResourceDeposit, WorldMap},
+};
+
+/// Фазы игрового тика в порядке их выполнения
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TickPhase {
+    /// Исследования: `TechnologyTree::update_research` для каждого игрока
+    Research,
+    /// Очереди строительства городов (`City::update_construction`)
+    Construction,
+    /// Города: производство с бонусами фракции и технологий, статистика
+    /// и население (`City::update`)
+    Cities,
+    /// Рост границ городов на карте мира за счет культуры
+    /// (`territory::expand_borders`) и напряженность на общих границах фракций
+    Borders,
+    /// Длящиеся случайные события городов (`EventManager::update_active_events`)
+    RandomEvents,
+    /// Излишки, не поместившиеся на склады (`GameState::resolve_storage_overflow`)
+    Storage,
+    /// Цены на рынках (`TradeManager::update_all_markets`) и пошлины с
+    /// торговых маршрутов (`GameState::collect_route_tariffs`)
+    Markets,
+    /// Доступность квестов (`QuestManager::update_available_quests`)
+    Quests,
+}
+
+impl TickPhase {
+    /// Фиксированный порядок фаз
+    pub const ORDER: [TickPhase; 8] = [
+        TickPhase::Research,
+        TickPhase::Construction,
+        TickPhase::Cities,
+        TickPhase::Borders,
+        TickPhase::RandomEvents,
+        TickPhase::Storage,
+        TickPhase::Markets,
+        TickPhase::Quests,
+    ];
+}
+
+/// Итоги одного тика
+#[derive(Debug, Clone, Default)]
+pub struct TickReport {
+    /// Номер хода, который был обработан
+    pub turn: u64,
+    /// Завершённые исследования: (ID игрока, технология)
+    pub completed_research: Vec<(String, TechnologyType)>,
+    /// Завершённое строительство: (ID города, ID здания)
+    pub completed_construction: Vec<(String, String)>,
+    /// Распределение излишков, не поместившихся на склады
+    pub storage_overflow: Vec<OverflowResolution>,
+    /// Исчерпанные месторождения: (ID города, месторождение)
+    pub depleted_deposits: Vec<(String, ResourceDeposit)>,
+    /// Клетки, присоединенные к территориям городов: (ID города, позиция)
+    pub claimed_tiles: Vec<(String, (i32, i32))>,
+}
+
+/// Дерево без исследованных технологий для владельцев без своего дерева
+static NO_TECHNOLOGIES: LazyLock<TechnologyTree> = LazyLock::new(TechnologyTree::new);
+
+/// Дерево технологий владельца города; у владельца без дерева ничего не исследовано
+fn owner_technologies<'a>(tech_trees: &'a BTreeMap<String, TechnologyTree>, owner_id: &str) -> &'a TechnologyTree {
+    tech_trees.get(owner_id).unwrap_or(&NO_TECHNOLOGIES)
+}
+
+/// Полное состояние игрового мира
+pub struct GameState {
+    /// Зерно мира
+    pub seed: u64,
+    /// Игровые часы: номер хода, календарь и источник реального времени
+    pub clock: GameClock,
+    /// Игроки
+    pub players: PlayerManager,
+    /// Карта мира с месторождениями и территориями городов
+    pub world: WorldMap,
+    /// Города (ID города -> город)
+    pub cities: BTreeMap<String, City>,
+    /// Описания зданий: стоимость, производство и влияние на статистику
+    pub building_registry: BuildingRegistry,
+    /// Деревья технологий (ID игрока -> дерево)
+    pub tech_trees: BTreeMap<String, TechnologyTree>,
+    /// Квесты (ID игрока -> менеджер квестов)
+    pub quests: BTreeMap<String, QuestManager>,
+    /// Рынки городов и торговые маршруты (рынки индексируются ID города)
+    pub trade: TradeManager,
+    /// Фракции. Тик их не продвигает: состав и бонусы меняются только командами
+    pub factions: FactionManager,
+    /// Дипломатические отношения. Тик их не продвигает, кроме напряженности
+    /// на общих границах фракций (фаза [`TickPhase::Borders`])
+    pub diplomacy: DiplomacyManager,
+    /// Случайные события (ID города -> менеджер событий)
+    pub random_events: BTreeMap<String, EventManager>,
+    /// Система событий для плагинов
+    pub events: EventSystem,
+    /// Асинхронная шина событий: доставка в конце тика и отложенные события.
+    /// Получает все неотмененные события `events`
+    pub event_bus: AsyncEventBus,
+    /// Генератор случайных чисел мира
+    rng: GameRng,
+}
+
+impl Default for GameState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GameState {
+    /// Создает пустой мир со случайным зерном
+    pub fn new() -> Self {
+        Self::with_seed(rand::random())
+    }
+
+    /// Создает пустой мир с заданным зерном
+    pub fn with_seed(seed: u64) -> Self {
+        let mut events = EventSystem::new();
+        let event_bus = AsyncEventBus::new();
+        event_bus.forward_all(&mut events);
+
+        GameState {
+            seed,
+            clock: GameClock::new(),
+            players: PlayerManager::new(),
+            world: WorldMap::new(0, 0),
+            cities: BTreeMap::new(),
+            building_registry: BuildingRegistry::builtin(),
+            tech_trees: BTreeMap::new(),
+            quests: BTreeMap::new(),
+            trade: TradeManager::new(),
+            factions: FactionManager::new(),
+            diplomacy: DiplomacyManager::new(),
+            random_events: BTreeMap::new(),
+            events,
+            event_bus,
+            rng: rng::seeded(seed),
+        }
+    }
+
+    /// Заменяет игровые часы (например, на часы с ручным источником времени)
+    pub fn with_clock(mut self, clock: GameClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Заменяет карту мира (например, созданную `WorldGenerator` из зерна мира)
+    pub fn with_world(mut self, world: WorldMap) -> Self {
+        self.world = world;
+        self
+    }
+
+    /// Заменяет описания зданий (например, загруженные из файлов данных)
+    pub fn with_building_registry(mut self, registry: BuildingRegistry) -> Self {
+        self.building_registry = registry;
+        self
+    }
+
+    /// Номер текущего хода
+    pub fn turn(&self) -> u64 {
+        self.clock.turn()
+    }
+
+    /// Новый ID для игрока или города из генератора мира: одинаковое зерно
+    /// дает одинаковые ID
+    pub fn next_id(&mut self) -> String {
+        rng::random_id(&mut self.rng)
+    }
+
+    /// Добавляет игрока вместе с его деревом технологий и квестами.
+    /// ID задает вызывающий в `Player` (например, из `next_id`).
+    pub fn add_player(&mut self, player: Player) -> String {
+        let player_id = player.id.clone();
+
+        self.tech_trees
+            .insert(player_id.clone(), TechnologyTree::new().with_owner(player_id.clone()));
+        self.quests
+            .insert(player_id.clone(), QuestManager::new().with_owner(player_id.clone()));
+        self.players.add_player(player);
+
+        player_id
+    }
+
+    /// Основывает город: регистрирует его рынок и менеджер случайных событий.
+    /// ID задает вызывающий в `City` (например, из `next_id`).
+    pub fn found_city(&mut self, city: City) -> String {
+        let city_id = city.id.clone();
+
+        self.trade.create_city_market(&city_id);
+        self.random_events
+            .insert(city_id.clone(), EventManager::with_seed(self.rng.random()));
+        self.cities.insert(city_id.clone(), city);
+
+        city_id
+    }
+
+    /// Основывает город игрока на карте мира. Город сначала ставится на
+    /// карту (`WorldMap::add_city` проверяет место и отдает городу территорию
+    /// вокруг него), а местность и модификаторы ресурсов города берутся из
+    /// окрестных клеток. Если место не подходит, город не регистрируется.
+    pub fn found_city_at(&mut self, name: String, owner_id: &str, position: (i32, i32)) -> Result<String, String> {
+        if self.players.get_player(owner_id).is_none() {
+            return Err("Игрок не найден".to_string());
+        }
+        let city_id = self.next_id();
+        let site = self.world.add_city(position.0, position.1, &city_id, name.clone())?;
+        let city = City::from_site(city_id, name, owner_id.to_string(), &site, &self.clock);
+
+        Ok(self.found_city(city))
+    }
+
+    /// Ставит здание в очередь строительства города; возвращает ID будущего здания.
+    /// Условия постройки проверяются по дереву технологий владельца города.
+    pub fn add_building(
+        &mut self,
+        city_id: &str,
+        building_type: BuildingType,
+        name: String,
+        position: (i32, i32),
+    ) -> Result<String, BuildingError> {
+        let city = self
+            .cities
+            .get_mut(city_id)
+            .ok_or(BuildingError::CityNotFound)?;
+        let technologies = owner_technologies(&self.tech_trees, &city.owner_id);
+
+        city.add_building(building_type, name, position, &self.building_registry, technologies, &mut self.rng)
+    }
+
+    /// Ставит улучшение здания в очередь строительства города
+    pub fn upgrade_building(&mut self, city_id: &str, building_id: &str) -> Result<(), BuildingError> {
+        let city = self
+            .cities
+            .get_mut(city_id)
+            .ok_or(BuildingError::CityNotFound)?;
+        let technologies = owner_technologies(&self.tech_trees, &city.owner_id);
+
+        city.upgrade_building(building_id, &self.building_registry, technologies)
+    }
+
+    /// Прокладывает дорогу по клетке сетки города
+    pub fn build_road(&mut self, city_id: &str, position: (i32, i32)) -> Result<(), BuildingError> {
+        let city = self
+            .cities
+            .get_mut(city_id)
+            .ok_or(BuildingError::CityNotFound)?;
+
+        city.build_road(position)
+    }
+
+    /// Клетки города, на которые можно поставить здание
+    pub fn valid_build_spots(&self, city_id: &str, building_type: &BuildingType) -> Vec<(i32, i32)> {
+        self.cities
+            .get(city_id)
+            .map(|city| city.valid_build_spots(building_type, &self.building_registry))
+            .unwrap_or_default()
+    }
+
+    /// Отменяет задание строительства в городе с частичным возвратом ресурсов
+    pub fn cancel_construction(
+        &mut self,
+        city_id: &str,
+        building_id: &str,
+    ) -> Result<Vec<(ResourceType, u32)>, BuildingError> {
+        let city = self.cities.get_mut(city_id).ok_or(BuildingError::CityNotFound)?;
+
+        city.cancel_construction(building_id)
+    }
+
+    /// Принимает политику в городе. Политика должна быть открыта
+    /// технологиями владельца города.
+    pub fn enact_policy(&mut self, city_id: &str, policy: PolicyType) -> Result<(), PolicyError> {
+        let turn = self.clock.turn();
+        let city = self.cities.get_mut(city_id).ok_or(PolicyError::CityNotFound)?;
+        let technologies = owner_technologies(&self.tech_trees, &city.owner_id);
+
+        city.policies.enact(policy, technologies, turn)
+    }
+
+    /// Отменяет политику в городе
+    pub fn revoke_policy(&mut self, city_id: &str, policy: PolicyType) -> Result<(), PolicyError> {
+        let turn = self.clock.turn();
+        let city = self.cities.get_mut(city_id).ok_or(PolicyError::CityNotFound)?;
+
+        city.policies.revoke(policy, turn)
+    }
+
+    /// Модификатор скорости строительства города в процентах:
+    /// бонус фракции (`FactionBonus::BuildingSpeed`) плюс бонус технологий владельца
+    pub fn construction_speed(&self, city_id: &str) -> i32 {
+        let Some(city) = self.cities.get(city_id) else {
+            return 0;
+        };
+
+        let faction_bonus = self
+            .factions
+            .get_city_faction(city_id)
+            .map(|faction| faction.building_speed_modifier())
+            .unwrap_or(0);
+        let tech_bonus = self
+            .tech_trees
+            .get(&city.owner_id)
+            .map(|tree| (tree.get_building_speed_bonus() * 100.0).round() as i32)
+            .unwrap_or(0);
+
+        faction_bonus + tech_bonus
+    }
+
+    /// Бонусы к производству города: фракция города, технологии владельца,
+    /// месторождения в пределах `DEPOSIT_REACH` от города и обрабатываемые
+    /// клетки его территории
+    pub fn production_modifiers(&self, city_id: &str) -> ProductionModifiers {
+        let mut modifiers = ProductionModifiers::default();
+        let Some(city) = self.cities.get(city_id) else {
+            return modifiers;
+        };
+        modifiers.deposits = self.world.deposit_richness(city.position, DEPOSIT_REACH);
+        modifiers.worked_tiles = territory::worked_tile_yields(&self.world, city_id, city.population.total());
+
+        let faction = self.factions.get_city_faction(city_id);
+        let tree = self.tech_trees.get(&city.owner_id);
+        for resource in ResourceType::ALL {
+            if let Some(faction) = faction {
+                let bonus = faction.get_resource_production_modifier(&resource);
+                if bonus != 0 {
+                    modifiers.faction.insert(resource.clone(), bonus);
+                }
+            }
+            if let Some(tree) = tree {
+                let bonus = (tree.get_resource_production_bonus(&format!("{:?}", resource)) * 100.0).round() as i32;
+                if bonus != 0 {
+                    modifiers.technology.insert(resource.clone(), bonus);
+                }
+            }
+        }
+
+        modifiers
+    }
+
+    /// Клетки карты мира, принадлежащие городам фракции
+    pub fn faction_territory(&self, faction_id: &str) -> Vec<(i32, i32)> {
+        let Some(faction) = self.factions.get_faction(faction_id) else {
+            return Vec::new();
+        };
+        let mut tiles: Vec<(i32, i32)> = faction
+            .cities
+            .iter()
+            .flat_map(|city_id| self.world.territory(city_id))
+            .collect();
+        tiles.sort();
+        tiles
+    }
+
+    /// Граница между территориями двух фракций: клетки каждой из них,
+    /// граничащие с клетками другой
+    pub fn faction_border(&self, first: &str, second: &str) -> Vec<(i32, i32)> {
+        let (Some(first), Some(second)) = (self.factions.get_faction(first), self.factions.get_faction(second)) else {
+            return Vec::new();
+        };
+        let mut tiles: Vec<(i32, i32)> = first
+            .cities
+            .iter()
+            .flat_map(|a| second.cities.iter().flat_map(move |b| self.world.border_between(a, b)))
+            .collect();
+        tiles.sort();
+        tiles.dedup();
+        tiles
+    }
+
+    /// Копит культуру городов на карте мира и расширяет их границы;
+    /// затем общие границы фракций портят их отношения
+    fn update_borders(&mut self) -> Vec<(String, (i32, i32))> {
+        let mut claims = Vec::new();
+        let mut culture = BTreeMap::new();
+        for (city_id, city) in self.cities.iter_mut() {
+            if self.world.city_position(city_id).is_none() {
+                continue;
+            }
+            culture.insert(city_id.clone(), city.stats.culture);
+            // Копится не больше стоимости следующей клетки: город, которому
+            // некуда расти, не собирает запас на много клеток вперед
+            let cost = territory::border_tile_cost(self.world.territory(city_id).len());
+            city.border_progress = city.border_progress.saturating_add(city.stats.culture).min(cost);
+            if city.border_progress >= cost {
+                claims.push(BorderClaim {
+                    city_id: city_id.clone(),
+                    culture: city.stats.culture,
+                });
+            }
+        }
+
+        let claimed = territory::expand_borders(&mut self.world, &claims, &culture);
+        for (city_id, _) in &claimed {
+            if let Some(city) = self.cities.get_mut(city_id) {
+                // Стоимость считается по размеру территории до присоединения клетки
+                let cost = territory::border_tile_cost(self.world.territory(city_id).len() - 1);
+                city.border_progress = city.border_progress.saturating_sub(cost);
+            }
+        }
+
+        let mut faction_ids: Vec<String> = self.factions.get_all_factions().iter().map(|f| f.id.clone()).collect();
+        faction_ids.sort();
+        for (index, first) in faction_ids.iter().enumerate() {
+            for second in &faction_ids[index + 1..] {
+                let border = self.faction_border(first, second).len();
+                if border > 0 {
+                    self.diplomacy.apply_border_tension(first, second, border, &self.events);
+                }
+            }
+        }
+
+        claimed
+    }
+
+    /// Покупает ресурс на рынке города за золото города; возвращает
+    /// потраченное золото. Покупка, не помещающаяся на склад, отклоняется
+    pub fn buy_resource(&mut self, city_id: &str, resource: &ResourceType, amount: u32) -> Result<u32, String> {
+        let city = self.cities.get(city_id).ok_or("Город не найден")?;
+        let price = self
+            .trade
+            .get_city_market(city_id)
+            .and_then(|market| market.get_buy_price(resource))
+            .ok_or("Рынок не торгует этим ресурсом")?;
+        let cost = (price * amount as f32).ceil() as u32;
+        if city.resources.get(&ResourceType::Gold) < cost {
+            return Err("Недостаточно золота".to_string());
+        }
+        if city.resources.free_space(resource) < amount {
+            return Err("Недостаточно места на складе".to_string());
+        }
+        if self.trade.buy(city_id, resource, amount, &self.events).is_none() {
+            return Err("Сделка не состоялась".to_string());
+        }
+
+        let city = self.cities.get_mut(city_id).ok_or("Город не найден")?;
+        city.resources.subtract(&ResourceType::Gold, cost);
+        city.resources.add(resource, amount);
+        city.ledger.record(LedgerSource::Trade, ResourceType::Gold, -(cost as i64));
+        city.ledger.record(LedgerSource::Trade, resource.clone(), amount as i64);
+        Ok(cost)
+    }
+
+    /// Продает ресурс города на его рынке; возвращает полученное золото.
+    /// Продажа, выручка которой не помещается в казну, отклоняется
+    pub fn sell_resource(&mut self, city_id: &str, resource: &ResourceType, amount: u32) -> Result<u32, String> {
+        let city = self.cities.get(city_id).ok_or("Город не найден")?;
+        if city.resources.get(resource) < amount {
+            return Err("Недостаточно ресурсов".to_string());
+        }
+        let expected = self
+            .trade
+            .get_city_market(city_id)
+            .and_then(|market| market.get_sell_price(resource))
+            .ok_or("Рынок не торгует этим ресурсом")?;
+        if city.resources.free_space(&ResourceType::Gold) < (expected * amount as f32).max(0.0) as u32 {
+            return Err("Недостаточно места в казне".to_string());
+        }
+        let price = self
+            .trade
+            .sell(city_id, resource, amount, &self.events)
+            .ok_or("Сделка не состоялась")?;
+        let income = price.max(0.0) as u32;
+
+        let city = self.cities.get_mut(city_id).ok_or("Город не найден")?;
+        city.resources.subtract(resource, amount);
+        city.resources.add(&ResourceType::Gold, income);
+        city.ledger.record(LedgerSource::Trade, resource.clone(), -(amount as i64));
+        city.ledger.record(LedgerSource::Trade, ResourceType::Gold, income as i64);
+        Ok(income)
+    }
+
+    /// Взимает пошлины с торговых маршрутов: каждый город на концах маршрута
+    /// получает свою пошлину с его оборота (`City::levy_tariff`).
+    /// Возвращает собранные пошлины по городам.
+    pub fn collect_route_tariffs(&mut self) -> BTreeMap<String, u32> {
+        let mut collected = BTreeMap::new();
+        for route in &self.trade.trade_routes {
+            let mut endpoints = vec![&route.source_city, &route.target_city];
+            endpoints.dedup();
+            for city_id in endpoints {
+                if let Some(city) = self.cities.get_mut(city_id) {
+                    let tariff = city.levy_tariff(route.value());
+                    *collected.entry(city_id.clone()).or_insert(0) += tariff;
+                }
+            }
+        }
+        collected
+    }
+
+    /// Распределяет излишки городов по их политикам переполнения.
+    ///
+    /// `AutoSell` продает излишек на рынке города (если рынок не покупает
+    /// ресурс, излишек теряется), `Spill` отправляет его в связанный город
+    /// (не поместившееся там теряется), `Waste` просто отбрасывает.
+    pub fn resolve_storage_overflow(&mut self) -> Vec<OverflowResolution> {
+        let mut resolutions = Vec::new();
+        let city_ids: Vec<String> = self.cities.keys().cloned().collect();
+
+        for city_id in city_ids {
+            let Some(city) = self.cities.get_mut(&city_id) else {
+                continue;
+            };
+            let policy = city.storage_policy.clone();
+            let overflow = city.resources.take_overflow();
+
+            for (resource, amount) in overflow {
+                let mut resolution = OverflowResolution {
+                    city_id: city_id.clone(),
+                    resource: resource.clone(),
+                    amount,
+                    sold_for: 0,
+                    spilled: 0,
+                    wasted: amount,
+                };
+
+                // Излишек уже учтен как доход источника; здесь он списывается
+                if let Some(city) = self.cities.get_mut(&city_id) {
+                    city.ledger.record(LedgerSource::Overflow(city_id.clone()), resource.clone(), -(amount as i64));
+                }
+
+                match policy.policy_for(&resource) {
+                    OverflowPolicy::Waste => {}
+                    OverflowPolicy::AutoSell => {
+                        if resource != ResourceType::Gold
+                            && let Some(price) = self.trade.sell(&city_id, &resource, amount, &self.events)
+                        {
+                            resolution.sold_for = price.max(0.0) as u32;
+                            resolution.wasted = 0;
+                            if let Some(city) = self.cities.get_mut(&city_id) {
+                                city.resources.add(&ResourceType::Gold, resolution.sold_for);
+                                city.ledger.record(LedgerSource::Trade, ResourceType::Gold, resolution.sold_for as i64);
+                            }
+                        }
+                    }
+                    OverflowPolicy::Spill(target_id) => {
+                        if *target_id != city_id
+                            && let Some(target) = self.cities.get_mut(target_id)
+                        {
+                            let rest = target.resources.try_store(&resource, amount);
+                            resolution.spilled = amount - rest;
+                            target.ledger.record(
+                                LedgerSource::Overflow(city_id.clone()),
+                                resource.clone(),
+                                resolution.spilled as i64,
+                            );
+                            resolution.wasted = rest;
+                        }
+                    }
+                }
+
+                resolutions.push(resolution);
+            }
+        }
+
+        resolutions
+    }
+
+    /// Продвигает мир на один ход.
+    ///
+    /// Фазы выполняются строго в порядке [`TickPhase::ORDER`].
+    /// Фракции и дипломатия своей фазы не имеют: тик только читает бонусы
+    /// фракций, а отношения меняет лишь напряженностью на общих границах.
+    /// В конце тика события асинхронной шины раскладываются по очередям
+    /// обработчиков; выполнить их можно через `event_bus.flush()`.
+    pub fn tick(&mut self) -> TickReport {
+        let mut report = TickReport {
+            turn: self.clock.turn(),
+            ..TickReport::default()
+        };
+
+        for city in self.cities.values_mut() {
+            city.ledger.begin_turn(report.turn);
+        }
+
+        for phase in TickPhase::ORDER {
+            self.run_phase(phase, &mut report);
+        }
+
+        self.event_bus.end_tick(self.clock.turn());
+        self.clock.advance_turn();
+        report
+    }
+
+    /// Последовательно выполняет `turns` ходов
+    pub fn run_turns(&mut self, turns: u64) -> Vec<TickReport> {
+        (0..turns).map(|_| self.tick()).collect()
+    }
+
+    fn run_phase(&mut self, phase: TickPhase, report: &mut TickReport) {
+        match phase {
+            TickPhase::Research => {
+                for (player_id, tree) in self.tech_trees.iter_mut() {
+                    if let Some(tech) = tree.update_research(&self.events) {
+                        report.completed_research.push((player_id.clone(), tech));
+                    }
+                }
+            }
+            TickPhase::Construction => {
+                let speeds: Vec<(String, i32)> = self
+                    .cities
+                    .keys()
+                    .map(|city_id| (city_id.clone(), self.construction_speed(city_id)))
+                    .collect();
+
+                for (city_id, speed) in speeds {
+                    if let Some(city) = self.cities.get_mut(&city_id) {
+                        for building_id in city.update_construction(speed, &self.events) {
+                            report.completed_construction.push((city_id.clone(), building_id));
+                        }
+                    }
+                }
+            }
+            TickPhase::Cities => {
+                let modifiers: Vec<(String, ProductionModifiers)> = self
+                    .cities
+                    .keys()
+                    .map(|city_id| (city_id.clone(), self.production_modifiers(city_id)))
+                    .collect();
+
+                for (city_id, modifiers) in modifiers {
+                    if let Some(city) = self.cities.get_mut(&city_id) {
+                        city.update(&self.building_registry, &modifiers, &self.clock, &mut self.rng, &self.events);
+
+                        // Добытое истощает месторождения рядом с городом
+                        for (resource, amount) in city.deposit_extraction() {
+                            if !modifiers.deposits.contains_key(&resource) {
+                                continue;
+                            }
+                            for deposit in self.world.extract(city.position, DEPOSIT_REACH, &resource, amount) {
+                                report.depleted_deposits.push((city_id.clone(), deposit));
+                            }
+                        }
+                    }
+                }
+            }
+            TickPhase::Borders => {
+                report.claimed_tiles = self.update_borders();
+            }
+            TickPhase::RandomEvents => {
+                for (city_id, manager) in self.random_events.iter_mut() {
+                    if let Some(city) = self.cities.get_mut(city_id) {
+                        for (event_id, resource, change) in manager.update_active_events(&mut city.resources) {
+                            city.ledger.record(LedgerSource::Event(event_id), resource, change);
+                        }
+                    }
+                }
+            }
+            TickPhase::Storage => {
+                report.storage_overflow = self.resolve_storage_overflow();
+            }
+            TickPhase::Markets => {
+                self.trade.update_all_markets(&self.clock, &mut self.rng);
+                self.collect_route_tariffs();
+            }
+            TickPhase::Quests => {
+                for manager in self.quests.values_mut() {
+                    manager.update_available_quests(&self.clock);
+                }
+            }
+        }
+    }
+}
+
+/// Переводит реальное время в тики `GameState`
+pub struct Simulation {
+    pub state: GameState,
+    tick_interval: Duration,
+    accumulator: Duration,
+}
+
+impl Simulation {
+    /// Создает симуляцию с периодом тика `constants::TICK_RATE_MS`
+    pub fn new(state: GameState) -> Self {
+        Simulation {
+            state,
+            tick_interval: Duration::from_millis(TICK_RATE_MS),
+            accumulator: Duration::ZERO,
+        }
+    }
+
+    /// Период одного тика
+    pub fn tick_interval(&self) -> Duration {
+        self.tick_interval
+    }
+
+    /// Выполняет один тик немедленно
+    pub fn tick(&mut self) -> TickReport {
+        self.state.tick()
+    }
+
+    /// Учитывает прошедшее реальное время и выполняет столько тиков,
+    /// сколько полных периодов накопилось. Остаток переносится на следующий вызов.
+    /// Ручной источник времени часов сдвигается на `elapsed`.
+    pub fn advance(&mut self, elapsed: Duration) -> Vec<TickReport> {
+        self.state.clock.advance_time(elapsed);
+        self.accumulator += elapsed;
+
+        let mut reports = Vec::new();
+        while self.accumulator >= self.tick_interval {
+            self.accumulator -= self.tick_interval;
+            reports.push(self.state.tick());
+        }
+
+        reports
+    }
+
+    /// Догоняет ходы, пропущенные с момента `last_seen` (например, время
+    /// последнего сохранения перед остановкой сервера): выполняет по одному
+    /// тику за каждый полный период, прошедший по часам мира.
+    pub fn catch_up(&mut self, last_seen: DateTime<Utc>) -> Vec<TickReport> {
+        let missed = self.state.clock.turns_since(last_seen, self.tick_interval);
+        self.accumulator = Duration::ZERO;
+        self.state.run_turns(missed)
+    }
+}
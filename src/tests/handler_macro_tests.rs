@@ -0,0 +1,86 @@
+use std::sync::{Arc, Mutex};
+
+use futures::executor::block_on;
+
+use cityrade_macros::handler;
+use cityrade_types::building::BuildingType;
+use cityrade_types::city::{City, Terrain};
+use cityrade_types::events::{BuildingConstructedEvent, PlayerJoinEvent, PlayerLeaveEvent};
+use cityrade_types::player::Player;
+
+use crate::simulation::GameState;
+
+#[derive(Default)]
+struct AuditPlugin {
+    log: Mutex<Vec<String>>,
+}
+
+#[handler]
+impl AuditPlugin {
+    async fn on_player_join(&self, event: Arc<PlayerJoinEvent>) {
+        self.log.lock().unwrap().push(format!("join {}", event.player_id));
+    }
+
+    #[backpressure(capacity = 1, overflow = "drop_newest")]
+    async fn on_player_leave(&self, event: Arc<PlayerLeaveEvent>) {
+        self.log.lock().unwrap().push(format!("leave {}", event.player_id));
+    }
+}
+
+#[test]
+fn test_handler_macro_registers_async_handlers() {
+    let mut state = GameState::with_seed(1);
+    let plugin = Arc::new(AuditPlugin::default());
+    plugin.register_handlers(&mut state.event_bus);
+
+    state.event_bus.publish(PlayerJoinEvent::new("p1".to_string(), "w".to_string()));
+    state.event_bus.publish(PlayerLeaveEvent::new("p1".to_string(), "w".to_string()));
+    state.event_bus.publish(PlayerLeaveEvent::new("p2".to_string(), "w".to_string()));
+    state.event_bus.publish_delayed(PlayerJoinEvent::new("p3".to_string(), "w".to_string()), 2);
+
+    state.tick();
+    block_on(state.event_bus.flush());
+    // Разные обработчики выполняются конкурентно, порядок между ними не задан
+    let mut log = plugin.log.lock().unwrap().clone();
+    log.sort();
+    assert_eq!(log, vec!["join p1", "leave p1"]);
+    assert_eq!(state.event_bus.dropped_count("AuditPlugin::on_player_leave"), Some(1));
+
+    state.tick();
+    state.tick();
+    block_on(state.event_bus.flush());
+    assert_eq!(plugin.log.lock().unwrap().last().unwrap(), "join p3");
+}
+
+#[derive(Default)]
+struct BuildersPlugin {
+    built: Mutex<Vec<(String, BuildingType)>>,
+}
+
+#[handler]
+impl BuildersPlugin {
+    async fn on_building_constructed(&self, event: Arc<BuildingConstructedEvent>) {
+        self.built.lock().unwrap().push((event.city_id.clone(), event.building_type.clone()));
+    }
+}
+
+#[test]
+fn test_tick_delivers_gameplay_events_to_handlers() {
+    let mut state = GameState::with_seed(1);
+    let player_id = state.next_id();
+    state.add_player(Player::new(player_id.clone(), "Игрок".to_string(), "account".to_string(), &state.clock));
+    let city_id = state.next_id();
+    state.found_city(City::new(city_id.clone(), "Столица".to_string(), player_id, Terrain::Plain, (0, 0), &state.clock));
+    let plugin = Arc::new(BuildersPlugin::default());
+    plugin.register_handlers(&mut state.event_bus);
+
+    let spot = state.valid_build_spots(&city_id, &BuildingType::Farm)[0];
+    state
+        .add_building(&city_id, BuildingType::Farm, "Ферма".to_string(), spot)
+        .unwrap();
+    let reports = state.run_turns(10);
+    assert!(reports.iter().any(|report| !report.completed_construction.is_empty()));
+    block_on(state.event_bus.flush());
+
+    assert_eq!(*plugin.built.lock().unwrap(), vec![(city_id, BuildingType::Farm)]);
+}
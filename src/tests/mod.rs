@@ -0,0 +1,2 @@
+mod handler_macro_tests;
+mod simulation_tests;
@@ -0,0 +1,526 @@
+use std::time::Duration;
+
+use chrono::{TimeZone, Utc};
+
+use cityrade_types::building::{Building, BuildingType};
+use cityrade_types::city::{City, Terrain};
+use cityrade_types::clock::GameClock;
+use cityrade_types::constants::TICK_RATE_MS;
+use cityrade_types::faction::{Faction, FactionBonus, FactionSpecialization};
+use cityrade_types::market::TradeRoute;
+use cityrade_types::player::Player;
+use cityrade_types::policies::{PolicyError, PolicyType, POLICY_SWITCH_COOLDOWN};
+use cityrade_types::quest::{Quest, QuestStatus};
+use cityrade_types::resources::ResourceType;
+use cityrade_types::storage::OverflowPolicy;
+use cityrade_types::technology::{ResearchStatus, TechnologyType};
+use cityrade_types::territory;
+use cityrade_types::world::{ResourceDeposit, TerrainTile, WorldMap};
+
+use crate::simulation::{GameState, Simulation};
+
+fn add_player(state: &mut GameState) -> String {
+    let player_id = state.next_id();
+    state.add_player(Player::new(player_id, "Игрок".to_string(), "account".to_string(), &state.clock))
+}
+
+fn state_with_city() -> (GameState, String, String) {
+    let mut state = GameState::new();
+    let player_id = add_player(&mut state);
+    let city_id = state.next_id();
+    state.found_city(City::new(
+        city_id.clone(),
+        "Столица".to_string(),
+        player_id.clone(),
+        Terrain::Plain,
+        (0, 0),
+        &state.clock,
+    ));
+    (state, player_id, city_id)
+}
+
+#[test]
+fn test_found_city_registers_subsystems() {
+    let (state, player_id, city_id) = state_with_city();
+
+    assert!(state.cities.contains_key(&city_id));
+    assert!(state.trade.get_city_market(&city_id).is_some());
+    assert!(state.random_events.contains_key(&city_id));
+    assert!(state.tech_trees.contains_key(&player_id));
+    assert!(state.quests.contains_key(&player_id));
+}
+
+#[test]
+fn test_ids_come_from_world_seed() {
+    let mut first = GameState::with_seed(9);
+    let mut second = GameState::with_seed(9);
+    assert_eq!(first.next_id(), second.next_id());
+
+    // ID, выданный до основания, остается за городом
+    let city_id = first.next_id();
+    let city = City::new(city_id.clone(), "Столица".to_string(), "player".to_string(), Terrain::Plain, (0, 0), &first.clock);
+    assert_eq!(first.found_city(city), city_id);
+    assert!(first.cities.contains_key(&city_id));
+}
+
+#[test]
+fn test_tick_advances_turn_and_cities() {
+    let (mut state, _, city_id) = state_with_city();
+    let gold_before = state.cities[&city_id].resources.get(&ResourceType::Gold);
+
+    let report = state.tick();
+
+    assert_eq!(report.turn, 0);
+    assert_eq!(state.turn(), 1);
+    let gold_after = state.cities[&city_id].resources.get(&ResourceType::Gold);
+    assert!(gold_after > gold_before);
+}
+
+#[test]
+fn test_tick_completes_research() {
+    let (mut state, player_id, _) = state_with_city();
+    let tree = state.tech_trees.get_mut(&player_id).unwrap();
+    tree.start_research(TechnologyType::Agriculture).unwrap();
+    tree.set_research_rate(100);
+
+    let report = state.tick();
+
+    assert_eq!(report.completed_research, vec![(player_id.clone(), TechnologyType::Agriculture)]);
+    assert_eq!(
+        state.tech_trees[&player_id].get_research_status(&TechnologyType::Agriculture),
+        Some(&ResearchStatus::Completed)
+    );
+}
+
+#[test]
+fn test_simulation_advance_uses_tick_rate() {
+    let mut simulation = Simulation::new(GameState::new());
+    let interval = Duration::from_millis(TICK_RATE_MS);
+
+    assert!(simulation.advance(interval / 2).is_empty());
+    assert_eq!(simulation.advance(interval / 2).len(), 1);
+    assert_eq!(simulation.advance(interval * 3).len(), 3);
+    assert_eq!(simulation.state.turn(), 4);
+}
+
+#[test]
+fn test_construction_speed_from_faction_and_technology() {
+    let (mut state, player_id, city_id) = state_with_city();
+    let city = state.cities.get_mut(&city_id).unwrap();
+    city.add_resources(&ResourceType::Stone, 1000);
+    city.add_resources(&ResourceType::Iron, 1000);
+
+    let mut faction = Faction::new("guild".to_string(), "Гильдия".to_string(), FactionSpecialization::Balanced);
+    faction.bonuses = vec![FactionBonus::BuildingSpeed(40)];
+    faction.cities.push(city_id.clone());
+    state.factions.add_faction(faction);
+
+    let tree = state.tech_trees.get_mut(&player_id).unwrap();
+    tree.start_research(TechnologyType::BasicConstruction).unwrap();
+    tree.add_research_points(1000, &state.events);
+
+    // +40% фракции и +10% технологии
+    assert_eq!(state.construction_speed(&city_id), 50);
+
+    // Стена строится 5 ходов, с бонусом +50% — 4
+    let wall_id = state
+        .add_building(&city_id, BuildingType::Wall, "Стена".to_string(), (1, 1))
+        .unwrap();
+    let reports = state.run_turns(4);
+    assert!(reports[..3].iter().all(|report| report.completed_construction.is_empty()));
+    assert_eq!(reports[3].completed_construction, vec![(city_id.clone(), wall_id.clone())]);
+    assert!(state.cities[&city_id].buildings.contains_key(&wall_id));
+}
+
+#[test]
+fn test_ledger_records_trades_and_bonuses() {
+    let (mut state, player_id, city_id) = state_with_city();
+
+    let mut faction = Faction::new("farmers".to_string(), "Земледельцы".to_string(), FactionSpecialization::Balanced);
+    faction.bonuses = vec![FactionBonus::ResourceProduction(ResourceType::Food, 25)];
+    faction.cities.push(city_id.clone());
+    state.factions.add_faction(faction);
+
+    let tree = state.tech_trees.get_mut(&player_id).unwrap();
+    tree.start_research(TechnologyType::Agriculture).unwrap();
+    tree.add_research_points(1000, &state.events);
+
+    let modifiers = state.production_modifiers(&city_id);
+    assert_eq!(modifiers.faction_bonus(&ResourceType::Food), 25);
+    assert_eq!(modifiers.technology_bonus(&ResourceType::Food), 20);
+
+    state.tick();
+    let wood_before = state.cities[&city_id].resources.get(&ResourceType::Wood);
+    let gold_before = state.cities[&city_id].resources.get(&ResourceType::Gold);
+    let income = state.sell_resource(&city_id, &ResourceType::Wood, 50).unwrap();
+    assert!(income > 0);
+    assert!(state.sell_resource(&city_id, &ResourceType::Wood, 1_000_000).is_err());
+
+    let city = &state.cities[&city_id];
+    assert_eq!(city.resources.get(&ResourceType::Wood), wood_before - 50);
+    assert_eq!(city.resources.get(&ResourceType::Gold), gold_before + income);
+
+    // Со своих сделок город пошлину не платит
+    let statement = city.ledger.income_statement(1);
+    assert_eq!(statement["trade"][&ResourceType::Wood], -50);
+    assert_eq!(statement["trade"][&ResourceType::Gold], income as i64);
+    assert!(!statement.contains_key("tariff"));
+    assert!(statement["faction_bonus"][&ResourceType::Food] > 0);
+    assert!(statement["technology_bonus"][&ResourceType::Food] > 0);
+}
+
+#[test]
+fn test_route_tariffs_fill_treasury_but_slow_growth() {
+    let mut outcomes = Vec::new();
+    for rate in [0, 20] {
+        let mut state = GameState::with_seed(11);
+        let player_id = add_player(&mut state);
+        let capital_id = state.next_id();
+        state.found_city(City::new(capital_id.clone(), "Столица".to_string(), player_id.clone(), Terrain::Plain, (0, 0), &state.clock));
+        let port_id = state.next_id();
+        state.found_city(City::new(port_id.clone(), "Порт".to_string(), player_id, Terrain::Plain, (20, 0), &state.clock));
+        state.cities.get_mut(&capital_id).unwrap().tax_policy.set_tariff(rate).unwrap();
+        state.trade.add_trade_route(TradeRoute {
+            source_city: port_id.clone(),
+            target_city: capital_id.clone(),
+            resource_type: ResourceType::Wood,
+            quantity: 100,
+            price_per_unit: 2.0,
+            duration: 10,
+        });
+
+        state.tick();
+        let capital = &state.cities[&capital_id];
+        assert_eq!(capital.fiscal_report.tariffs, rate * 2);
+        if rate > 0 {
+            let statement = capital.ledger.income_statement(1);
+            assert_eq!(statement["tariff"][&ResourceType::Gold], (rate * 2) as i64);
+        }
+        outcomes.push((capital.resources.get(&ResourceType::Gold), capital.stats.growth));
+    }
+
+    // Пошлина с чужого оборота пополняет казну, но отпугивает переселенцев
+    let (free_gold, free_growth) = outcomes[0];
+    let (taxed_gold, taxed_growth) = outcomes[1];
+    assert_eq!(taxed_gold, free_gold + 40);
+    assert_eq!(taxed_growth, free_growth - 10);
+}
+
+#[test]
+fn test_trades_that_do_not_fit_storage_are_rejected() {
+    let (mut state, _, city_id) = state_with_city();
+    let city = state.cities.get_mut(&city_id).unwrap();
+    let treasury = city.resources.capacity(&ResourceType::Gold).unwrap();
+    city.resources.set(ResourceType::Gold, treasury);
+    let wood = city.resources.get(&ResourceType::Wood);
+
+    // Выручке некуда поступить: товар остается у города
+    assert!(state.sell_resource(&city_id, &ResourceType::Wood, 10).is_err());
+    let city = &state.cities[&city_id];
+    assert_eq!(city.resources.get(&ResourceType::Wood), wood);
+    assert_eq!(city.resources.get(&ResourceType::Gold), treasury);
+
+    // Склад дерева полон: покупка не списывает золото
+    let city = state.cities.get_mut(&city_id).unwrap();
+    let stock = city.resources.capacity(&ResourceType::Wood).unwrap();
+    city.resources.set(ResourceType::Wood, stock);
+    assert!(state.buy_resource(&city_id, &ResourceType::Wood, 10).is_err());
+    let city = &state.cities[&city_id];
+    assert_eq!(city.resources.get(&ResourceType::Gold), treasury);
+    assert_eq!(city.resources.get(&ResourceType::Wood), stock);
+    assert_eq!(city.resources.pending_overflow(&ResourceType::Wood), 0);
+}
+
+fn seeded_run(seed: u64, turns: u32) -> GameState {
+    let mut state = GameState::with_seed(seed);
+    let player_id = add_player(&mut state);
+    let city_id = state.next_id();
+    state.found_city(City::new(
+        city_id.clone(),
+        "Столица".to_string(),
+        player_id,
+        Terrain::Plain,
+        (0, 0),
+        &state.clock,
+    ));
+    state
+        .add_building(&city_id, BuildingType::Farm, "Ферма".to_string(), (1, 1))
+        .unwrap();
+
+    for _ in 0..turns {
+        state.tick();
+    }
+    state
+}
+
+fn snapshot(state: &GameState) -> Vec<String> {
+    let mut lines = Vec::new();
+    for (city_id, city) in &state.cities {
+        let mut resources = city.resources.get_all_resources();
+        resources.sort();
+        lines.push(format!("{} {} {:?}", city_id, city.population.total(), resources));
+        let market = state.trade.get_city_market(city_id).unwrap();
+        for resource in [ResourceType::Gold, ResourceType::Wood, ResourceType::Food, ResourceType::Crystal] {
+            lines.push(format!("{:?} {:?}", resource, market.get_buy_price(&resource)));
+        }
+        let buildings: Vec<&String> = city.buildings.keys().collect();
+        lines.push(format!("{:?}", buildings));
+    }
+    lines
+}
+
+#[test]
+fn test_storage_overflow_policies() {
+    let (mut state, player_id, capital_id) = state_with_city();
+    let colony_id = state.next_id();
+    state.found_city(City::new(
+        colony_id.clone(),
+        "Колония".to_string(),
+        player_id,
+        Terrain::Plain,
+        (5, 5),
+        &state.clock,
+    ));
+
+    {
+        let capital = state.cities.get_mut(&capital_id).unwrap();
+        capital.storage_policy.set(ResourceType::Wood, OverflowPolicy::AutoSell);
+        capital.storage_policy.set(ResourceType::Stone, OverflowPolicy::Spill(colony_id.clone()));
+        capital.resources.set(ResourceType::Wood, 1000);
+        capital.resources.set(ResourceType::Stone, 1000);
+        capital.resources.set(ResourceType::Gold, 0);
+        capital.add_resources(&ResourceType::Wood, 40);
+        capital.add_resources(&ResourceType::Stone, 30);
+        capital.add_resources(&ResourceType::Iron, 600);
+    }
+    state.cities.get_mut(&colony_id).unwrap().resources.set(ResourceType::Stone, 990);
+
+    let resolutions = state.resolve_storage_overflow();
+    assert_eq!(resolutions.len(), 3);
+
+    let wood = resolutions.iter().find(|r| r.resource == ResourceType::Wood).unwrap();
+    assert_eq!((wood.amount, wood.wasted), (40, 0));
+    assert!(wood.sold_for > 0);
+    assert_eq!(state.cities[&capital_id].resources.get(&ResourceType::Gold), wood.sold_for);
+
+    let stone = resolutions.iter().find(|r| r.resource == ResourceType::Stone).unwrap();
+    assert_eq!((stone.spilled, stone.wasted), (10, 20));
+    assert_eq!(state.cities[&colony_id].resources.get(&ResourceType::Stone), 1000);
+
+    let iron = resolutions.iter().find(|r| r.resource == ResourceType::Iron).unwrap();
+    assert_eq!((iron.amount, iron.wasted), (100, 100));
+    assert_eq!(state.cities[&capital_id].resources.get(&ResourceType::Iron), 500);
+
+    assert!(state.resolve_storage_overflow().is_empty());
+}
+
+#[test]
+fn test_same_seed_reproduces_state() {
+    let first = seeded_run(42, 200);
+    let second = seeded_run(42, 200);
+
+    assert_eq!(snapshot(&first), snapshot(&second));
+}
+
+#[test]
+fn test_different_seeds_diverge() {
+    let first = seeded_run(1, 200);
+    let second = seeded_run(2, 200);
+
+    assert_ne!(snapshot(&first), snapshot(&second));
+}
+
+#[test]
+fn test_manual_clock_stamps_entities_and_fast_forwards() {
+    let start = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+    let mut simulation = Simulation::new(GameState::with_seed(7).with_clock(GameClock::manual(start)));
+    let state = &mut simulation.state;
+    let player_id = add_player(state);
+    let city_id = state.next_id();
+    state.found_city(City::new(
+        city_id.clone(),
+        "Столица".to_string(),
+        player_id,
+        Terrain::Plain,
+        (0, 0),
+        &state.clock,
+    ));
+    assert_eq!(state.cities[&city_id].created_at, start);
+
+    let interval = simulation.tick_interval();
+    assert_eq!(simulation.advance(interval * 10).len(), 10);
+
+    let state = &simulation.state;
+    assert_eq!(state.clock.now(), start + chrono::Duration::from_std(interval * 10).unwrap());
+    assert_eq!(state.cities[&city_id].last_updated, state.clock.now());
+    assert_eq!(state.clock.date(), state.clock.date_of_turn(0) + chrono::Duration::days(10));
+}
+
+#[test]
+fn test_catch_up_runs_missed_turns() {
+    let start = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+    let mut simulation = Simulation::new(GameState::with_seed(7).with_clock(GameClock::manual(start)));
+    let last_seen = simulation.state.clock.now();
+
+    // Сервер был выключен, пока прошло 25 периодов тика
+    simulation.state.clock.advance_time(simulation.tick_interval() * 25);
+    let reports = simulation.catch_up(last_seen);
+
+    assert_eq!(reports.len(), 25);
+    assert_eq!(reports.last().unwrap().turn, 24);
+    assert_eq!(simulation.state.turn(), 25);
+}
+
+#[test]
+fn test_quests_expire_by_game_clock() {
+    let start = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+    let mut state = GameState::with_seed(7).with_clock(GameClock::manual(start));
+    let player_id = add_player(&mut state);
+    let quest = Quest::new("q1", "Квест", "Описание", &state.clock)
+        .with_expiry(start + chrono::Duration::hours(1));
+    assert!(!quest.is_expired(&state.clock));
+
+    state.clock.advance_time(Duration::from_secs(2 * 60 * 60));
+    assert!(quest.is_expired(&state.clock));
+
+    state.quests.get_mut(&player_id).unwrap().add_quest(quest);
+    state.tick();
+    assert_eq!(
+        state.quests[&player_id].get_quest("q1").unwrap().status,
+        QuestStatus::NotStarted
+    );
+}
+
+#[test]
+fn test_policies_follow_owner_technologies_and_turns() {
+    let (mut state, player_id, city_id) = state_with_city();
+
+    assert!(state.enact_policy(&city_id, PolicyType::FreeTrade).is_err());
+    assert_eq!(state.enact_policy("missing", PolicyType::FreeTrade), Err(PolicyError::CityNotFound));
+    let tree = state.tech_trees.get_mut(&player_id).unwrap();
+    tree.completed_technologies.insert(TechnologyType::Governance);
+    state.enact_policy(&city_id, PolicyType::FreeTrade).unwrap();
+
+    // Отменить политику можно только через несколько ходов
+    assert!(state.revoke_policy(&city_id, PolicyType::FreeTrade).is_err());
+    state.run_turns(POLICY_SWITCH_COOLDOWN);
+    state.revoke_policy(&city_id, PolicyType::FreeTrade).unwrap();
+    assert!(!state.cities[&city_id].policies.is_active(&PolicyType::FreeTrade));
+}
+
+#[test]
+fn test_build_spots_follow_city_grid() {
+    let (mut state, _, city_id) = state_with_city();
+
+    let mines = state.valid_build_spots(&city_id, &BuildingType::Mine);
+    let grid = &state.cities[&city_id].grid;
+    assert!(mines.iter().all(|position| grid.terrain(*position) == Some(&Terrain::Mountain)));
+    assert!(state.valid_build_spots("missing", &BuildingType::Mine).is_empty());
+
+    state.build_road(&city_id, (1, 0)).unwrap();
+    assert!(!state.valid_build_spots(&city_id, &BuildingType::Farm).contains(&(1, 0)));
+    assert!(state.build_road(&city_id, (1, 0)).is_err());
+}
+
+#[test]
+fn test_mines_extract_from_nearby_deposits() {
+    let (state, _, city_id) = state_with_city();
+    let mut world = WorldMap::new(20, 20);
+    world.add_deposit(ResourceDeposit {
+        remaining: 1,
+        ..ResourceDeposit::new((1, 1), ResourceType::Iron, 100)
+    });
+    world.add_deposit(ResourceDeposit::new((3, 0), ResourceType::Iron, 50));
+    // Слишком далеко от города
+    world.add_deposit(ResourceDeposit::new((10, 10), ResourceType::Iron, 150));
+    let mut state = state.with_world(world);
+
+    let city = state.cities.get_mut(&city_id).unwrap();
+    city.population.grow(20);
+    let mine = Building::new("mine".to_string(), "Шахта".to_string(), BuildingType::Mine, (1, 1));
+    city.buildings.insert(mine.id.clone(), mine);
+    assert_eq!(state.production_modifiers(&city_id).deposit_bonus(&ResourceType::Iron), 100);
+
+    // Богатое месторождение исчерпано первым, остальное добыто из бедного
+    let report = state.tick();
+    assert_eq!(report.depleted_deposits.len(), 1);
+    assert_eq!(report.depleted_deposits[0].0, city_id);
+    assert_eq!(report.depleted_deposits[0].1.position, (1, 1));
+    let extracted = state.cities[&city_id].deposit_extraction()[&ResourceType::Iron];
+    assert!(extracted > 1);
+    assert_eq!(state.world.deposit(3, 0).unwrap().remaining, 50 * 20 - (extracted - 1));
+    assert_eq!(state.world.deposit(10, 10).unwrap().remaining, 150 * 20);
+    assert_eq!(state.production_modifiers(&city_id).deposit_bonus(&ResourceType::Iron), 50);
+}
+
+#[test]
+fn test_found_city_on_world_map() {
+    let mut world = WorldMap::new(30, 30);
+    for y in 0..30 {
+        world.set_tile(4, y, TerrainTile::Forest);
+        world.set_tile(5, y, TerrainTile::Forest);
+        world.set_tile(6, y, TerrainTile::Forest);
+    }
+    world.set_tile(20, 20, TerrainTile::Water);
+    let mut state = GameState::with_seed(3).with_world(world);
+    let player_id = add_player(&mut state);
+
+    let city_id = state.found_city_at("Лесной".to_string(), &player_id, (5, 5)).unwrap();
+    let city = &state.cities[&city_id];
+    assert_eq!(city.terrain, Terrain::Forest);
+    assert_eq!(city.position, (5, 5));
+    assert!(city.resource_modifiers()[&ResourceType::Wood] > 1.0);
+    assert_eq!(state.world.owner(5, 5), Some(city_id.as_str()));
+
+    assert!(state.found_city_at("Морской".to_string(), &player_id, (20, 20)).is_err());
+    assert!(state.found_city_at("Соседний".to_string(), &player_id, (6, 6)).is_err());
+    assert!(state.found_city_at("Чужой".to_string(), "missing", (15, 15)).is_err());
+    // Неудачное основание не оставляет ни города, ни его рынка и событий
+    assert_eq!(state.cities.len(), 1);
+    assert_eq!(state.random_events.len(), 1);
+}
+
+#[test]
+fn test_borders_expand_and_strain_neighbours() {
+    let mut state = GameState::with_seed(5).with_world(WorldMap::new(30, 12));
+    let player_id = add_player(&mut state);
+    let north = state.found_city_at("Северный".to_string(), &player_id, (5, 5)).unwrap();
+    let south = state.found_city_at("Южный".to_string(), &player_id, (10, 5)).unwrap();
+    for (faction_id, city_id) in [("north", &north), ("south", &south)] {
+        let mut faction = Faction::new(faction_id.to_string(), faction_id.to_string(), FactionSpecialization::Balanced);
+        faction.cities.push(city_id.clone());
+        state.factions.add_faction(faction);
+    }
+    assert_eq!(state.faction_border("north", "south").len(), 10);
+
+    // Культура копится годами — сразу даем городу запас на одну клетку
+    state.cities.get_mut(&north).unwrap().border_progress = 100;
+    let report = state.tick();
+
+    assert_eq!(report.claimed_tiles, vec![(north.clone(), (2, 5))]);
+    assert_eq!(state.world.owner(2, 5), Some(north.as_str()));
+    assert_eq!(state.faction_territory("north").len(), 26);
+    // Запас ограничен стоимостью клетки и целиком ушел на нее
+    assert_eq!(state.cities[&north].border_progress, 0);
+
+    // Общая граница портит отношения соседей
+    assert_eq!(state.diplomacy.get_relation("north", "south").unwrap().reputation, -3);
+
+    let statement = state.cities[&north].ledger.income_statement(1);
+    assert!(statement["worked_tiles"][&ResourceType::Food] > 0);
+}
+
+#[test]
+fn test_border_progress_is_capped_when_city_cannot_grow() {
+    // Территория города занимает всю карту
+    let mut state = GameState::with_seed(5).with_world(WorldMap::new(5, 5));
+    let player_id = add_player(&mut state);
+    let city_id = state.found_city_at("Тесный".to_string(), &player_id, (2, 2)).unwrap();
+    assert_eq!(state.world.territory(&city_id).len(), 25);
+
+    state.cities.get_mut(&city_id).unwrap().border_progress = u32::MAX;
+    let report = state.tick();
+
+    assert!(report.claimed_tiles.is_empty());
+    assert_eq!(state.cities[&city_id].border_progress, territory::border_tile_cost(25));
+}